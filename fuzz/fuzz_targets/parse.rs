@@ -0,0 +1,42 @@
+// Any input must come back as Ok or Err; a panic is a bug.
+//
+// Usage (nightly): cargo fuzz run parse
+#![no_main]
+
+use joel_chirayath::tokenizer::{split_statements, StatementScanner, Token, Tokenizer};
+use joel_chirayath::{parse_input_with_options, parse_script, parse_sql, Dialect, Limits, ParserOptions, StatementIterator};
+use libfuzzer_sys::fuzz_target;
+
+const DIALECTS: [Option<Dialect>; 6] =
+    [None, Some(Dialect::Ansi), Some(Dialect::Postgres), Some(Dialect::MySql), Some(Dialect::Sqlite), Some(Dialect::MsSql)];
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let _ = parse_sql(input);
+    let _ = parse_script(input);
+
+    for statement in split_statements(input) {
+        let _ = parse_sql(statement);
+    }
+
+    // Each dialect lexes its own quotes and operators and accepts its own extensions
+    for dialect in DIALECTS {
+        for strict in [false, true] {
+            let options = ParserOptions { dialect, strict, ..ParserOptions::default() };
+            let _ = parse_input_with_options(input, &Limits::default(), &options);
+            for result in StatementIterator::new(input.as_bytes()).with_options(options) {
+                let _ = result;
+            }
+        }
+        if let Some(dialect) = dialect {
+            let _ = StatementScanner::new().with_dialect(dialect).split(input);
+        }
+    }
+
+    // Trivia mode takes different paths through comments and whitespace
+    let mut tokenizer = Tokenizer::new(input).with_trivia();
+    while tokenizer.next_token() != Token::Eof {}
+});
@@ -0,0 +1,428 @@
+// Passes that walk a parsed statement to answer questions about it, such as which tables and
+// columns it touches, without executing anything.
+use crate::ast::*;
+use crate::tokenizer::Span;
+
+mod validate;  // Checking statements against a schema supplied by the caller
+pub use validate::{validate, Schema, SchemaTable, SemanticError, TypeClass};
+
+/// The tables and columns a statement uses, each listed once in the order first seen. Names
+/// compare by Ident::normalized, and each keeps the span of its first occurrence.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableColumnRefs {
+    pub tables_read: Vec<Ident>,
+    pub tables_written: Vec<Ident>,
+    pub columns: Vec<ColumnRef>,
+    /// Tables whose every column is read, by SELECT * or SELECT t.*
+    pub all_columns: Vec<Ident>,
+}
+
+/// A column and the table it belongs to. Unqualified names belong to the statement's table,
+/// which for a SELECT is the one after FROM rather than any joined to it; qualified ones to the
+/// table named before the column, e.g. t in s.t.a, or to the table a SELECT gives that alias.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnRef {
+    pub table: Ident,
+    pub column: Ident,
+}
+
+/// Every table read or written and every column referenced by `statement`
+///
+/// ```
+/// use joel_chirayath::{parse_sql, references};
+///
+/// let refs = references(&parse_sql("SELECT id, COUNT(*) FROM users WHERE users.age > 18").unwrap());
+/// assert_eq!(refs.tables_read[0].value, "users");
+/// let columns: Vec<&str> = refs.columns.iter().map(|column| column.column.value.as_str()).collect();
+/// assert_eq!(columns, ["id", "age"]);
+/// ```
+pub fn references(statement: &Statement) -> TableColumnRefs {
+    references_resolving(statement, |from, _| &from.relation.name)
+}
+
+// references, with the table each unqualified column of a SELECT belongs to chosen by `unqualified`
+pub(crate) fn references_resolving<'a>(
+    statement: &'a Statement,
+    unqualified: impl Fn(&'a TableWithJoins, &Ident) -> &'a Ident,
+) -> TableColumnRefs {
+    let mut refs = TableColumnRefs::default();
+    match statement {
+        Statement::Select { columns, from, selection, order_by, .. } => {
+            let unqualified = |column: &Ident| unqualified(from, column);
+            for table in from.tables() {
+                add_table(&mut refs.tables_read, &table.name);
+            }
+            for column in columns {
+                match column {
+                    Expression::Wildcard(_) => {
+                        for table in from.tables() {
+                            add_table(&mut refs.all_columns, &table.name);
+                        }
+                    }
+                    Expression::QualifiedWildcard(parts, _) => {
+                        if let Some(qualifier) = parts.last() {
+                            add_table(&mut refs.all_columns, from.resolve(qualifier).unwrap_or(qualifier));
+                        }
+                    }
+                    _ => add_select_columns(&mut refs.columns, from, &unqualified, column),
+                }
+            }
+            for join in &from.joins {
+                match join.join_operator.constraint() {
+                    Some(JoinConstraint::On(condition)) => add_select_columns(&mut refs.columns, from, &unqualified, condition),
+                    // USING names a column of the table before it as well as of the joined one
+                    Some(JoinConstraint::Using(names)) => {
+                        add_named_columns(&mut refs.columns, &from.relation.name, names);
+                        add_named_columns(&mut refs.columns, &join.relation.name, names);
+                    }
+                    None => {}
+                }
+            }
+            if let Some(selection) = selection {
+                add_select_columns(&mut refs.columns, from, &unqualified, selection);
+            }
+            for item in order_by.iter().flatten() {
+                add_select_columns(&mut refs.columns, from, &unqualified, &item.expr);
+            }
+        }
+        Statement::CreateTable { table_name, columns, constraints, .. } => {
+            add_table(&mut refs.tables_written, table_name);
+            for column in columns {
+                for option in &column.options {
+                    match option {
+                        ColumnOption::Default(expr) | ColumnOption::Check(expr) | ColumnOption::Generated { expr, .. } => {
+                            add_columns(&mut refs.columns, table_name, expr)
+                        }
+                        ColumnOption::References(reference) => add_foreign_key(&mut refs, reference),
+                        _ => {}
+                    }
+                }
+            }
+            for constraint in constraints {
+                match constraint {
+                    TableConstraint::PrimaryKey { columns, .. } | TableConstraint::Unique { columns, .. } => {
+                        add_named_columns(&mut refs.columns, table_name, columns)
+                    }
+                    TableConstraint::Check { expr, .. } => add_columns(&mut refs.columns, table_name, expr),
+                    TableConstraint::ForeignKey { columns, references, .. } => {
+                        add_named_columns(&mut refs.columns, table_name, columns);
+                        add_foreign_key(&mut refs, references);
+                    }
+                }
+            }
+        }
+        Statement::Insert { table_name, columns, values, .. } => {
+            add_table(&mut refs.tables_written, table_name);
+            add_named_columns(&mut refs.columns, table_name, columns);
+            for value in values {
+                add_columns(&mut refs.columns, table_name, value);
+            }
+        }
+    }
+    refs
+}
+
+/// A bind parameter as written in a statement
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParameterRef {
+    /// The placeholder as written, e.g. ?, $2 or :name
+    pub text: String,
+    pub style: ParameterStyle,
+    pub clause: Clause,
+    pub span: Span,
+}
+
+/// How a placeholder identifies the value bound to it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParameterStyle {
+    /// ?, bound in order of appearance
+    Positional,
+    /// $n; numbers too large for a u64 are u64::MAX
+    Numbered(u64),
+    /// :name or @name, holding the name without its sigil
+    Named(String),
+}
+
+/// The part of a statement an expression appears in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Clause {
+    /// The columns of a SELECT
+    Projection,
+    /// The ON condition of a JOIN
+    Join,
+    Where,
+    OrderBy,
+    /// The VALUES of an INSERT
+    Values,
+    /// A column's DEFAULT in CREATE TABLE
+    Default,
+    /// A generated column's expression in CREATE TABLE
+    Generated,
+    /// A CHECK on a column or the whole table in CREATE TABLE
+    Check,
+}
+
+/// Every placeholder in `statement`, in the order written, so a driver can check how many
+/// values it was given before running the statement
+///
+/// ```
+/// use joel_chirayath::{parse_sql, parameters, Clause, ParameterStyle};
+///
+/// let found = parameters(&parse_sql("SELECT a FROM t WHERE b = $2 AND c > $1").unwrap());
+/// assert_eq!(found.len(), 2);
+/// assert_eq!((&found[0].style, found[0].clause), (&ParameterStyle::Numbered(2), Clause::Where));
+/// assert_eq!(found[1].span.start.column, 38);
+/// ```
+pub fn parameters(statement: &Statement) -> Vec<ParameterRef> {
+    let mut found = Vec::new();
+    for (clause, expr) in clause_expressions(statement) {
+        visit_expressions(expr, &mut |expr| {
+            if let Expression::Placeholder(text, span) = expr {
+                found.push(ParameterRef { text: text.clone(), style: parameter_style(text), clause, span: span.0 });
+            }
+        });
+    }
+    found
+}
+
+fn parameter_style(text: &str) -> ParameterStyle {
+    match text.split_at(1) {
+        ("?", _) => ParameterStyle::Positional,
+        ("$", number) => ParameterStyle::Numbered(number.parse().unwrap_or(u64::MAX)),
+        (_, name) => ParameterStyle::Named(name.to_string()),
+    }
+}
+
+// The top-level expressions of `statement` with the clause each is in, in the order written
+fn clause_expressions(statement: &Statement) -> Vec<(Clause, &Expression)> {
+    let mut found = Vec::new();
+    match statement {
+        Statement::Select { columns, from, selection, order_by, .. } => {
+            found.extend(columns.iter().map(|column| (Clause::Projection, column)));
+            for join in &from.joins {
+                if let Some(JoinConstraint::On(condition)) = join.join_operator.constraint() {
+                    found.push((Clause::Join, condition));
+                }
+            }
+            found.extend(selection.iter().map(|selection| (Clause::Where, selection)));
+            found.extend(order_by.iter().flatten().map(|item| (Clause::OrderBy, &item.expr)));
+        }
+        Statement::CreateTable { columns, constraints, .. } => {
+            for option in columns.iter().flat_map(|column| &column.options) {
+                match option {
+                    ColumnOption::Default(expr) => found.push((Clause::Default, expr)),
+                    ColumnOption::Generated { expr, .. } => found.push((Clause::Generated, expr)),
+                    ColumnOption::Check(expr) => found.push((Clause::Check, expr)),
+                    _ => {}
+                }
+            }
+            for constraint in constraints {
+                if let TableConstraint::Check { expr, .. } = constraint {
+                    found.push((Clause::Check, expr));
+                }
+            }
+        }
+        Statement::Insert { values, .. } => found.extend(values.iter().map(|value| (Clause::Values, value))),
+    }
+    found
+}
+
+// A foreign key reads the table it points at whenever a row is written
+fn add_foreign_key(refs: &mut TableColumnRefs, reference: &ForeignKeyReference) {
+    add_table(&mut refs.tables_read, &reference.table);
+    add_named_columns(&mut refs.columns, &reference.table, &reference.columns);
+}
+
+fn add_table(tables: &mut Vec<Ident>, table: &Ident) {
+    if !tables.iter().any(|seen| seen.normalized() == table.normalized()) {
+        tables.push(table.clone());
+    }
+}
+
+fn add_named_columns(found: &mut Vec<ColumnRef>, table: &Ident, columns: &[Ident]) {
+    for column in columns {
+        add_column(found, table, column);
+    }
+}
+
+// Every column named anywhere in `expr`, unqualified ones belonging to `table`
+fn add_columns(found: &mut Vec<ColumnRef>, table: &Ident, expr: &Expression) {
+    visit_expressions(expr, &mut |expr| match expr {
+        Expression::Identifier(column) => add_column(found, table, column),
+        Expression::CompoundIdentifier(parts, _) => {
+            if let [.., qualifier, column] = parts.as_slice() {
+                add_column(found, qualifier, column);
+            }
+        }
+        _ => {}
+    });
+}
+
+// Every column named anywhere in `expr` in a SELECT: unqualified ones belong to the table
+// `unqualified` gives, and qualifiers are aliases or names of the tables in `from`
+fn add_select_columns<'a>(
+    found: &mut Vec<ColumnRef>,
+    from: &'a TableWithJoins,
+    unqualified: &impl Fn(&Ident) -> &'a Ident,
+    expr: &Expression,
+) {
+    visit_expressions(expr, &mut |expr| match expr {
+        Expression::Identifier(column) => add_column(found, unqualified(column), column),
+        Expression::CompoundIdentifier(parts, _) => {
+            if let [.., qualifier, column] = parts.as_slice() {
+                add_column(found, from.resolve(qualifier).unwrap_or(qualifier), column);
+            }
+        }
+        _ => {}
+    });
+}
+
+fn add_column(found: &mut Vec<ColumnRef>, table: &Ident, column: &Ident) {
+    let seen = |other: &ColumnRef| {
+        other.table.normalized() == table.normalized() && other.column.normalized() == column.normalized()
+    };
+    if !found.iter().any(seen) {
+        found.push(ColumnRef { table: table.clone(), column: column.clone() });
+    }
+}
+
+/// Calls `visit` on `expr` and then on every expression inside it, parents before children
+/// and left to right as written.
+pub(crate) fn visit_expressions<'a>(expr: &'a Expression, visit: &mut impl FnMut(&'a Expression)) {
+    visit(expr);
+    for child in children(expr) {
+        visit_expressions(child, visit);
+    }
+}
+
+// The expressions directly inside `expr`, in the order they are written
+fn children(expr: &Expression) -> Vec<&Expression> {
+    match expr {
+        Expression::Identifier(_)
+        | Expression::CompoundIdentifier(_, _)
+        | Expression::Literal(..)
+        | Expression::Placeholder(..)
+        | Expression::Wildcard(_)
+        | Expression::Error
+        | Expression::QualifiedWildcard(..)
+        | Expression::Interval { .. }
+        | Expression::TypedString { .. } => Vec::new(),
+        Expression::UnaryOperation { operand, .. } => vec![operand],
+        Expression::BinaryOperation { left_operand, right_operand, .. } => vec![left_operand, right_operand],
+        Expression::Grouped(inner, _) => vec![inner],
+        Expression::Function { args, order_by, filter, .. } => {
+            let mut children: Vec<&Expression> = args.iter().map(|arg| &arg.value).collect();
+            children.extend(order_by.iter().map(|item| &item.expr));
+            children.extend(filter.as_deref());
+            children
+        }
+        Expression::Position { substring, string, .. } => vec![substring, string],
+        Expression::Trim { characters, expr, .. } => characters.as_deref().into_iter().chain([&**expr]).collect(),
+        Expression::Array(items, _) | Expression::Tuple(items, _) => items.iter().collect(),
+        Expression::Subscript { expr, subscript, .. } => {
+            let mut children = vec![&**expr];
+            match &**subscript {
+                Subscript::Index(index) => children.push(index),
+                Subscript::Slice { lower, upper } => children.extend(lower.iter().chain(upper)),
+            }
+            children
+        }
+        Expression::InList { expr, list, .. } => [&**expr].into_iter().chain(list).collect(),
+        Expression::Collate { expr, .. } | Expression::Is { expr, .. } | Expression::Cast { expr, .. } => vec![expr],
+        Expression::AtTimeZone { timestamp, time_zone, .. } => vec![timestamp, time_zone],
+        Expression::RegexMatch { expr, pattern, .. } | Expression::Like { expr, pattern, .. } => vec![expr, pattern],
+        Expression::Between { expr, low, high, .. } => vec![expr, low, high],
+        Expression::Case { operand, branches, else_result, .. } => {
+            let mut children: Vec<&Expression> = operand.as_deref().into_iter().collect();
+            for branch in branches {
+                children.extend([&branch.condition, &branch.result]);
+            }
+            children.extend(else_result.as_deref());
+            children
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sql;
+
+    fn names(idents: &[Ident]) -> Vec<&str> {
+        idents.iter().map(|ident| ident.value.as_str()).collect()
+    }
+
+    fn columns(refs: &TableColumnRefs) -> Vec<String> {
+        refs.columns.iter().map(|column| format!("{}.{}", column.table.value, column.column.value)).collect()
+    }
+
+    #[test]
+    fn select_reads_its_table_and_the_columns_it_names() {
+        let refs = references(&parse_sql(
+            "SELECT t.*, a, UPPER(t.b) FROM t WHERE A > 1 AND CASE WHEN c IN (d, s.u.e) THEN f[g] END ORDER BY COUNT(*) FILTER (WHERE h)",
+        ).unwrap());
+        assert_eq!(names(&refs.tables_read), ["t"]);
+        assert!(refs.tables_written.is_empty());
+        assert_eq!(names(&refs.all_columns), ["t"]);
+        assert_eq!(columns(&refs), ["t.a", "t.b", "t.c", "t.d", "u.e", "t.f", "t.g", "t.h"]);
+
+        let refs = references(&parse_sql("SELECT * FROM T WHERE A = a").unwrap());
+        assert_eq!(names(&refs.all_columns), ["T"]);
+        assert_eq!(columns(&refs), ["T.A"]);
+    }
+
+    #[test]
+    fn joined_tables_are_read_and_aliases_resolve_to_them() {
+        let refs = references(&parse_sql(
+            "SELECT u.*, o.total, name FROM users u JOIN orders AS o ON u.id = o.user_id LEFT JOIN items USING (sku) WHERE x.y = ?",
+        ).unwrap());
+        assert_eq!(names(&refs.tables_read), ["users", "orders", "items"]);
+        assert_eq!(names(&refs.all_columns), ["users"]);
+        assert_eq!(columns(&refs), ["orders.total", "users.name", "users.id", "orders.user_id", "users.sku", "items.sku", "x.y"]);
+
+        let refs = references(&parse_sql("SELECT * FROM a JOIN b ON TRUE").unwrap());
+        assert_eq!(names(&refs.all_columns), ["a", "b"]);
+        let found = parameters(&parse_sql("SELECT a FROM t JOIN u ON u.k = ? WHERE b = ?").unwrap());
+        assert_eq!(found.iter().map(|parameter| parameter.clause).collect::<Vec<_>>(), [Clause::Join, Clause::Where]);
+    }
+
+    #[test]
+    fn parameters_are_listed_with_their_style_and_clause() {
+        let sql = "SELECT a, ? FROM t WHERE b IN (:low, @high) AND c = $10 ORDER BY f(?)";
+        let found: Vec<(ParameterStyle, Clause, &str)> = parameters(&parse_sql(sql).unwrap())
+            .into_iter()
+            .map(|parameter| (parameter.style, parameter.clause, &sql[parameter.span.start.offset..parameter.span.end.offset]))
+            .collect();
+        assert_eq!(found, [
+            (ParameterStyle::Positional, Clause::Projection, "?"),
+            (ParameterStyle::Named("low".to_string()), Clause::Where, ":low"),
+            (ParameterStyle::Named("high".to_string()), Clause::Where, "@high"),
+            (ParameterStyle::Numbered(10), Clause::Where, "$10"),
+            (ParameterStyle::Positional, Clause::OrderBy, "?"),
+        ]);
+
+        let found = parameters(&parse_sql("INSERT INTO t (a, b) VALUES (?, -?)").unwrap());
+        assert_eq!(found.iter().map(|parameter| parameter.clause).collect::<Vec<_>>(), [Clause::Values, Clause::Values]);
+        assert!(parameters(&parse_sql("SELECT '?' FROM t").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn insert_and_create_table_write_their_table() {
+        let refs = references(&parse_sql("INSERT INTO t (a, b) VALUES (1, 'x')").unwrap());
+        assert_eq!(names(&refs.tables_written), ["t"]);
+        assert!(refs.tables_read.is_empty());
+        assert_eq!(columns(&refs), ["t.a", "t.b"]);
+
+        let sql = "CREATE TABLE t (a INT REFERENCES u (id), b INT CHECK (b > a), c INT AS (a + b), \
+                   PRIMARY KEY (a), FOREIGN KEY (b) REFERENCES v (id))";
+        let refs = references(&parse_sql(sql).unwrap());
+        assert_eq!(names(&refs.tables_written), ["t"]);
+        assert_eq!(names(&refs.tables_read), ["u", "v"]);
+        assert_eq!(columns(&refs), ["u.id", "t.b", "t.a", "v.id"]);
+    }
+}
@@ -0,0 +1,420 @@
+// Checks a statement against table definitions supplied by the caller: that the tables and
+// columns it names exist, that an INSERT has a value for each column, and that comparisons
+// compare like with like.
+use super::{clause_expressions, references_resolving, visit_expressions, ColumnRef};
+use crate::ast::*;
+use crate::tokenizer::Span;
+
+/// The tables a statement is validated against, with their columns as CREATE TABLE gives them
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schema {
+    pub tables: Vec<SchemaTable>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchemaTable {
+    pub name: Ident,
+    pub columns: Vec<ColumnDef>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    /// Adds a table, replacing any with the same name
+    pub fn with_table(mut self, name: Ident, columns: Vec<ColumnDef>) -> Self {
+        self.tables.retain(|table| table.name.normalized() != name.normalized());
+        self.tables.push(SchemaTable { name, columns });
+        self
+    }
+
+    /// The tables defined by the CREATE TABLE statements among `statements`, later ones
+    /// replacing earlier ones of the same name
+    pub fn from_statements<'a>(statements: impl IntoIterator<Item = &'a Statement>) -> Self {
+        statements.into_iter().fold(Schema::new(), |schema, statement| match statement {
+            Statement::CreateTable { table_name, columns, .. } => schema.with_table(table_name.clone(), columns.clone()),
+            _ => schema,
+        })
+    }
+
+    /// The columns of the table called `name`, compared by Ident::normalized
+    pub fn table(&self, name: &Ident) -> Option<&[ColumnDef]> {
+        let table = self.tables.iter().find(|table| table.name.normalized() == name.normalized())?;
+        Some(&table.columns)
+    }
+}
+
+/// Something a statement refers to that the schema says cannot work
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SemanticError {
+    UnknownTable(Ident),
+    UnknownColumn { table: Ident, column: Ident },
+    /// An INSERT with a different number of values than columns, spanning the values
+    InsertArity { columns: usize, values: usize, span: Span },
+    /// A comparison between values of different kinds, spanning the comparison
+    TypeMismatch { left: TypeClass, right: TypeClass, span: Span },
+}
+
+impl SemanticError {
+    // A stable identifier for the kind of error, like ParseError::code
+    pub fn code(&self) -> &'static str {
+        match self {
+            SemanticError::UnknownTable(_) => "S0001",
+            SemanticError::UnknownColumn { .. } => "S0002",
+            SemanticError::InsertArity { .. } => "S0003",
+            SemanticError::TypeMismatch { .. } => "S0004",
+        }
+    }
+
+    // Where in the source the error is
+    pub fn span(&self) -> Span {
+        match self {
+            SemanticError::UnknownTable(table) => table.span.0,
+            SemanticError::UnknownColumn { column, .. } => column.span.0,
+            SemanticError::InsertArity { span, .. } | SemanticError::TypeMismatch { span, .. } => *span,
+        }
+    }
+
+    // The error as one JSON object with its code, message and span, like ParseError::to_json
+    pub fn to_json(&self) -> String {
+        crate::to_json(self.code(), &self.to_string(), self.span())
+    }
+}
+
+impl std::fmt::Display for SemanticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SemanticError::UnknownTable(table) => write!(f, "Unknown table {}", table),
+            SemanticError::UnknownColumn { table, column } => write!(f, "Unknown column {} in table {}", column, table),
+            SemanticError::InsertArity { columns, values, .. } => {
+                write!(f, "INSERT has {} columns but {} values", columns, values)
+            }
+            SemanticError::TypeMismatch { left, right, .. } => write!(f, "Cannot compare {} with {}", left, right),
+        }
+    }
+}
+
+impl std::error::Error for SemanticError {}
+
+/// The broad kinds of value that comparisons are checked between. Types within a kind, such as
+/// INT and DECIMAL, compare freely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypeClass {
+    Number,
+    Text,
+    Boolean,
+    /// Dates, times and timestamps
+    Temporal,
+    Binary,
+}
+
+impl TypeClass {
+    /// The kind of `data_type`, or None for types such as JSON and arrays that are not checked
+    pub fn of(data_type: &DataType) -> Option<TypeClass> {
+        match data_type {
+            DataType::Integer { .. }
+            | DataType::Serial(_)
+            | DataType::Float(_)
+            | DataType::Real
+            | DataType::Double
+            | DataType::Decimal(_) => Some(TypeClass::Number),
+            DataType::Varchar(_) | DataType::Char(_) | DataType::Text | DataType::Enum(_) | DataType::Uuid => {
+                Some(TypeClass::Text)
+            }
+            DataType::Boolean => Some(TypeClass::Boolean),
+            DataType::Date | DataType::Time { .. } | DataType::Timestamp { .. } => Some(TypeClass::Temporal),
+            DataType::Binary(_) | DataType::Varbinary(_) | DataType::Blob | DataType::Bytea => Some(TypeClass::Binary),
+            DataType::Json
+            | DataType::Jsonb
+            | DataType::Array(..)
+            | DataType::Struct(_)
+            | DataType::Row(_)
+            | DataType::Custom(..) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TypeClass::Number => "a number",
+            TypeClass::Text => "text",
+            TypeClass::Boolean => "a boolean",
+            TypeClass::Temporal => "a date or time",
+            TypeClass::Binary => "binary data",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Every problem with running `statement` against `schema`, in the order they occur in the
+/// source; empty if there are none. A CREATE TABLE is checked against its own columns and
+/// the tables its foreign keys reference.
+///
+/// ```
+/// use joel_chirayath::{parse_sql, validate, Schema};
+///
+/// let schema = Schema::from_statements(&[parse_sql("CREATE TABLE users (id INT, name TEXT)").unwrap()]);
+/// assert!(validate(&parse_sql("SELECT name FROM users WHERE id = 1").unwrap(), &schema).is_empty());
+/// let errors = validate(&parse_sql("SELECT email FROM users WHERE name = 1").unwrap(), &schema);
+/// let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+/// assert_eq!(messages, ["Unknown column email in table users", "Cannot compare text with a number"]);
+/// ```
+pub fn validate(statement: &Statement, schema: &Schema) -> Vec<SemanticError> {
+    // A CREATE TABLE's own columns are checked against the table it defines
+    let extended;
+    let schema = match statement {
+        Statement::CreateTable { table_name, columns, .. } => {
+            extended = schema.clone().with_table(table_name.clone(), columns.clone());
+            &extended
+        }
+        _ => schema,
+    };
+    let mut errors = Vec::new();
+
+    // Each unknown table is reported once, where it is first named
+    // An unqualified column of a SELECT belongs to the first table in FROM that has it
+    let refs = references_resolving(statement, |from, column| {
+        from.tables().map(|table| &table.name).find(|table| has_column(schema, table, column)).unwrap_or(&from.relation.name)
+    });
+    let mut unknown_tables: Vec<&Ident> = Vec::new();
+    let named_tables = refs.tables_read.iter().chain(&refs.tables_written).chain(&refs.all_columns);
+    for table in named_tables.chain(refs.columns.iter().map(|column| &column.table)) {
+        let reported = unknown_tables.iter().any(|unknown| unknown.normalized() == table.normalized());
+        if schema.table(table).is_none() && !reported {
+            errors.push(SemanticError::UnknownTable(table.clone()));
+            unknown_tables.push(table);
+        }
+    }
+    for ColumnRef { table, column } in &refs.columns {
+        if schema.table(table).is_some() && !has_column(schema, table, column) {
+            errors.push(SemanticError::UnknownColumn { table: table.clone(), column: column.clone() });
+        }
+    }
+
+    if let Statement::Insert { table_name, columns, values, .. } = statement {
+        let expected = match (columns.len(), schema.table(table_name)) {
+            (0, Some(definitions)) => Some(definitions.len()),
+            (0, None) => None,
+            (named, _) => Some(named),
+        };
+        if let (Some(expected), Some(first), Some(last)) = (expected, values.first(), values.last()) {
+            if expected != values.len() {
+                let span = Span { start: first.span().start, end: last.span().end };
+                errors.push(SemanticError::InsertArity { columns: expected, values: values.len(), span });
+            }
+        }
+    }
+
+    let (tables, from) = match statement {
+        Statement::Select { from, .. } => (from.tables().map(|table| &table.name).collect(), Some(&**from)),
+        Statement::CreateTable { table_name, .. } | Statement::Insert { table_name, .. } => (vec![table_name], None),
+    };
+    let types = Types { schema, tables, from };
+    for expr in clause_expressions(statement).into_iter().map(|(_, expr)| expr) {
+        visit_expressions(expr, &mut |expr| {
+            if let Expression::BinaryOperation { left_operand, operator, right_operand, .. } = expr {
+                if is_comparison(operator) {
+                    if let (Some(left), Some(right)) = (types.of(left_operand), types.of(right_operand)) {
+                        if !left.compares_with(right) {
+                            let (left, right) = (left.class(), right.class());
+                            errors.push(SemanticError::TypeMismatch { left, right, span: expr.span() });
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    errors.sort_by_key(|error| error.span().start.offset);
+    errors
+}
+
+fn is_comparison(operator: &BinaryOperator) -> bool {
+    matches!(
+        operator,
+        BinaryOperator::Equals
+            | BinaryOperator::NotEquals
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterThanOrEqual
+            | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEqual
+    )
+}
+
+// The type of an operand as far as comparisons are concerned
+#[derive(Clone, Copy)]
+enum ValueType {
+    Class(TypeClass),
+    // A quoted string, which also compares with dates and times: d > '2024-01-01'
+    StringLiteral,
+}
+
+impl ValueType {
+    fn class(self) -> TypeClass {
+        match self {
+            ValueType::Class(class) => class,
+            ValueType::StringLiteral => TypeClass::Text,
+        }
+    }
+
+    fn compares_with(self, other: ValueType) -> bool {
+        match (self, other) {
+            (ValueType::StringLiteral, other) | (other, ValueType::StringLiteral) => {
+                matches!(other.class(), TypeClass::Text | TypeClass::Temporal)
+            }
+            (ValueType::Class(left), ValueType::Class(right)) => left == right,
+        }
+    }
+}
+
+// Works out operand types, looking unqualified columns up in the first of `tables` that has them
+// and qualified ones through the aliases in `from`
+struct Types<'a> {
+    schema: &'a Schema,
+    tables: Vec<&'a Ident>,
+    from: Option<&'a TableWithJoins>,
+}
+
+impl Types<'_> {
+    // None where the type is unknown, such as a placeholder, NULL or an unknown column, so
+    // nothing is reported about it
+    fn of(&self, expr: &Expression) -> Option<ValueType> {
+        let class = match expr {
+            Expression::Literal(Literal::Integer(_) | Literal::Float(_) | Literal::Decimal(_), _) => TypeClass::Number,
+            Expression::Literal(Literal::String(_) | Literal::NationalString(_), _) => return Some(ValueType::StringLiteral),
+            Expression::Literal(Literal::Boolean(_), _) => TypeClass::Boolean,
+            Expression::Identifier(column) => {
+                let table = self.tables.iter().find(|table| has_column(self.schema, table, column))?;
+                self.column(table, column)?
+            }
+            Expression::CompoundIdentifier(parts, _) => match parts.as_slice() {
+                [.., table, column] => self.column(self.from.and_then(|from| from.resolve(table)).unwrap_or(table), column)?,
+                _ => return None,
+            },
+            Expression::Grouped(inner, _) => return self.of(inner),
+            Expression::Cast { data_type, .. } | Expression::TypedString { data_type, .. } => TypeClass::of(data_type)?,
+            Expression::UnaryOperation { operator: UnaryOperator::Not, .. }
+            | Expression::Like { .. }
+            | Expression::RegexMatch { .. }
+            | Expression::Between { .. }
+            | Expression::InList { .. }
+            | Expression::Is { .. } => TypeClass::Boolean,
+            Expression::UnaryOperation { operator: UnaryOperator::Negate | UnaryOperator::Factorial, .. } => TypeClass::Number,
+            Expression::BinaryOperation { operator, .. } => match operator {
+                BinaryOperator::Add
+                | BinaryOperator::Subtract
+                | BinaryOperator::Multiply
+                | BinaryOperator::Divide
+                | BinaryOperator::Exponent => TypeClass::Number,
+                BinaryOperator::StringConcat => TypeClass::Text,
+                operator if is_comparison(operator) => TypeClass::Boolean,
+                BinaryOperator::And | BinaryOperator::Or => TypeClass::Boolean,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(ValueType::Class(class))
+    }
+
+    fn column(&self, table: &Ident, column: &Ident) -> Option<TypeClass> {
+        TypeClass::of(&column_definition(self.schema, table, column)?.data_type)
+    }
+}
+
+fn column_definition<'a>(schema: &'a Schema, table: &Ident, column: &Ident) -> Option<&'a ColumnDef> {
+    schema.table(table)?.iter().find(|definition| definition.name.normalized() == column.normalized())
+}
+
+fn has_column(schema: &Schema, table: &Ident, column: &Ident) -> bool {
+    column_definition(schema, table, column).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{lit, Insert};
+    use crate::{parse_script, parse_sql};
+
+    fn codes(sql: &str) -> Vec<(&'static str, String)> {
+        let schema = Schema::from_statements(&parse_script(
+            "CREATE TABLE users (id INT, name VARCHAR(50), born DATE, active BOOLEAN);
+             CREATE TABLE orders (id INT, user_id INT REFERENCES users (id), total DECIMAL(10, 2))",
+        ).unwrap());
+        validate(&parse_sql(sql).unwrap(), &schema)
+            .iter()
+            .map(|error| (error.code(), sql[error.span().start.offset..error.span().end.offset].to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn names_must_be_in_the_schema() {
+        assert!(codes("SELECT id, users.name, * FROM users WHERE active ORDER BY born").is_empty());
+        assert_eq!(codes("SELECT id FROM people WHERE people.x = 1 AND y = 2"), [("S0001", "people".to_string())]);
+        assert_eq!(codes("SELECT nme, orders.totl FROM users WHERE ID > 0"), [
+            ("S0002", "nme".to_string()),
+            ("S0002", "totl".to_string()),
+        ]);
+        assert_eq!(codes("SELECT a FROM users WHERE s.other.b = 1"), [
+            ("S0002", "a".to_string()),
+            ("S0001", "other".to_string()),
+        ]);
+        assert_eq!(codes("CREATE TABLE t (a INT REFERENCES users (uid) CHECK (d > 0), b INT, CHECK (c > b))"), [
+            ("S0002", "uid".to_string()),
+            ("S0002", "d".to_string()),
+            ("S0002", "c".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn joined_tables_are_checked_through_their_aliases() {
+        assert!(codes("SELECT u.name, o.total, total FROM users u JOIN orders o ON u.id = o.user_id WHERE name <> 'x'").is_empty());
+        assert_eq!(codes("SELECT u.total, o.name, nothing FROM users u JOIN orders o ON u.id = o.user_id"), [
+            ("S0002", "total".to_string()),
+            ("S0002", "name".to_string()),
+            ("S0002", "nothing".to_string()),
+        ]);
+        assert_eq!(codes("SELECT u.id FROM users u JOIN orders o ON u.name = o.total AND total = u.id"), [
+            ("S0004", "u.name = o.total".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn inserts_need_a_value_per_column() {
+        assert!(codes("INSERT INTO users (id, name) VALUES (1, 'a')").is_empty());
+        assert_eq!(codes("INSERT INTO users (id, name) VALUES (1, 'a', 2)"), [("S0003", "1, 'a', 2".to_string())]);
+
+        // Built without a column list, an INSERT gives a value for every column of the table
+        let schema = Schema::new().with_table(Ident::new("t"), vec![ColumnDef {
+            name: Ident::new("a"),
+            data_type: DataType::Text,
+            options: Vec::new(),
+            span: NodeSpan::default(),
+        }]);
+        let insert = |values: Vec<Expression>| Statement::from(Insert::into("t").values(values));
+        assert!(validate(&insert(vec![lit("x")]), &schema).is_empty());
+        let errors = validate(&insert(vec![lit("x"), lit("y")]), &schema);
+        assert_eq!(errors[0].to_string(), "INSERT has 1 columns but 2 values");
+    }
+
+    #[test]
+    fn comparisons_must_compare_like_with_like() {
+        assert!(codes("SELECT id FROM users WHERE born > '2000-01-01' AND name <> 'x' AND id = 1.5 AND active = TRUE").is_empty());
+        assert!(codes("SELECT id FROM users WHERE name = ? AND id = NULL AND CAST(name AS INT) = id").is_empty());
+        assert_eq!(codes("SELECT id FROM users WHERE id = 'one' OR (name) < 2 OR active = born"), [
+            ("S0004", "id = 'one'".to_string()),
+            ("S0004", "(name) < 2".to_string()),
+            ("S0004", "active = born".to_string()),
+        ]);
+        let error = &validate(&parse_sql("SELECT id FROM users WHERE -id = name || 'x'").unwrap(), &Schema::from_statements(
+            &[parse_sql("CREATE TABLE users (id INT, name TEXT)").unwrap()],
+        ))[0];
+        assert_eq!(error.to_string(), "Cannot compare a number with text");
+    }
+}
@@ -1,28 +1,327 @@
 // SQL AST components for Rust SQL parser
 // Author: Joel Chirayath
 
+use crate::tokenizer::Span;
+
+//Display for every node prints it back as SQL
+mod display;
+
+//Where a node was read from. Nodes built in code have Span::default(). Spans never take part in
+//comparisons, so a parsed tree equals the same tree built by hand.
+//Statements, expressions, names and the structs inside them carry one. The enums that only pick
+//between forms (DataType, ColumnOption, Subscript, the operators) do not: they are located by the
+//span of the node holding them, such as the ColumnDef, the Cast or the BinaryOperation.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeSpan(pub Span);
+
+impl PartialEq for NodeSpan {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl From<Span> for NodeSpan {
+    fn from(span: Span) -> Self {
+        NodeSpan(span)
+    }
+}
+
 //This defines all the possible kinds of values or conditions in SQL expressions like in WHERE, ORDER BY, or math formulas.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expression {
-    Identifier(String),
-    Number(u64),
-    String(String),
+    Identifier(Ident),
+    //A dotted name such as users.id or schema.users.id
+    CompoundIdentifier(Vec<Ident>, NodeSpan),
+    Literal(Literal, NodeSpan),
+    //Bind parameter as written: ?, $1, :name or @name
+    Placeholder(String, NodeSpan),
     UnaryOperation {
         operator: UnaryOperator,
         operand: Box<Expression>,
+        span: NodeSpan,
     },
     BinaryOperation {
         left_operand: Box<Expression>,
         operator: BinaryOperator,
         right_operand: Box<Expression>,
+        span: NodeSpan,
+    },
+    Grouped(Box<Expression>, NodeSpan),
+    Function {
+        name: Ident,
+        args: Vec<FunctionArg>,
+        //Ordered aggregates: STRING_AGG(name, ',' ORDER BY name)
+        order_by: Vec<OrderByExpr>,
+        //Aggregate filter: COUNT(*) FILTER (WHERE condition)
+        filter: Option<Box<Expression>>,
+        span: NodeSpan,
+    },
+    //The * in COUNT(*) or SELECT *
+    Wildcard(NodeSpan),
+    //Placeholder in a partial statement for an expression that failed to parse
+    Error,
+    //t.* in a projection
+    QualifiedWildcard(Vec<Ident>, NodeSpan),
+    //POSITION(substring IN string) is written like a function call but uses IN instead of a comma.
+    Position {
+        substring: Box<Expression>,
+        string: Box<Expression>,
+        span: NodeSpan,
+    },
+    //TRIM([LEADING | TRAILING | BOTH] [characters] FROM string) or plain TRIM(string).
+    Trim {
+        side: Option<TrimSide>,
+        characters: Option<Box<Expression>>,
+        expr: Box<Expression>,
+        span: NodeSpan,
+    },
+    //INTERVAL '1 day', INTERVAL '2' HOUR, INTERVAL '1:30' HOUR TO MINUTE
+    Interval {
+        value: String,
+        unit: Option<IntervalUnit>,
+        precision: Option<u64>,
+        last_unit: Option<IntervalUnit>,
+        span: NodeSpan,
+    },
+    //A string literal prefixed with its type, e.g. DATE '2024-01-01'.
+    TypedString {
+        data_type: DataType,
+        value: String,
+        span: NodeSpan,
+    },
+    //ARRAY[1, 2, 3]
+    Array(Vec<Expression>, NodeSpan),
+    //tags[1] or arr[1:3]
+    Subscript {
+        expr: Box<Expression>,
+        subscript: Box<Subscript>,
+        span: NodeSpan,
+    },
+    //A parenthesized list of two or more expressions, e.g. (a, b) = (1, 2)
+    Tuple(Vec<Expression>, NodeSpan),
+    //expr [NOT] IN (value, ...)
+    InList {
+        expr: Box<Expression>,
+        list: Vec<Expression>,
+        negated: bool,
+        span: NodeSpan,
+    },
+    //expr COLLATE collation; a quoted collation name keeps its quotes, ' for one written as a string
+    Collate {
+        expr: Box<Expression>,
+        collation: Ident,
+        span: NodeSpan,
+    },
+    //timestamp AT TIME ZONE zone
+    AtTimeZone {
+        timestamp: Box<Expression>,
+        time_zone: Box<Expression>,
+        span: NodeSpan,
+    },
+    //expr [NOT] SIMILAR TO pattern, expr ~ pattern, expr REGEXP pattern, expr RLIKE pattern, ...
+    RegexMatch {
+        expr: Box<Expression>,
+        pattern: Box<Expression>,
+        syntax: RegexSyntax,
+        negated: bool,
+        case_insensitive: bool,
+        span: NodeSpan,
+    },
+    //expr [NOT] LIKE pattern, expr [NOT] ILIKE pattern
+    Like {
+        expr: Box<Expression>,
+        pattern: Box<Expression>,
+        negated: bool,
+        case_insensitive: bool,
+        span: NodeSpan,
+    },
+    //expr [NOT] BETWEEN low AND high
+    Between {
+        expr: Box<Expression>,
+        low: Box<Expression>,
+        high: Box<Expression>,
+        negated: bool,
+        span: NodeSpan,
+    },
+    //expr IS [NOT] NULL / TRUE / FALSE / UNKNOWN
+    Is {
+        expr: Box<Expression>,
+        test: IsTest,
+        negated: bool,
+        span: NodeSpan,
+    },
+    //expr::type or CAST(expr AS type)
+    Cast {
+        expr: Box<Expression>,
+        data_type: DataType,
+        span: NodeSpan,
+    },
+    //CASE [operand] WHEN condition THEN result ... [ELSE result] END
+    Case {
+        operand: Option<Box<Expression>>,
+        branches: Vec<CaseWhen>,
+        else_result: Option<Box<Expression>>,
+        span: NodeSpan,
+    },
+}
+
+impl Expression {
+    //Where the expression was read from, from its first token to its last.
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Identifier(ident) => ident.span.0,
+            Expression::Error => Span::default(),
+            Expression::CompoundIdentifier(_, span)
+            | Expression::Literal(_, span)
+            | Expression::Placeholder(_, span)
+            | Expression::Grouped(_, span)
+            | Expression::Wildcard(span)
+            | Expression::QualifiedWildcard(_, span)
+            | Expression::Array(_, span)
+            | Expression::Tuple(_, span)
+            | Expression::UnaryOperation { span, .. }
+            | Expression::BinaryOperation { span, .. }
+            | Expression::Function { span, .. }
+            | Expression::Position { span, .. }
+            | Expression::Trim { span, .. }
+            | Expression::Interval { span, .. }
+            | Expression::TypedString { span, .. }
+            | Expression::Subscript { span, .. }
+            | Expression::InList { span, .. }
+            | Expression::Collate { span, .. }
+            | Expression::AtTimeZone { span, .. }
+            | Expression::RegexMatch { span, .. }
+            | Expression::Like { span, .. }
+            | Expression::Between { span, .. }
+            | Expression::Is { span, .. }
+            | Expression::Cast { span, .. }
+            | Expression::Case { span, .. } => span.0,
+        }
+    }
+}
+
+//One WHEN ... THEN ... arm of a CASE expression.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaseWhen {
+    pub condition: Expression,
+    pub result: Expression,
+    //From WHEN to the end of the result
+    pub span: NodeSpan,
+}
+
+//What an IS expression checks for.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IsTest {
+    Null,
+    True,
+    False,
+    Unknown,
+}
+
+//The pattern language of a RegexMatch. ~, REGEXP and RLIKE are all POSIX-style.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RegexSyntax {
+    SimilarTo,
+    Posix,
+}
+
+//An ORDER BY item; `asc` is None when neither ASC nor DESC was written.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderByExpr {
+    pub expr: Expression,
+    pub asc: Option<bool>,
+    pub span: NodeSpan,
+}
+
+//One argument of a function call; `name` is set for named arguments like `val => x`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionArg {
+    pub name: Option<Ident>,
+    pub value: Expression,
+    pub span: NodeSpan,
+}
+
+//The part between the brackets of a subscript expression.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Subscript {
+    Index(Expression),
+    Slice {
+        lower: Option<Expression>,
+        upper: Option<Expression>,
     },
+}
+
+//Which end(s) of the string TRIM removes characters from.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrimSide {
+    Leading,
+    Trailing,
+    Both,
+}
+
+//Fields that can qualify an INTERVAL literal.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntervalUnit {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+//Constant values written directly in the query. Numbers are signed: a minus directly before a
+//number, as in -1 or - 1, is part of the literal, so -1 is Integer(-1) while -a and -(1) are
+//UnaryOperator::Negate, and both -1 and - 1 print as -1.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Literal {
+    Integer(i128),
+    Float(f64),
+    //Exact numbers that don't fit the other variants, kept as written
+    Decimal(String),
+    String(String),
+    //0xFF / x'FF': hex digits as written
+    HexString(String),
+    //b'1010': bits as written
+    BitString(String),
+    //N'text'
+    NationalString(String),
     Boolean(bool),
     Null,
-    Grouped(Box<Expression>),
+}
+
+//How a Float with no SQL literal (infinity or NaN) is spelled in a cast from a string, as in
+//CAST('Infinity' AS DOUBLE PRECISION).
+pub(crate) fn non_finite_name(n: f64) -> &'static str {
+    if n.is_nan() {
+        "NaN"
+    } else if n > 0.0 {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+//Whether a name can be written without quotes (reserved keywords aside)
+pub(crate) fn is_word(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(|c| c.is_alphanumeric() || c == '_')
 }
 
 //this defines all the two-input operators used in SQL expressions.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BinaryOperator {
     Equals,
     NotEquals,
@@ -36,44 +335,435 @@ pub enum BinaryOperator {
     Subtract,
     Multiply,
     Divide,
+    Exponent,
+    //||
+    StringConcat,
+    //<< and >>
+    BitwiseShiftLeft,
+    BitwiseShiftRight,
+    JsonGet,
+    JsonGetText,
+    JsonGetPath,
+    JsonGetPathText,
 }
 
 //These are single-input operators.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOperator {
     Not,
+    //-expr, for any operand but a number (see Literal)
     Negate,
+    //Postfix n !
+    Factorial,
+}
+
+//A name as written. `value` keeps its original case without the quotes; `quote_style` is the
+//opening quote (", ` or [) for quoted names, or ' for a collation name written as a string.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ident {
+    pub value: String,
+    pub quote_style: Option<char>,
+    pub span: NodeSpan,
+}
+
+impl Ident {
+    pub fn new(value: impl Into<String>) -> Self {
+        Ident { value: value.into(), quote_style: None, span: NodeSpan::default() }
+    }
+
+    pub fn quoted(value: impl Into<String>, quote: char) -> Self {
+        Ident { value: value.into(), quote_style: Some(quote), span: NodeSpan::default() }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = NodeSpan(span);
+        self
+    }
+
+    //The name for comparisons: unquoted names fold to lower case, quoted names are exact.
+    pub fn normalized(&self) -> String {
+        match self.quote_style {
+            Some(_) => self.value.clone(),
+            None => self.value.to_lowercase(),
+        }
+    }
 }
 
 //This represents top-level SQL statements. Right now, only support SELECT.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
     Select {
-        columns: Vec<String>,
-        table: String,
+        columns: Vec<Expression>,
+        from: Box<TableWithJoins>,
         selection: Option<Expression>,
-        order_by: Option<Vec<String>>,
+        order_by: Option<Vec<OrderByExpr>>,
+        span: NodeSpan,
     },
     CreateTable {
-        table_name: String,
+        table_name: Ident,
         columns: Vec<ColumnDef>,
+        constraints: Vec<TableConstraint>,
+        options: Vec<TableOption>,
+        span: NodeSpan,
     },
     Insert {
-        table_name: String,
-        columns: Vec<String>,
+        //SQLite INSERT OR REPLACE and the like
+        or: Option<ConflictAction>,
+        table_name: Ident,
+        columns: Vec<Ident>,
         values: Vec<Expression>,
+        span: NodeSpan,
     },
 }
 
+impl Statement {
+    //From the first keyword to the last token before the terminating semicolon.
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Select { span, .. } | Statement::CreateTable { span, .. } | Statement::Insert { span, .. } => span.0,
+        }
+    }
+
+    pub fn kind(&self) -> StatementKind {
+        match self {
+            Statement::Select { .. } => StatementKind::Select,
+            Statement::CreateTable { .. } => StatementKind::CreateTable,
+            Statement::Insert { .. } => StatementKind::Insert,
+        }
+    }
+
+    //True if running the statement cannot change any data or schema.
+    pub fn is_read_only(&self) -> bool {
+        self.kind() == StatementKind::Select
+    }
+
+    //True for statements that define or change the schema rather than the data.
+    pub fn is_ddl(&self) -> bool {
+        self.kind() == StatementKind::CreateTable
+    }
+
+    //The tables whose rows or definition the statement changes; empty for reads.
+    pub fn tables_written(&self) -> Vec<&Ident> {
+        match self {
+            Statement::Select { .. } => Vec::new(),
+            Statement::CreateTable { table_name, .. } | Statement::Insert { table_name, .. } => vec![table_name],
+        }
+    }
+}
+
+//Which kind of statement a Statement is, for routing and permission checks without matching its fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatementKind {
+    Select,
+    CreateTable,
+    Insert,
+}
+
+//The FROM clause of a SELECT: the first table and the tables joined to it, in the order written.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableWithJoins {
+    pub relation: TableFactor,
+    pub joins: Vec<Join>,
+}
+
+impl TableWithJoins {
+    pub fn new(relation: TableFactor) -> Self {
+        TableWithJoins { relation, joins: Vec::new() }
+    }
+
+    //Every table in the clause, the FROM table first.
+    pub fn tables(&self) -> impl Iterator<Item = &TableFactor> {
+        std::iter::once(&self.relation).chain(self.joins.iter().map(|join| &join.relation))
+    }
+
+    //The table a qualifier such as the u in u.id stands for: the table with that alias, or with
+    //that name if it has no alias. Compared by Ident::normalized.
+    pub fn resolve(&self, qualifier: &Ident) -> Option<&Ident> {
+        self.tables()
+            .find(|table| table.alias.as_ref().unwrap_or(&table.name).normalized() == qualifier.normalized())
+            .map(|table| &table.name)
+    }
+}
+
+//A table named in FROM or JOIN, and the name the rest of the statement calls it by if it has an alias.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableFactor {
+    pub name: Ident,
+    pub alias: Option<Ident>,
+    pub span: NodeSpan,
+}
+
+impl TableFactor {
+    pub fn new(name: Ident) -> Self {
+        TableFactor { name, alias: None, span: NodeSpan::default() }
+    }
+}
+
+//One JOIN of a FROM clause, from the first word of the join to the end of its condition.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Join {
+    pub relation: TableFactor,
+    pub join_operator: JoinOperator,
+    pub span: NodeSpan,
+}
+
+//How a JOIN matches rows; INNER and the OUTER joins need a condition, CROSS JOIN takes none.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinOperator {
+    Inner(JoinConstraint),
+    LeftOuter(JoinConstraint),
+    RightOuter(JoinConstraint),
+    FullOuter(JoinConstraint),
+    Cross,
+}
+
+impl JoinOperator {
+    pub fn constraint(&self) -> Option<&JoinConstraint> {
+        match self {
+            JoinOperator::Inner(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => Some(constraint),
+            JoinOperator::Cross => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinConstraint {
+    On(Expression),
+    //USING (a, b): the columns both tables have, matched by name
+    Using(Vec<Ident>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColumnDef {
-    pub name: String,
+    pub name: Ident,
     pub data_type: DataType,
+    pub options: Vec<ColumnOption>,
+    pub span: NodeSpan,
+}
+
+//Everything that can follow the type in a column definition.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnOption {
+    NotNull,
+    //Explicit NULL, allowing nulls
+    Null,
+    PrimaryKey,
+    Unique,
+    Default(Expression),
+    //CHECK (expr) on one column, as a column option rather than a table constraint
+    Check(Expression),
+    //REFERENCES table [(columns)] [ON DELETE ...] [ON UPDATE ...]
+    References(ForeignKeyReference),
+    //[GENERATED ALWAYS] AS (expr) [STORED | VIRTUAL]
+    Generated {
+        expr: Expression,
+        storage: Option<GeneratedStorage>,
+    },
+    //GENERATED { ALWAYS | BY DEFAULT } AS IDENTITY [(START WITH n INCREMENT BY n)]
+    Identity {
+        always: bool,
+        start: Option<i64>,
+        increment: Option<i64>,
+    },
+    //MySQL AUTO_INCREMENT / SQLite AUTOINCREMENT
+    AutoIncrement,
+}
+
+//What SQLite's INSERT OR ... does when the row breaks a constraint.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConflictAction {
+    Replace,
+    Ignore,
+    Abort,
+    Fail,
+    Rollback,
 }
 
+//Whether a generated column is computed on write or on read.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GeneratedStorage {
+    Stored,
+    Virtual,
+}
+
+//A trailing table option such as ENGINE=InnoDB, fillfactor=70 from WITH (...),
+//or PARTITION BY with value "RANGE (col)". Names are uppercased except inside WITH (...).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableOption {
+    pub name: String,
+    pub value: String,
+    pub span: NodeSpan,
+}
+
+//Constraints listed among the columns of CREATE TABLE; `name` is set by CONSTRAINT name.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableConstraint {
+    //PRIMARY KEY (columns)
+    PrimaryKey {
+        name: Option<Ident>,
+        columns: Vec<Ident>,
+        span: NodeSpan,
+    },
+    //UNIQUE (columns)
+    Unique {
+        name: Option<Ident>,
+        columns: Vec<Ident>,
+        span: NodeSpan,
+    },
+    //CHECK (expr)
+    Check {
+        name: Option<Ident>,
+        expr: Expression,
+        span: NodeSpan,
+    },
+    //FOREIGN KEY (columns) REFERENCES ...
+    ForeignKey {
+        name: Option<Ident>,
+        columns: Vec<Ident>,
+        references: ForeignKeyReference,
+        span: NodeSpan,
+    },
+}
+
+impl TableConstraint {
+    //From CONSTRAINT, or the constraint keyword when unnamed, to the end of the constraint.
+    pub fn span(&self) -> Span {
+        match self {
+            TableConstraint::PrimaryKey { span, .. }
+            | TableConstraint::Unique { span, .. }
+            | TableConstraint::Check { span, .. }
+            | TableConstraint::ForeignKey { span, .. } => span.0,
+        }
+    }
+}
+
+//The target of a foreign key and what happens when the referenced row changes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForeignKeyReference {
+    pub table: Ident,
+    //Empty when the referenced table's primary key is implied
+    pub columns: Vec<Ident>,
+    pub on_delete: Option<ReferentialAction>,
+    pub on_update: Option<ReferentialAction>,
+    //From REFERENCES to the end of the last action
+    pub span: NodeSpan,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReferentialAction {
+    Cascade,
+    Restrict,
+    NoAction,
+    SetNull,
+    SetDefault,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DataType {
-    Int,
-    Varchar(usize),
+    //INT, BIGINT, SMALLINT, ... optionally UNSIGNED (MySQL)
+    Integer {
+        width: IntegerWidth,
+        unsigned: bool,
+    },
+    //Postgres auto-incrementing integers: SMALLSERIAL, SERIAL, BIGSERIAL
+    Serial(IntegerWidth),
+    //VARCHAR, VARCHAR(n) or VARCHAR(MAX)
+    Varchar(Option<CharacterLength>),
+    //CHAR / CHARACTER with optional length
+    Char(Option<u64>),
+    Text,
+    //BINARY(n) / VARBINARY(n)
+    Binary(Option<u64>),
+    Varbinary(Option<u64>),
+    Blob,
+    //Postgres binary string
+    Bytea,
+    Uuid,
+    Json,
+    //Postgres binary JSON
+    Jsonb,
     Boolean,
-}
\ No newline at end of file
+    //MySQL ENUM('a', 'b', ...) with its allowed values
+    Enum(Vec<String>),
+    //INT[] / TEXT[][] / ARRAY<INT>: element type and number of dimensions
+    Array(Box<DataType>, usize),
+    //BigQuery STRUCT<name STRING, INT64>
+    Struct(Vec<StructField>),
+    //ANSI ROW(a INT, b TEXT)
+    Row(Vec<StructField>),
+    Date,
+    //TIME[(p)] [WITH | WITHOUT TIME ZONE], TIMETZ
+    Time {
+        precision: Option<u64>,
+        with_time_zone: bool,
+    },
+    //TIMESTAMP[(p)] [WITH | WITHOUT TIME ZONE], TIMESTAMPTZ
+    Timestamp {
+        precision: Option<u64>,
+        with_time_zone: bool,
+    },
+    //FLOAT or FLOAT(p), where p is the precision in bits
+    Float(Option<u64>),
+    Real,
+    //DOUBLE or DOUBLE PRECISION
+    Double,
+    //DECIMAL / NUMERIC with optional (precision[, scale])
+    Decimal(Option<(u64, Option<u64>)>),
+    //Any other type name, with its parenthesized arguments as written (strings keep their quotes)
+    Custom(Ident, Vec<String>),
+}
+
+//A field of a STRUCT or ROW type; STRUCT fields may be unnamed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructField {
+    pub name: Option<Ident>,
+    pub data_type: DataType,
+    pub span: NodeSpan,
+}
+
+//The length argument of VARCHAR.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CharacterLength {
+    Length(u64),
+    //SQL Server VARCHAR(MAX)
+    Max,
+}
+
+//Storage size of an integer column type.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntegerWidth {
+    //TINYINT
+    Tiny,
+    //SMALLINT, INT2
+    Small,
+    //MEDIUMINT
+    Medium,
+    //INT, INTEGER, INT4
+    Regular,
+    //BIGINT, INT8
+    Big,
+}
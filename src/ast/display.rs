@@ -0,0 +1,816 @@
+// SQL text for the AST: every node prints as SQL that parses back to the same node.
+//Parentheses are printed only where the tree has Grouped nodes, so a hand-built tree needs
+//Grouped wherever operator precedence would otherwise regroup it.
+use std::fmt;
+
+use super::*;
+use crate::tokenizer::lookup_keyword;
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Select { columns, from, selection, order_by, .. } => {
+                f.write_str("SELECT ")?;
+                write_separated(f, columns, ", ")?;
+                write!(f, " FROM {}", from)?;
+                if let Some(selection) = selection {
+                    write!(f, " WHERE {}", selection)?;
+                }
+                if let Some(order_by) = order_by.as_ref().filter(|items| !items.is_empty()) {
+                    f.write_str(" ORDER BY ")?;
+                    write_separated(f, order_by, ", ")?;
+                }
+                Ok(())
+            }
+            Statement::CreateTable { table_name, columns, constraints, options, .. } => {
+                write!(f, "CREATE TABLE {} (", table_name)?;
+                write_separated(f, columns, ", ")?;
+                if !columns.is_empty() && !constraints.is_empty() {
+                    f.write_str(", ")?;
+                }
+                write_separated(f, constraints, ", ")?;
+                f.write_str(")")?;
+                write_table_options(f, options)
+            }
+            Statement::Insert { or, table_name, columns, values, .. } => {
+                f.write_str("INSERT ")?;
+                if let Some(action) = or {
+                    write!(f, "OR {} ", action)?;
+                }
+                write!(f, "INTO {} (", table_name)?;
+                write_separated(f, columns, ", ")?;
+                f.write_str(") VALUES (")?;
+                write_separated(f, values, ", ")?;
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Identifier(name) => write!(f, "{}", name),
+            Expression::CompoundIdentifier(parts, _) => write_compound(f, parts),
+            Expression::Literal(literal, _) => write!(f, "{}", literal),
+            Expression::Placeholder(text, _) => f.write_str(text),
+            Expression::UnaryOperation { operator: UnaryOperator::Factorial, operand, .. } => write!(f, "{}!", operand),
+            Expression::UnaryOperation { operator: UnaryOperator::Negate, operand, .. } => {
+                //A space keeps - -x from being read as the start of a -- comment
+                let operand = operand.to_string();
+                let space = if operand.starts_with('-') { " " } else { "" };
+                write!(f, "-{}{}", space, operand)
+            }
+            Expression::UnaryOperation { operator, operand, .. } => write!(f, "{} {}", operator, operand),
+            Expression::BinaryOperation { left_operand, operator, right_operand, .. } => {
+                write!(f, "{} {} {}", left_operand, operator, right_operand)
+            }
+            Expression::Grouped(inner, _) => write!(f, "({})", inner),
+            Expression::Function { name, args, order_by, filter, .. } => {
+                write!(f, "{}(", name)?;
+                write_separated(f, args, ", ")?;
+                if !order_by.is_empty() {
+                    f.write_str(" ORDER BY ")?;
+                    write_separated(f, order_by, ", ")?;
+                }
+                f.write_str(")")?;
+                if let Some(filter) = filter {
+                    write!(f, " FILTER (WHERE {})", filter)?;
+                }
+                Ok(())
+            }
+            Expression::Wildcard(_) => f.write_str("*"),
+            //Not SQL on purpose, so a partial statement cannot be mistaken for a complete one
+            Expression::Error => f.write_str("<error>"),
+            Expression::QualifiedWildcard(parts, _) => {
+                write_compound(f, parts)?;
+                f.write_str(".*")
+            }
+            Expression::Position { substring, string, .. } => write!(f, "POSITION({} IN {})", substring, string),
+            Expression::Trim { side, characters, expr, .. } => {
+                f.write_str("TRIM(")?;
+                if let Some(side) = side {
+                    write!(f, "{} ", side)?;
+                }
+                if let Some(characters) = characters {
+                    write!(f, "{} ", characters)?;
+                }
+                if side.is_some() || characters.is_some() {
+                    f.write_str("FROM ")?;
+                }
+                write!(f, "{})", expr)
+            }
+            Expression::Interval { value, unit, precision, last_unit, .. } => {
+                f.write_str("INTERVAL ")?;
+                write_string(f, value)?;
+                if let Some(unit) = unit {
+                    write!(f, " {}", unit)?;
+                }
+                if let Some(precision) = precision {
+                    write!(f, "({})", precision)?;
+                }
+                if let Some(last_unit) = last_unit {
+                    write!(f, " TO {}", last_unit)?;
+                }
+                Ok(())
+            }
+            Expression::TypedString { data_type, value, .. } => {
+                //TIMESTAMPTZ is the only one-word spelling of a zoned timestamp
+                match data_type {
+                    DataType::Timestamp { precision: None, with_time_zone: true } => f.write_str("TIMESTAMPTZ ")?,
+                    _ => write!(f, "{} ", data_type)?,
+                }
+                write_string(f, value)
+            }
+            Expression::Array(elements, _) => {
+                f.write_str("ARRAY[")?;
+                write_separated(f, elements, ", ")?;
+                f.write_str("]")
+            }
+            Expression::Subscript { expr, subscript, .. } => write!(f, "{}[{}]", expr, subscript),
+            Expression::Tuple(elements, _) => {
+                f.write_str("(")?;
+                write_separated(f, elements, ", ")?;
+                f.write_str(")")
+            }
+            Expression::InList { expr, list, negated, .. } => {
+                write!(f, "{} {}IN (", expr, not(*negated))?;
+                write_separated(f, list, ", ")?;
+                f.write_str(")")
+            }
+            Expression::Collate { expr, collation, .. } => write!(f, "{} COLLATE {}", expr, collation),
+            Expression::AtTimeZone { timestamp, time_zone, .. } => write!(f, "{} AT TIME ZONE {}", timestamp, time_zone),
+            Expression::RegexMatch { expr, pattern, syntax, negated, case_insensitive, .. } => match syntax {
+                RegexSyntax::SimilarTo => write!(f, "{} {}SIMILAR TO {}", expr, not(*negated), pattern),
+                RegexSyntax::Posix => {
+                    let operator = match (negated, case_insensitive) {
+                        (false, false) => "~",
+                        (false, true) => "~*",
+                        (true, false) => "!~",
+                        (true, true) => "!~*",
+                    };
+                    write!(f, "{} {} {}", expr, operator, pattern)
+                }
+            },
+            Expression::Like { expr, pattern, negated, case_insensitive, .. } => {
+                let operator = if *case_insensitive { "ILIKE" } else { "LIKE" };
+                write!(f, "{} {}{} {}", expr, not(*negated), operator, pattern)
+            }
+            Expression::Between { expr, low, high, negated, .. } => {
+                write!(f, "{} {}BETWEEN {} AND {}", expr, not(*negated), low, high)
+            }
+            Expression::Is { expr, test, negated, .. } => write!(f, "{} IS {}{}", expr, not(*negated), test),
+            Expression::Cast { expr, data_type, .. } => write!(f, "CAST({} AS {})", expr, data_type),
+            Expression::Case { operand, branches, else_result, .. } => {
+                f.write_str("CASE ")?;
+                if let Some(operand) = operand {
+                    write!(f, "{} ", operand)?;
+                }
+                for branch in branches {
+                    write!(f, "{} ", branch)?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, "ELSE {} ", else_result)?;
+                }
+                f.write_str("END")
+            }
+        }
+    }
+}
+
+impl fmt::Display for CaseWhen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WHEN {} THEN {}", self.condition, self.result)
+    }
+}
+
+impl fmt::Display for IsTest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IsTest::Null => "NULL",
+            IsTest::True => "TRUE",
+            IsTest::False => "FALSE",
+            IsTest::Unknown => "UNKNOWN",
+        })
+    }
+}
+
+impl fmt::Display for OrderByExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)?;
+        match self.asc {
+            Some(true) => f.write_str(" ASC"),
+            Some(false) => f.write_str(" DESC"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for FunctionArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "{} => ", name)?;
+        }
+        write!(f, "{}", self.value)
+    }
+}
+
+impl fmt::Display for Subscript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Subscript::Index(index) => write!(f, "{}", index),
+            Subscript::Slice { lower, upper } => {
+                if let Some(lower) = lower {
+                    write!(f, "{}", lower)?;
+                }
+                f.write_str(":")?;
+                if let Some(upper) = upper {
+                    write!(f, "{}", upper)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for TrimSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TrimSide::Leading => "LEADING",
+            TrimSide::Trailing => "TRAILING",
+            TrimSide::Both => "BOTH",
+        })
+    }
+}
+
+impl fmt::Display for IntervalUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IntervalUnit::Year => "YEAR",
+            IntervalUnit::Month => "MONTH",
+            IntervalUnit::Day => "DAY",
+            IntervalUnit::Hour => "HOUR",
+            IntervalUnit::Minute => "MINUTE",
+            IntervalUnit::Second => "SECOND",
+        })
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Integer(n) => write!(f, "{}", n),
+            //Debug keeps the fraction or exponent, so 1.0 does not come back as an Integer
+            //Infinity and NaN have no literal; lit() builds the matching Cast, so built trees still round-trip
+            Literal::Float(n) if !n.is_finite() => write!(f, "CAST('{}' AS DOUBLE PRECISION)", non_finite_name(*n)),
+            Literal::Float(n) => write!(f, "{:?}", n),
+            Literal::Decimal(digits) => f.write_str(digits),
+            Literal::String(value) => write_string(f, value),
+            Literal::HexString(digits) => write!(f, "X'{}'", digits),
+            Literal::BitString(bits) => write!(f, "B'{}'", bits),
+            Literal::NationalString(value) => {
+                f.write_str("N")?;
+                write_string(f, value)
+            }
+            Literal::Boolean(true) => f.write_str("TRUE"),
+            Literal::Boolean(false) => f.write_str("FALSE"),
+            Literal::Null => f.write_str("NULL"),
+        }
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinaryOperator::Equals => "=",
+            BinaryOperator::NotEquals => "<>",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::GreaterThanOrEqual => ">=",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::LessThanOrEqual => "<=",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Or => "OR",
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Exponent => "^",
+            BinaryOperator::StringConcat => "||",
+            BinaryOperator::BitwiseShiftLeft => "<<",
+            BinaryOperator::BitwiseShiftRight => ">>",
+            BinaryOperator::JsonGet => "->",
+            BinaryOperator::JsonGetText => "->>",
+            BinaryOperator::JsonGetPath => "#>",
+            BinaryOperator::JsonGetPathText => "#>>",
+        })
+    }
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UnaryOperator::Not => "NOT",
+            UnaryOperator::Negate => "-",
+            UnaryOperator::Factorial => "!",
+        })
+    }
+}
+
+//Names that need quoting to be read back are quoted with backticks.
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.quote_style {
+            Some('[') => write!(f, "[{}]", self.value.replace(']', "]]")),
+            Some(quote) => write_quoted(f, quote, &self.value),
+            //Names spelled like a keyword are quoted, so they are not read back as one
+            None if is_word(&self.value) && lookup_keyword(&self.value).is_none() => {
+                f.write_str(&self.value)
+            }
+            None => write_quoted(f, '`', &self.value),
+        }
+    }
+}
+
+impl fmt::Display for ColumnDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        for option in &self.options {
+            write!(f, " {}", option)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ColumnOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnOption::NotNull => f.write_str("NOT NULL"),
+            ColumnOption::Null => f.write_str("NULL"),
+            ColumnOption::PrimaryKey => f.write_str("PRIMARY KEY"),
+            ColumnOption::Unique => f.write_str("UNIQUE"),
+            ColumnOption::Default(expr) => write!(f, "DEFAULT {}", expr),
+            ColumnOption::Check(expr) => write!(f, "CHECK ({})", expr),
+            ColumnOption::References(reference) => write!(f, "REFERENCES {}", reference),
+            ColumnOption::Generated { expr, storage } => {
+                write!(f, "GENERATED ALWAYS AS ({})", expr)?;
+                match storage {
+                    Some(GeneratedStorage::Stored) => f.write_str(" STORED"),
+                    Some(GeneratedStorage::Virtual) => f.write_str(" VIRTUAL"),
+                    None => Ok(()),
+                }
+            }
+            ColumnOption::Identity { always, start, increment } => {
+                let when = if *always { "ALWAYS" } else { "BY DEFAULT" };
+                write!(f, "GENERATED {} AS IDENTITY", when)?;
+                let mut sequence = Vec::new();
+                if let Some(start) = start {
+                    sequence.push(format!("START WITH {}", start));
+                }
+                if let Some(increment) = increment {
+                    sequence.push(format!("INCREMENT BY {}", increment));
+                }
+                if !sequence.is_empty() {
+                    write!(f, " ({})", sequence.join(" "))?;
+                }
+                Ok(())
+            }
+            ColumnOption::AutoIncrement => f.write_str("AUTO_INCREMENT"),
+        }
+    }
+}
+
+impl fmt::Display for TableConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TableConstraint::PrimaryKey { name, .. }
+            | TableConstraint::Unique { name, .. }
+            | TableConstraint::Check { name, .. }
+            | TableConstraint::ForeignKey { name, .. } => name,
+        };
+        if let Some(name) = name {
+            write!(f, "CONSTRAINT {} ", name)?;
+        }
+        match self {
+            TableConstraint::PrimaryKey { columns, .. } => {
+                f.write_str("PRIMARY KEY ")?;
+                write_column_list(f, columns)
+            }
+            TableConstraint::Unique { columns, .. } => {
+                f.write_str("UNIQUE ")?;
+                write_column_list(f, columns)
+            }
+            TableConstraint::Check { expr, .. } => write!(f, "CHECK ({})", expr),
+            TableConstraint::ForeignKey { columns, references, .. } => {
+                f.write_str("FOREIGN KEY ")?;
+                write_column_list(f, columns)?;
+                write!(f, " REFERENCES {}", references)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TableWithJoins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.relation)?;
+        for join in &self.joins {
+            write!(f, " {}", join)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TableFactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(alias) = &self.alias {
+            write!(f, " AS {}", alias)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Join {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self.join_operator {
+            JoinOperator::Inner(_) => "JOIN",
+            JoinOperator::LeftOuter(_) => "LEFT JOIN",
+            JoinOperator::RightOuter(_) => "RIGHT JOIN",
+            JoinOperator::FullOuter(_) => "FULL JOIN",
+            JoinOperator::Cross => "CROSS JOIN",
+        };
+        write!(f, "{} {}", keyword, self.relation)?;
+        match self.join_operator.constraint() {
+            Some(JoinConstraint::On(condition)) => write!(f, " ON {}", condition),
+            Some(JoinConstraint::Using(columns)) => {
+                f.write_str(" USING ")?;
+                write_column_list(f, columns)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ForeignKeyReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.table)?;
+        if !self.columns.is_empty() {
+            f.write_str(" ")?;
+            write_column_list(f, &self.columns)?;
+        }
+        if let Some(action) = &self.on_delete {
+            write!(f, " ON DELETE {}", action)?;
+        }
+        if let Some(action) = &self.on_update {
+            write!(f, " ON UPDATE {}", action)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConflictAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConflictAction::Replace => "REPLACE",
+            ConflictAction::Ignore => "IGNORE",
+            ConflictAction::Abort => "ABORT",
+            ConflictAction::Fail => "FAIL",
+            ConflictAction::Rollback => "ROLLBACK",
+        })
+    }
+}
+
+impl fmt::Display for ReferentialAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReferentialAction::Cascade => "CASCADE",
+            ReferentialAction::Restrict => "RESTRICT",
+            ReferentialAction::NoAction => "NO ACTION",
+            ReferentialAction::SetNull => "SET NULL",
+            ReferentialAction::SetDefault => "SET DEFAULT",
+        })
+    }
+}
+
+//A single option as it is written after CREATE TABLE (...). Options that came from WITH (...)
+//are printed in a WITH list by the statement instead.
+impl fmt::Display for TableOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name == "PARTITION BY" {
+            return write!(f, "PARTITION BY {}", self.value);
+        }
+        write!(f, "{}=", self.name)?;
+        write_option_value(f, &self.value)
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Integer { width, unsigned } => {
+                f.write_str(match width {
+                    IntegerWidth::Tiny => "TINYINT",
+                    IntegerWidth::Small => "SMALLINT",
+                    IntegerWidth::Medium => "MEDIUMINT",
+                    IntegerWidth::Regular => "INT",
+                    IntegerWidth::Big => "BIGINT",
+                })?;
+                if *unsigned {
+                    f.write_str(" UNSIGNED")?;
+                }
+                Ok(())
+            }
+            //There are no TINY or MEDIUM serials, so those print as the next size up
+            DataType::Serial(width) => f.write_str(match width {
+                IntegerWidth::Tiny | IntegerWidth::Small => "SMALLSERIAL",
+                IntegerWidth::Medium | IntegerWidth::Regular => "SERIAL",
+                IntegerWidth::Big => "BIGSERIAL",
+            }),
+            DataType::Varchar(None) => f.write_str("VARCHAR"),
+            DataType::Varchar(Some(CharacterLength::Length(n))) => write!(f, "VARCHAR({})", n),
+            DataType::Varchar(Some(CharacterLength::Max)) => f.write_str("VARCHAR(MAX)"),
+            DataType::Char(length) => write_with_argument(f, "CHAR", length),
+            DataType::Text => f.write_str("TEXT"),
+            DataType::Binary(length) => write_with_argument(f, "BINARY", length),
+            DataType::Varbinary(length) => write_with_argument(f, "VARBINARY", length),
+            DataType::Blob => f.write_str("BLOB"),
+            DataType::Bytea => f.write_str("BYTEA"),
+            DataType::Uuid => f.write_str("UUID"),
+            DataType::Json => f.write_str("JSON"),
+            DataType::Jsonb => f.write_str("JSONB"),
+            DataType::Boolean => f.write_str("BOOLEAN"),
+            DataType::Enum(values) => {
+                f.write_str("ENUM(")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write_string(f, value)?;
+                }
+                f.write_str(")")
+            }
+            //INT[][] would read back as one two-dimensional array, so an array of arrays is ARRAY<...>
+            DataType::Array(element, 1) if matches!(**element, DataType::Array(..)) => write!(f, "ARRAY<{}>", element),
+            DataType::Array(element, dimensions) => write!(f, "{}{}", element, "[]".repeat(*dimensions)),
+            DataType::Struct(fields) => {
+                f.write_str("STRUCT<")?;
+                write_separated(f, fields, ", ")?;
+                f.write_str(">")
+            }
+            DataType::Row(fields) => {
+                f.write_str("ROW(")?;
+                write_separated(f, fields, ", ")?;
+                f.write_str(")")
+            }
+            DataType::Date => f.write_str("DATE"),
+            DataType::Time { precision, with_time_zone } => {
+                write_with_argument(f, "TIME", precision)?;
+                write_time_zone(f, *with_time_zone)
+            }
+            DataType::Timestamp { precision, with_time_zone } => {
+                write_with_argument(f, "TIMESTAMP", precision)?;
+                write_time_zone(f, *with_time_zone)
+            }
+            DataType::Float(precision) => write_with_argument(f, "FLOAT", precision),
+            DataType::Real => f.write_str("REAL"),
+            DataType::Double => f.write_str("DOUBLE PRECISION"),
+            DataType::Decimal(None) => f.write_str("DECIMAL"),
+            DataType::Decimal(Some((precision, None))) => write!(f, "DECIMAL({})", precision),
+            DataType::Decimal(Some((precision, Some(scale)))) => write!(f, "DECIMAL({}, {})", precision, scale),
+            DataType::Custom(name, args) => {
+                write!(f, "{}", name)?;
+                if !args.is_empty() {
+                    write!(f, "({})", args.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for StructField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "{} ", name)?;
+        }
+        write!(f, "{}", self.data_type)
+    }
+}
+
+fn write_separated<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T], separator: &str) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+fn write_column_list(f: &mut fmt::Formatter<'_>, columns: &[Ident]) -> fmt::Result {
+    f.write_str("(")?;
+    write_separated(f, columns, ", ")?;
+    f.write_str(")")
+}
+
+//After a period even reserved words are names, so only the first part may need quotes for them
+fn write_compound(f: &mut fmt::Formatter<'_>, parts: &[Ident]) -> fmt::Result {
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            f.write_str(".")?;
+            if part.quote_style.is_none() && is_word(&part.value) {
+                f.write_str(&part.value)?;
+                continue;
+            }
+        }
+        write!(f, "{}", part)?;
+    }
+    Ok(())
+}
+
+//Options named in upper case are MySQL style NAME=value; the rest came from Postgres WITH (...)
+//and are printed back in one WITH list per run.
+fn write_table_options(f: &mut fmt::Formatter<'_>, options: &[TableOption]) -> fmt::Result {
+    let mut i = 0;
+    while i < options.len() {
+        let storage_parameters = options[i..]
+            .iter()
+            .take_while(|option| option.name != "PARTITION BY" && option.name.chars().any(|c| c.is_lowercase()))
+            .count();
+        if storage_parameters == 0 {
+            write!(f, " {}", options[i])?;
+            i += 1;
+            continue;
+        }
+        f.write_str(" WITH (")?;
+        for (n, option) in options[i..i + storage_parameters].iter().enumerate() {
+            if n > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} = ", option.name)?;
+            write_option_value(f, &option.value)?;
+        }
+        f.write_str(")")?;
+        i += storage_parameters;
+    }
+    Ok(())
+}
+
+//Option values are kept without their quotes; words and numbers are written bare, anything
+//else as a string.
+fn write_option_value(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    let number = value.starts_with(|c: char| c.is_ascii_digit()) && value.parse::<f64>().is_ok();
+    let word = is_word(value) && (lookup_keyword(value).is_none() || value == "true" || value == "false");
+    if number || word {
+        f.write_str(value)
+    } else {
+        write_string(f, value)
+    }
+}
+
+fn write_with_argument(f: &mut fmt::Formatter<'_>, name: &str, argument: &Option<u64>) -> fmt::Result {
+    f.write_str(name)?;
+    match argument {
+        Some(argument) => write!(f, "({})", argument),
+        None => Ok(()),
+    }
+}
+
+fn write_time_zone(f: &mut fmt::Formatter<'_>, with_time_zone: bool) -> fmt::Result {
+    if with_time_zone {
+        f.write_str(" WITH TIME ZONE")?;
+    }
+    Ok(())
+}
+
+//A single-quoted string literal, with quotes inside it doubled
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write_quoted(f, '\'', value)
+}
+
+fn write_quoted(f: &mut fmt::Formatter<'_>, quote: char, value: &str) -> fmt::Result {
+    let doubled: String = [quote, quote].iter().collect();
+    write!(f, "{}{}{}", quote, value.replace(quote, &doubled), quote)
+}
+
+//Whether `text` lexes as a single word: a letter or _ followed by letters, digits and _
+fn not(negated: bool) -> &'static str {
+    if negated { "NOT " } else { "" }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_input_with_options, parse_sql, Dialect, Limits, ParserOptions};
+
+    //Each statement prints as the given canonical text, which parses back to the same tree.
+    fn round_trip(sql: &str, printed: &str) {
+        let statement = parse_sql(sql).unwrap();
+        assert_eq!(statement.to_string(), printed, "{}", sql);
+        assert_eq!(parse_sql(printed).unwrap(), statement, "{}", printed);
+    }
+
+    #[test]
+    fn statements_print_as_sql_that_parses_back() {
+        round_trip("select a,b from t", "SELECT a, b FROM t");
+        round_trip("SELECT * FROM t WHERE a <> 1 ORDER BY a desc, b", "SELECT * FROM t WHERE a <> 1 ORDER BY a DESC, b");
+        round_trip("SELECT t.*, t.order, `my col`, [x]]y] FROM t", "SELECT t.*, t.order, `my col`, [x]]y] FROM t");
+        round_trip("SELECT `Order`, t.From, Coalesce(`KEY`, 0) FROM t", "SELECT `Order`, t.From, Coalesce(`KEY`, 0) FROM t");
+        //Keywords used as names keep their spelling and print quoted, so they read back as names
+        let statement = parse_sql("SELECT Order, t.From, Coalesce(KEY, 0) FROM t").unwrap();
+        assert_eq!(statement.to_string(), "SELECT `Order`, t.From, Coalesce(`KEY`, 0) FROM t");
+        round_trip(
+            "SELECT u.*, o.total FROM users u JOIN orders o ON u.id = o.user_id left outer join p using (id) CROSS JOIN q",
+            "SELECT u.*, o.total FROM users AS u JOIN orders AS o ON u.id = o.user_id LEFT JOIN p USING (id) CROSS JOIN q",
+        );
+        round_trip("SELECT * FROM t AS `Key` RIGHT JOIN u ON TRUE FULL JOIN v ON FALSE", "SELECT * FROM t AS `Key` RIGHT JOIN u ON TRUE FULL JOIN v ON FALSE");
+        round_trip("insert or rollback into t (a) values (1)", "INSERT OR ROLLBACK INTO t (a) VALUES (1)");
+        round_trip(
+            "INSERT INTO t (a, b) VALUES (-5, 'it''s')",
+            "INSERT INTO t (a, b) VALUES (-5, 'it''s')",
+        );
+        round_trip(
+            "CREATE TABLE t (id SERIAL PRIMARY KEY, name VARCHAR(20) NOT NULL DEFAULT 'x' check (name <> ''), \
+             p INT REFERENCES u (id) ON DELETE SET NULL, CONSTRAINT c CHECK (id > 0)) ENGINE=InnoDB",
+            "CREATE TABLE t (id SERIAL PRIMARY KEY, name VARCHAR(20) NOT NULL DEFAULT 'x' CHECK (name <> ''), \
+             p INT REFERENCES u (id) ON DELETE SET NULL, CONSTRAINT c CHECK (id > 0)) ENGINE=InnoDB",
+        );
+        round_trip(
+            "CREATE TABLE t (a INT GENERATED BY DEFAULT AS IDENTITY (START WITH 10 INCREMENT BY 5), \
+             b INT AS (a * 2) STORED, c INT AUTO_INCREMENT) WITH (fillfactor = 70) PARTITION BY RANGE (a)",
+            "CREATE TABLE t (a INT GENERATED BY DEFAULT AS IDENTITY (START WITH 10 INCREMENT BY 5), \
+             b INT GENERATED ALWAYS AS (a * 2) STORED, c INT AUTO_INCREMENT) WITH (fillfactor = 70) PARTITION BY RANGE (a)",
+        );
+    }
+
+    #[test]
+    fn quoted_collations_keep_their_quotes() {
+        let options = ParserOptions { dialect: Some(Dialect::Postgres), ..ParserOptions::default() };
+        let parse = |sql: &str| {
+            let output = parse_input_with_options(sql, &Limits::default(), &options);
+            assert!(!output.has_errors(), "{}", sql);
+            output.value.unwrap()
+        };
+        let statement = parse("SELECT name FROM t ORDER BY name COLLATE \"de_DE\"");
+        let printed = statement.to_string();
+        assert_eq!(printed, "SELECT name FROM t ORDER BY name COLLATE \"de_DE\"");
+        assert_eq!(parse(&printed), statement);
+
+        //Without a dialect double quotes delimit strings, but not after COLLATE
+        round_trip("SELECT name FROM t ORDER BY name COLLATE \"de_DE\"", "SELECT name FROM t ORDER BY name COLLATE \"de_DE\"");
+    }
+
+    #[test]
+    fn expressions_print_as_sql_that_parses_back() {
+        let cases = [
+            ("(a + b) * -c", "(a + b) * -c"),
+            ("- -a", "- -a"),
+            ("5!", "5!"),
+            ("NOT a = 1 AND b IS NOT NULL", "NOT a = 1 AND b IS NOT NULL"),
+            ("a NOT IN (1, 2) OR b NOT BETWEEN 1 AND 2", "a NOT IN (1, 2) OR b NOT BETWEEN 1 AND 2"),
+            ("a ilike 'x%' AND a !~* 'y' AND a NOT SIMILAR TO 'z'", "a ILIKE 'x%' AND a !~* 'y' AND a NOT SIMILAR TO 'z'"),
+            ("1.0 + 1e300 + 0xFF + b'10' + N'n'", "1.0 + 1e300 + X'FF' + B'10' + N'n'"),
+            ("1e400 - 1E-400", "1e400 - 0.0"),
+            ("a::INT + CAST(b AS DECIMAL(10, 2))", "CAST(a AS INT) + CAST(b AS DECIMAL(10, 2))"),
+            ("count(*) FILTER (WHERE a > 1)", "count(*) FILTER (WHERE a > 1)"),
+            ("string_agg(name, ',' ORDER BY name)", "string_agg(name, ',' ORDER BY name)"),
+            ("f(val => 1)", "f(val => 1)"),
+            ("POSITION('a' IN b) + TRIM(LEADING 'x' FROM c)", "POSITION('a' IN b) + TRIM(LEADING 'x' FROM c)"),
+            ("INTERVAL '1:30' HOUR TO MINUTE", "INTERVAL '1:30' HOUR TO MINUTE"),
+            ("TIMESTAMPTZ '2024-01-01' AT TIME ZONE 'UTC'", "TIMESTAMPTZ '2024-01-01' AT TIME ZONE 'UTC'"),
+            ("ARRAY[1, 2][1:2] || tags[1]", "ARRAY[1, 2][1:2] || tags[1]"),
+            ("(a, b) = (1, 2)", "(a, b) = (1, 2)"),
+            ("name COLLATE \"de-DE\"", "name COLLATE \"de-DE\""),
+            ("name COLLATE 'de-DE'", "name COLLATE 'de-DE'"),
+            ("name COLLATE C", "name COLLATE C"),
+            ("CASE WHEN a THEN 1 ELSE 2 END", "CASE WHEN a THEN 1 ELSE 2 END"),
+            ("doc->'a'->>'b' #> c", "doc -> 'a' ->> 'b' #> c"),
+            ("a->>'x' + 1", "a ->> 'x' + 1"),
+            ("(a->>'x') + 1", "(a ->> 'x') + 1"),
+        ];
+        for (sql, printed) in cases {
+            round_trip(&format!("SELECT {} FROM t", sql), &format!("SELECT {} FROM t", printed));
+        }
+    }
+
+    #[test]
+    fn data_types_print_as_sql_that_parses_back() {
+        let cases = [
+            ("int unsigned", "INT UNSIGNED"),
+            ("character varying(10)", "VARCHAR(10)"),
+            ("varchar(max)", "VARCHAR(MAX)"),
+            ("enum('a', 'b''c')", "ENUM('a', 'b''c')"),
+            ("text[][]", "TEXT[][]"),
+            ("ARRAY<ARRAY<INT>>", "ARRAY<INT[]>"),
+            ("STRUCT<a INT, b STRUCT<c TEXT>>", "STRUCT<a INT, b STRUCT<c TEXT>>"),
+            ("ROW(a INT)", "ROW(a INT)"),
+            ("timestamptz(3)", "TIMESTAMP(3) WITH TIME ZONE"),
+            ("double", "DOUBLE PRECISION"),
+            ("geometry(point, 4326)", "geometry(point, 4326)"),
+            ("my_type('it''s')", "my_type('it''s')"),
+            ("`MyType`", "`MyType`"),
+            ("\"MyType\"(3)", "\"MyType\"(3)"),
+        ];
+        for (sql, printed) in cases {
+            round_trip(&format!("CREATE TABLE t (c {})", sql), &format!("CREATE TABLE t (c {})", printed));
+        }
+    }
+}
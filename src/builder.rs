@@ -0,0 +1,530 @@
+// Helpers for building statements in code instead of assembling enum variants and Boxes by hand.
+// Operands are wrapped in Grouped where precedence needs it, so what is built prints (with
+// Display) as SQL that parses back to the same tree.
+use crate::ast::*;
+use crate::pratt::{binary_operator_precedence, expression_precedence, parse_number, IS_PRECEDENCE, NEGATE_PRECEDENCE, NOT_PRECEDENCE, PATTERN_PRECEDENCE};
+use crate::tokenizer::lookup_keyword;
+
+/// A column reference; a dotted name such as "t.id" becomes a compound identifier
+pub fn col(name: &str) -> Expression {
+    let mut parts: Vec<Ident> = name.split('.').enumerate().map(|(i, part)| ident(part, i > 0)).collect();
+    if parts.len() == 1 {
+        Expression::Identifier(parts.remove(0))
+    } else {
+        Expression::CompoundIdentifier(parts, NodeSpan::default())
+    }
+}
+
+/// A literal value: lit(18), lit(1.5), lit("text"), lit(true) or lit(Literal::Null)
+/// Infinite and NaN floats have no literal, so they become CAST('Infinity' AS DOUBLE PRECISION) and the like.
+pub fn lit(value: impl Into<Literal>) -> Expression {
+    match value.into() {
+        Literal::Float(n) if !n.is_finite() => lit(non_finite_name(n)).cast(DataType::Double),
+        literal => Expression::Literal(literal, NodeSpan::default()),
+    }
+}
+
+/// A call of the function `name` with positional arguments
+pub fn func(name: &str, args: impl IntoIterator<Item = Expression>) -> Expression {
+    Expression::Function {
+        name: ident(name, false),
+        args: args.into_iter().map(|value| FunctionArg { name: None, value, span: NodeSpan::default() }).collect(),
+        order_by: Vec::new(),
+        filter: None,
+        span: NodeSpan::default(),
+    }
+}
+
+/// NOT expr
+pub fn not(expr: Expression) -> Expression {
+    Expression::UnaryOperation { operator: UnaryOperator::Not, operand: Box::new(operand(expr, NOT_PRECEDENCE)), span: NodeSpan::default() }
+}
+
+/// -expr; a minus before a number is part of the literal, as the parser reads -1
+pub fn negate(expr: Expression) -> Expression {
+    if let Expression::Literal(literal @ (Literal::Integer(_) | Literal::Float(_) | Literal::Decimal(_)), span) = &expr {
+        let digits = literal.to_string();
+        if !digits.starts_with('-') {
+            if let Ok(negated) = parse_number(&format!("-{}", digits)) {
+                return Expression::Literal(negated, *span);
+            }
+        }
+    }
+    Expression::UnaryOperation { operator: UnaryOperator::Negate, operand: Box::new(operand(expr, NEGATE_PRECEDENCE)), span: NodeSpan::default() }
+}
+
+//The name as the parser reads it back once printed: names that print quoted are stored with
+//the backtick Display uses.
+fn ident(name: &str, after_period: bool) -> Ident {
+    if !is_word(name) || (!after_period && lookup_keyword(name).is_some()) {
+        Ident::quoted(name, '`')
+    } else {
+        Ident::new(name)
+    }
+}
+
+impl From<i32> for Literal {
+    fn from(value: i32) -> Self {
+        Literal::Integer(value.into())
+    }
+}
+
+impl From<i64> for Literal {
+    fn from(value: i64) -> Self {
+        Literal::Integer(value.into())
+    }
+}
+
+impl From<f64> for Literal {
+    fn from(value: f64) -> Self {
+        Literal::Float(value)
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal::Boolean(value)
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::String(value.to_string())
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal::String(value)
+    }
+}
+
+impl Expression {
+    pub fn equals(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::Equals, other)
+    }
+
+    pub fn not_equals(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::NotEquals, other)
+    }
+
+    pub fn gt(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::GreaterThan, other)
+    }
+
+    pub fn gt_eq(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::GreaterThanOrEqual, other)
+    }
+
+    pub fn lt(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::LessThan, other)
+    }
+
+    pub fn lt_eq(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::LessThanOrEqual, other)
+    }
+
+    pub fn and(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::And, other)
+    }
+
+    pub fn or(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::Or, other)
+    }
+
+    pub fn plus(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::Add, other)
+    }
+
+    pub fn minus(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::Subtract, other)
+    }
+
+    pub fn times(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::Multiply, other)
+    }
+
+    pub fn divided_by(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::Divide, other)
+    }
+
+    pub fn concat(self, other: Expression) -> Expression {
+        binary(self, BinaryOperator::StringConcat, other)
+    }
+
+    pub fn like(self, pattern: Expression) -> Expression {
+        Expression::Like {
+            expr: Box::new(operand(self, PATTERN_PRECEDENCE)),
+            pattern: Box::new(operand(pattern, PATTERN_PRECEDENCE + 1)),
+            negated: false,
+            case_insensitive: false,
+            span: NodeSpan::default(),
+        }
+    }
+
+    pub fn in_list(self, list: impl IntoIterator<Item = Expression>) -> Expression {
+        Expression::InList {
+            expr: Box::new(operand(self, PATTERN_PRECEDENCE)),
+            list: list.into_iter().collect(),
+            negated: false,
+            span: NodeSpan::default(),
+        }
+    }
+
+    pub fn between(self, low: Expression, high: Expression) -> Expression {
+        Expression::Between {
+            expr: Box::new(operand(self, PATTERN_PRECEDENCE)),
+            low: Box::new(operand(low, PATTERN_PRECEDENCE + 1)),
+            high: Box::new(operand(high, PATTERN_PRECEDENCE + 1)),
+            negated: false,
+            span: NodeSpan::default(),
+        }
+    }
+
+    pub fn is_null(self) -> Expression {
+        self.is(IsTest::Null, false)
+    }
+
+    pub fn is_not_null(self) -> Expression {
+        self.is(IsTest::Null, true)
+    }
+
+    fn is(self, test: IsTest, negated: bool) -> Expression {
+        Expression::Is { expr: Box::new(operand(self, IS_PRECEDENCE)), test, negated, span: NodeSpan::default() }
+    }
+
+    /// CAST(expr AS data_type)
+    pub fn cast(self, data_type: DataType) -> Expression {
+        Expression::Cast { expr: Box::new(self), data_type, span: NodeSpan::default() }
+    }
+
+    pub fn asc(self) -> OrderByExpr {
+        OrderByExpr { expr: self, asc: Some(true), span: NodeSpan::default() }
+    }
+
+    pub fn desc(self) -> OrderByExpr {
+        OrderByExpr { expr: self, asc: Some(false), span: NodeSpan::default() }
+    }
+}
+
+impl From<Expression> for OrderByExpr {
+    fn from(expr: Expression) -> Self {
+        OrderByExpr { expr, asc: None, span: NodeSpan::default() }
+    }
+}
+
+fn binary(left: Expression, operator: BinaryOperator, right: Expression) -> Expression {
+    // Only ^ groups to the right, so only its right operand may repeat its precedence unparenthesized
+    let precedence = binary_operator_precedence(&operator);
+    let right_associative = operator == BinaryOperator::Exponent;
+    Expression::BinaryOperation {
+        left_operand: Box::new(operand(left, precedence + u8::from(right_associative))),
+        operator,
+        right_operand: Box::new(operand(right, precedence + u8::from(!right_associative))),
+        span: NodeSpan::default(),
+    }
+}
+
+// `expr` in parentheses unless it binds at least as tightly as `min_precedence`
+fn operand(expr: Expression, min_precedence: u8) -> Expression {
+    if expression_precedence(&expr) < min_precedence {
+        Expression::Grouped(Box::new(expr), NodeSpan::default())
+    } else {
+        expr
+    }
+}
+
+/// SELECT builder; selects * until columns are given.
+///
+/// ```
+/// use joel_chirayath::builder::{col, lit, Select};
+/// use joel_chirayath::{parse_sql, Statement};
+///
+/// let statement: Statement = Select::new("users").columns(["id"]).filter(col("age").gt(lit(18))).into();
+/// assert_eq!(statement.to_string(), "SELECT id FROM users WHERE age > 18");
+/// assert_eq!(parse_sql(&statement.to_string()).unwrap(), statement);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Select {
+    columns: Vec<Expression>,
+    from: TableWithJoins,
+    selection: Option<Expression>,
+    order_by: Vec<OrderByExpr>,
+}
+
+impl Select {
+    pub fn new(table: &str) -> Self {
+        Select { columns: Vec::new(), from: TableWithJoins::new(table_factor(table)), selection: None, order_by: Vec::new() }
+    }
+
+    /// Gives `alias` to the table added last: the one passed to new, or that of the latest join
+    pub fn alias(mut self, alias: &str) -> Self {
+        let table = match self.from.joins.last_mut() {
+            Some(join) => &mut join.relation,
+            None => &mut self.from.relation,
+        };
+        table.alias = Some(ident(alias, false));
+        self
+    }
+
+    /// Adds JOIN `table` ON `condition`
+    pub fn join(self, table: &str, condition: Expression) -> Self {
+        self.add_join(table, JoinOperator::Inner(JoinConstraint::On(condition)))
+    }
+
+    /// Adds LEFT JOIN `table` ON `condition`
+    pub fn left_join(self, table: &str, condition: Expression) -> Self {
+        self.add_join(table, JoinOperator::LeftOuter(JoinConstraint::On(condition)))
+    }
+
+    fn add_join(mut self, table: &str, join_operator: JoinOperator) -> Self {
+        self.from.joins.push(Join { relation: table_factor(table), join_operator, span: NodeSpan::default() });
+        self
+    }
+
+    /// Adds columns by name
+    pub fn columns<S: AsRef<str>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.columns.extend(names.into_iter().map(|name| col(name.as_ref())));
+        self
+    }
+
+    /// Adds a column that is any expression
+    pub fn column(mut self, expr: Expression) -> Self {
+        self.columns.push(expr);
+        self
+    }
+
+    /// Sets the WHERE condition; a second call is ANDed with the first
+    pub fn filter(mut self, condition: Expression) -> Self {
+        self.selection = Some(match self.selection.take() {
+            Some(existing) => existing.and(condition),
+            None => condition,
+        });
+        self
+    }
+
+    pub fn order_by(mut self, item: impl Into<OrderByExpr>) -> Self {
+        self.order_by.push(item.into());
+        self
+    }
+}
+
+fn table_factor(name: &str) -> TableFactor {
+    TableFactor::new(ident(name, false))
+}
+
+impl From<Select> for Statement {
+    fn from(select: Select) -> Self {
+        let columns = if select.columns.is_empty() { vec![Expression::Wildcard(NodeSpan::default())] } else { select.columns };
+        Statement::Select {
+            columns,
+            from: Box::new(select.from),
+            selection: select.selection,
+            order_by: if select.order_by.is_empty() { None } else { Some(select.order_by) },
+            span: NodeSpan::default(),
+        }
+    }
+}
+
+/// INSERT builder
+#[derive(Debug, Clone, PartialEq)]
+pub struct Insert {
+    or: Option<ConflictAction>,
+    table_name: Ident,
+    columns: Vec<Ident>,
+    values: Vec<Expression>,
+}
+
+impl Insert {
+    pub fn into(table: &str) -> Self {
+        Insert { or: None, table_name: ident(table, false), columns: Vec::new(), values: Vec::new() }
+    }
+
+    /// INSERT OR `action` INTO ..., as SQLite writes it
+    pub fn or(mut self, action: ConflictAction) -> Self {
+        self.or = Some(action);
+        self
+    }
+
+    pub fn columns<S: AsRef<str>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.columns.extend(names.into_iter().map(|name| ident(name.as_ref(), false)));
+        self
+    }
+
+    pub fn values(mut self, values: impl IntoIterator<Item = Expression>) -> Self {
+        self.values.extend(values);
+        self
+    }
+}
+
+impl From<Insert> for Statement {
+    fn from(insert: Insert) -> Self {
+        Statement::Insert { or: insert.or, table_name: insert.table_name, columns: insert.columns, values: insert.values, span: NodeSpan::default() }
+    }
+}
+
+/// CREATE TABLE builder
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateTable {
+    table_name: Ident,
+    columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+}
+
+impl CreateTable {
+    pub fn new(table: &str) -> Self {
+        CreateTable { table_name: ident(table, false), columns: Vec::new(), constraints: Vec::new() }
+    }
+
+    pub fn column(mut self, column: ColumnDef) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    pub fn constraint(mut self, constraint: TableConstraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+}
+
+impl From<CreateTable> for Statement {
+    fn from(create: CreateTable) -> Self {
+        Statement::CreateTable {
+            table_name: create.table_name,
+            columns: create.columns,
+            constraints: create.constraints,
+            options: Vec::new(),
+            span: NodeSpan::default(),
+        }
+    }
+}
+
+impl ColumnDef {
+    pub fn new(name: &str, data_type: DataType) -> Self {
+        ColumnDef { name: ident(name, false), data_type, options: Vec::new(), span: NodeSpan::default() }
+    }
+
+    pub fn not_null(self) -> Self {
+        self.option(ColumnOption::NotNull)
+    }
+
+    pub fn primary_key(self) -> Self {
+        self.option(ColumnOption::PrimaryKey)
+    }
+
+    pub fn unique(self) -> Self {
+        self.option(ColumnOption::Unique)
+    }
+
+    pub fn default_value(self, value: Expression) -> Self {
+        self.option(ColumnOption::Default(value))
+    }
+
+    pub fn check(self, condition: Expression) -> Self {
+        self.option(ColumnOption::Check(condition))
+    }
+
+    pub fn option(mut self, option: ColumnOption) -> Self {
+        self.options.push(option);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sql;
+
+    // The statement prints as `sql`, which parses back to the same tree
+    fn assert_prints(statement: impl Into<Statement>, sql: &str) {
+        let statement = statement.into();
+        assert_eq!(statement.to_string(), sql);
+        assert_eq!(parse_sql(sql).unwrap(), statement);
+    }
+
+    #[test]
+    fn built_statements_print_as_sql() {
+        assert_prints(Select::new("users"), "SELECT * FROM users");
+        assert_prints(
+            Select::new("users")
+                .columns(["id", "u.name"])
+                .column(func("count", [Expression::Wildcard(NodeSpan::default())]))
+                .filter(col("age").gt(lit(18)))
+                .filter(col("name").like(lit("A%")).or(col("name").is_null()))
+                .order_by(col("id").desc())
+                .order_by(col("name")),
+            "SELECT id, u.name, count(*) FROM users WHERE age > 18 AND (name LIKE 'A%' OR name IS NULL) ORDER BY id DESC, name",
+        );
+        assert_prints(
+            Select::new("users")
+                .alias("u")
+                .columns(["u.name", "o.total"])
+                .join("orders", col("u.id").equals(col("o.user_id")))
+                .alias("o")
+                .left_join("order", lit(true)),
+            "SELECT u.name, o.total FROM users AS u JOIN orders AS o ON u.id = o.user_id LEFT JOIN `order` ON TRUE",
+        );
+        assert_prints(
+            Insert::into("t").columns(["a", "b"]).values([lit(1), lit("it's")]),
+            "INSERT INTO t (a, b) VALUES (1, 'it''s')",
+        );
+        assert_prints(
+            Insert::into("t").or(ConflictAction::Replace).columns(["a"]).values([lit(1)]),
+            "INSERT OR REPLACE INTO t (a) VALUES (1)",
+        );
+        assert_prints(
+            CreateTable::new("t")
+                .column(ColumnDef::new("id", DataType::Integer { width: IntegerWidth::Big, unsigned: false }).primary_key())
+                .column(ColumnDef::new("name", DataType::Text).not_null().default_value(lit("x")).check(col("name").not_equals(lit(""))))
+                .constraint(TableConstraint::Unique { name: None, columns: vec![Ident::new("name")], span: NodeSpan::default() }),
+            "CREATE TABLE t (id BIGINT PRIMARY KEY, name TEXT NOT NULL DEFAULT 'x' CHECK (name <> ''), UNIQUE (name))",
+        );
+    }
+
+    #[test]
+    fn built_names_read_back_as_built() {
+        assert_prints(Select::new("t").columns(["order", "Key", "select", "my col", "t.from"]), "SELECT `order`, `Key`, `select`, `my col`, t.from FROM t");
+        assert_prints(Insert::into("where").columns(["value"]).values([lit(1)]), "INSERT INTO `where` (value) VALUES (1)");
+    }
+
+    #[test]
+    fn negated_numbers_are_literals() {
+        let cases = [
+            (negate(lit(1)), "-1"),
+            (negate(lit(1.5)), "-1.5"),
+            (negate(lit(-1)), "- -1"),
+            (negate(Expression::Literal(Literal::Decimal("1e400".to_string()), NodeSpan::default())), "-1e400"),
+            (negate(lit(i64::MAX).plus(lit(1))), "-(9223372036854775807 + 1)"),
+        ];
+        assert_eq!(negate(lit(1)), lit(-1));
+        for (expr, sql) in cases {
+            assert_prints(Select::new("t").column(expr), &format!("SELECT {} FROM t", sql));
+        }
+    }
+
+    #[test]
+    fn non_finite_floats_print_as_casts() {
+        for (value, name) in [(f64::INFINITY, "Infinity"), (f64::NEG_INFINITY, "-Infinity"), (f64::NAN, "NaN")] {
+            assert_prints(Select::new("t").column(lit(value)), &format!("SELECT CAST('{}' AS DOUBLE PRECISION) FROM t", name));
+        }
+    }
+
+    #[test]
+    fn operands_are_parenthesized_where_precedence_needs_it() {
+        let cases = [
+            (col("a").plus(lit(1)).times(lit(2)), "(a + 1) * 2"),
+            (col("a").times(lit(2)).plus(lit(1)), "a * 2 + 1"),
+            (col("a").minus(col("b").minus(col("c"))), "a - (b - c)"),
+            (col("a").minus(col("b")).minus(col("c")), "a - b - c"),
+            (lit(2).plus(lit(0)).times(lit(3).times(lit(4))), "(2 + 0) * (3 * 4)"),
+            (not(col("a").equals(lit(1)).or(col("b"))), "NOT (a = 1 OR b)"),
+            (negate(col("a").plus(col("b"))), "-(a + b)"),
+            (col("a").between(lit(1), lit(2).plus(lit(3))).and(col("c").in_list([lit(1)])), "a BETWEEN 1 AND 2 + 3 AND c IN (1)"),
+            (col("a").and(col("b")).is_not_null(), "(a AND b) IS NOT NULL"),
+        ];
+        for (expr, sql) in cases {
+            let statement: Statement = Select::new("t").column(expr).into();
+            assert_prints(statement, &format!("SELECT {} FROM t", sql));
+        }
+    }
+}
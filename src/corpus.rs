@@ -0,0 +1,135 @@
+// Corpus runner: parses every statement found in sqllogictest files (.test, .slt) and
+// plain .sql dumps, then reports how many statements of each kind parsed successfully.
+//
+// Usage: cargo run -- --corpus <file or directory>...
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use joel_chirayath::parse_sql;
+use joel_chirayath::tokenizer::{split_statements, Token, Tokenizer};
+
+#[derive(Default)]
+struct Tally {
+    total: usize,
+    parsed: usize,
+}
+
+/// Runs the corpus over `paths` and prints a per-statement-type summary.
+/// Returns false when no statements were found or a file could not be read.
+pub fn run(paths: &[String]) -> bool {
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(Path::new(path), &mut files);
+    }
+    files.sort();
+
+    let mut tallies: BTreeMap<String, Tally> = BTreeMap::new();
+    let mut readable = true;
+    for file in &files {
+        let contents = match fs::read_to_string(file) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("Could not read {}: {}", file.display(), e);
+                readable = false;
+                continue;
+            }
+        };
+        let statements = if is_sqllogictest(file) {
+            sqllogictest_statements(&contents)
+        } else {
+            split_statements(&contents).into_iter().map(str::to_string).collect()
+        };
+        for sql in statements {
+            let tally = tallies.entry(statement_kind(&sql)).or_default();
+            tally.total += 1;
+            if parse_sql(&sql).is_ok() {
+                tally.parsed += 1;
+            }
+        }
+    }
+
+    print_summary(files.len(), &tallies);
+    readable && tallies.values().any(|tally| tally.total > 0)
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                collect_files(&entry.path(), files);
+            }
+        }
+    } else if matches!(path.extension().and_then(|e| e.to_str()), Some("sql" | "test" | "slt")) {
+        files.push(path.to_path_buf());
+    }
+}
+
+fn is_sqllogictest(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("test" | "slt"))
+}
+
+// In sqllogictest files, SQL follows a `statement ...` or `query ...` line and runs until a
+// blank line, or until `----` for queries (the expected results are skipped).
+fn sqllogictest_statements(contents: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if !(line.starts_with("statement") || line.starts_with("query")) {
+            continue;
+        }
+        let mut sql = Vec::new();
+        for body in lines.by_ref() {
+            if body.trim().is_empty() || body.trim() == "----" {
+                break;
+            }
+            sql.push(body);
+        }
+        if !sql.is_empty() {
+            statements.push(sql.join("\n"));
+        }
+    }
+    statements
+}
+
+// The statement's leading word, e.g. SELECT or CREATE, after any comments.
+fn statement_kind(sql: &str) -> String {
+    match Tokenizer::new(sql).next_token() {
+        Token::Keyword(keyword) => keyword.as_str().to_string(),
+        Token::Identifier(word) => word.to_uppercase(),
+        _ => String::new(),
+    }
+}
+
+fn print_summary(file_count: usize, tallies: &BTreeMap<String, Tally>) {
+    println!("Parsed {} file(s)\n", file_count);
+    println!("{:<12} {:>8} {:>8} {:>8}", "statement", "total", "parsed", "rate");
+    let mut total = Tally::default();
+    for (kind, tally) in tallies {
+        println!("{:<12} {:>8} {:>8} {:>7.1}%", kind, tally.total, tally.parsed, rate(tally));
+        total.total += tally.total;
+        total.parsed += tally.parsed;
+    }
+    println!("{:<12} {:>8} {:>8} {:>7.1}%", "ALL", total.total, total.parsed, rate(&total));
+}
+
+fn rate(tally: &Tally) -> f64 {
+    if tally.total == 0 {
+        0.0
+    } else {
+        tally.parsed as f64 * 100.0 / tally.total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqllogictest_blocks_become_statements() {
+        let contents = "# comment\nstatement ok\nCREATE TABLE t (a INT)\n\nquery I\nSELECT a\nFROM t\n----\n1\n\nhash-threshold 8\n";
+        assert_eq!(sqllogictest_statements(contents), ["CREATE TABLE t (a INT)", "SELECT a\nFROM t"]);
+    }
+}
@@ -0,0 +1,321 @@
+// Pretty-printer for parsed statements, built on the SQL that Display prints for each node.
+use crate::ast::{BinaryOperator, Expression, NodeSpan, Statement};
+use crate::tokenizer::{Token, Tokenizer};
+use crate::{parse_script, Diagnostic};
+
+/// How keywords are written in formatted output
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum KeywordCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+/// Where the commas go when a list is broken over several lines
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CommaStyle {
+    /// `a,` at the end of each line
+    #[default]
+    Trailing,
+    /// `, b` at the start of each line after the first
+    Leading,
+}
+
+/// Layout settings for format_statement and format_sql
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatOptions {
+    pub keyword_case: KeywordCase,
+    /// Spaces per level of indentation
+    pub indent: usize,
+    /// A clause longer than this, in characters, is broken over several lines
+    pub max_line_length: usize,
+    pub comma_style: CommaStyle,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            keyword_case: KeywordCase::Upper,
+            indent: 4,
+            max_line_length: 80,
+            comma_style: CommaStyle::Trailing,
+        }
+    }
+}
+
+/// Formats one statement. It stays on one line if it fits; otherwise each clause starts a line
+/// and lists that do not fit are broken one item per line.
+pub fn format_statement(statement: &Statement, options: &FormatOptions) -> String {
+    let formatter = Formatter { options };
+    let text = statement.to_string();
+    let text = if width(&text) <= options.max_line_length { text } else { formatter.layout(statement) };
+    recase(&text, options.keyword_case)
+}
+
+/// Formats every statement in a script, each ending with ';' and separated by a blank line.
+/// Comments are kept, but those inside a statement are moved to the lines before it.
+pub fn format_sql(sql: &str, options: &FormatOptions) -> Result<String, Vec<Diagnostic>> {
+    let statements = parse_script(sql)?;
+    let comments = comments_by_statement(sql);
+    let mut blocks = Vec::new();
+    for (i, statement) in statements.iter().enumerate() {
+        let mut block = String::new();
+        for comment in comments.get(i).into_iter().flatten() {
+            block.push_str(comment);
+            block.push('\n');
+        }
+        block.push_str(&format_statement(statement, options));
+        block.push(';');
+        blocks.push(block);
+    }
+    // Comments after the last statement
+    let trailing: Vec<&str> = comments.iter().skip(statements.len()).flatten().map(String::as_str).collect();
+    if !trailing.is_empty() {
+        blocks.push(trailing.join("\n"));
+    }
+    let mut formatted = blocks.join("\n\n");
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+    Ok(formatted)
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+}
+
+impl Formatter<'_> {
+    fn layout(&self, statement: &Statement) -> String {
+        match statement {
+            Statement::Select { columns, from, selection, order_by, .. } => {
+                let mut lines = vec![self.clause("SELECT", columns)];
+                // Each JOIN starts a line of its own, like a clause
+                lines.push(format!("FROM {}", from.relation));
+                lines.extend(from.joins.iter().map(ToString::to_string));
+                if let Some(selection) = selection {
+                    lines.push(self.condition("WHERE", selection));
+                }
+                if let Some(order_by) = order_by.as_ref().filter(|items| !items.is_empty()) {
+                    lines.push(self.clause("ORDER BY", order_by));
+                }
+                lines.join("\n")
+            }
+            Statement::CreateTable { table_name, columns, constraints, options, .. } => {
+                let mut definitions: Vec<String> = columns.iter().map(ToString::to_string).collect();
+                definitions.extend(constraints.iter().map(ToString::to_string));
+                // The options are what Display prints after the closing parenthesis
+                let empty = Statement::CreateTable {
+                    table_name: table_name.clone(),
+                    columns: Vec::new(),
+                    constraints: Vec::new(),
+                    options: options.clone(),
+                    span: NodeSpan::default(),
+                };
+                let empty = empty.to_string();
+                let (head, tail) = empty.split_once("()").unwrap_or((&empty, ""));
+                format!("{}(\n{}\n){}", head, self.list(&definitions), tail)
+            }
+            Statement::Insert { or, table_name, columns, values, .. } => {
+                let or = or.as_ref().map(|action| format!("OR {} ", action)).unwrap_or_default();
+                format!(
+                    "INSERT {}INTO {} {}\nVALUES {}",
+                    or,
+                    table_name,
+                    self.parenthesized(columns),
+                    self.parenthesized(values)
+                )
+            }
+        }
+    }
+
+    // `keyword items` on one line if it fits, else the keyword alone followed by one item per line
+    fn clause<T: ToString>(&self, keyword: &str, items: &[T]) -> String {
+        let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+        let line = format!("{} {}", keyword, items.join(", "));
+        if width(&line) <= self.options.max_line_length {
+            return line;
+        }
+        format!("{}\n{}", keyword, self.list(&items))
+    }
+
+    // `(items)` if it fits on the line, else one item per line between the parentheses
+    fn parenthesized<T: ToString>(&self, items: &[T]) -> String {
+        let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+        let line = format!("({})", items.join(", "));
+        if width(&line) <= self.options.max_line_length {
+            return line;
+        }
+        format!("(\n{}\n)", self.list(&items))
+    }
+
+    // A long condition is broken before each AND or OR that is not inside parentheses
+    fn condition(&self, keyword: &str, condition: &Expression) -> String {
+        let line = format!("{} {}", keyword, condition);
+        if width(&line) <= self.options.max_line_length {
+            return line;
+        }
+        let mut terms = Vec::new();
+        let first = split_conjunctions(condition, &mut terms);
+        let mut text = format!("{} {}", keyword, first);
+        for (operator, term) in terms {
+            text.push_str(&format!("\n{}{} {}", self.indentation(), operator, term));
+        }
+        text
+    }
+
+    // Indented items, one per line, with commas placed by the comma style
+    fn list(&self, items: &[String]) -> String {
+        let indentation = self.indentation();
+        let lines: Vec<String> = match self.options.comma_style {
+            CommaStyle::Trailing => items.iter().map(|item| format!("{}{}", indentation, item)).collect(),
+            CommaStyle::Leading => {
+                // The comma hangs to the left so the items stay aligned
+                let hanging = " ".repeat(self.options.indent.saturating_sub(2));
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| if i == 0 { format!("{}{}", indentation, item) } else { format!("{}, {}", hanging, item) })
+                    .collect()
+            }
+        };
+        match self.options.comma_style {
+            CommaStyle::Trailing => lines.join(",\n"),
+            CommaStyle::Leading => lines.join("\n"),
+        }
+    }
+
+    fn indentation(&self) -> String {
+        " ".repeat(self.options.indent)
+    }
+}
+
+// Flattens a left-leaning chain of AND and OR into its first operand, pushing each following
+// operator and operand onto `terms`
+fn split_conjunctions<'a>(expr: &'a Expression, terms: &mut Vec<(&'a BinaryOperator, &'a Expression)>) -> &'a Expression {
+    match expr {
+        Expression::BinaryOperation { left_operand, operator: operator @ (BinaryOperator::And | BinaryOperator::Or), right_operand, .. } => {
+            let first = split_conjunctions(left_operand, terms);
+            terms.push((operator, right_operand));
+            first
+        }
+        _ => expr,
+    }
+}
+
+// The comments of each statement in `sql`, in order, followed by those after the last statement
+fn comments_by_statement(sql: &str) -> Vec<Vec<String>> {
+    let mut groups = vec![Vec::new()];
+    let mut in_statement = false;
+    let mut tokenizer = Tokenizer::new(sql).with_trivia();
+    loop {
+        match tokenizer.next_token() {
+            Token::Eof | Token::Invalid(_) => break,
+            Token::LineComment(text) | Token::BlockComment(text) => {
+                groups.last_mut().unwrap().push(text.trim_end().to_string());
+            }
+            Token::Whitespace(_) => {}
+            Token::Semicolon => {
+                if in_statement {
+                    groups.push(Vec::new());
+                    in_statement = false;
+                }
+            }
+            _ => in_statement = true,
+        }
+    }
+    groups
+}
+
+// Words Display prints in upper case that the tokenizer reads as identifiers, such as type
+// names and the words of CAST(... AS ...) or AT TIME ZONE
+const SQL_WORDS: &[&str] = &[
+    "ACTION", "ALWAYS", "AT", "AUTO_INCREMENT", "BIGINT", "BIGSERIAL", "BLOB", "BYTEA", "CASCADE", "CAST",
+    "CHAR", "DATE", "DAY", "DECIMAL", "DELETE", "DOUBLE", "ENUM", "FILTER", "FLOAT", "GENERATED", "HOUR",
+    "IDENTITY", "INCREMENT", "JSON", "JSONB", "MEDIUMINT", "MINUTE", "MONTH", "NO", "PARTITION", "POSITION",
+    "PRECISION", "REAL", "RESTRICT", "ROW", "SECOND", "SERIAL", "SET", "SIMILAR", "SMALLINT", "SMALLSERIAL",
+    "START", "STORED", "STRUCT", "TEXT", "TIME", "TIMESTAMP", "TIMESTAMPTZ", "TINYINT", "TO", "TRIM",
+    "UNKNOWN", "UNSIGNED", "UPDATE", "UUID", "VARBINARY", "BINARY", "VIRTUAL", "WITH", "YEAR", "ZONE",
+];
+
+// Rewrites keywords and the SQL_WORDS in `text` in the given case. Other identifiers, strings
+// and comments are left as they are.
+fn recase(text: &str, case: KeywordCase) -> String {
+    if case == KeywordCase::Upper {
+        return text.to_string();
+    }
+    let mut tokenizer = Tokenizer::new(text).with_trivia();
+    let mut recased = String::with_capacity(text.len());
+    loop {
+        let spanned = tokenizer.next_spanned_token();
+        let source = &text[spanned.span.start.offset..spanned.span.end.offset];
+        match spanned.token {
+            Token::Eof => break,
+            Token::Keyword(_) => recased.push_str(&source.to_lowercase()),
+            Token::Identifier(word) if SQL_WORDS.contains(&word.as_str()) => recased.push_str(&source.to_lowercase()),
+            _ => recased.push_str(source),
+        }
+    }
+    recased
+}
+
+fn width(line: &str) -> usize {
+    line.lines().map(|line| line.chars().count()).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sql;
+
+    fn format(sql: &str, options: &FormatOptions) -> String {
+        format_statement(&parse_sql(sql).unwrap(), options)
+    }
+
+    #[test]
+    fn statements_that_fit_stay_on_one_line() {
+        let options = FormatOptions::default();
+        assert_eq!(format("select a,b from t where a=1", &options), "SELECT a, b FROM t WHERE a = 1");
+        let lower = FormatOptions { keyword_case: KeywordCase::Lower, ..options };
+        assert_eq!(
+            format("SELECT CAST(a AS INT), 'AND' FROM Users WHERE a IS NOT NULL", &lower),
+            "select cast(a as int), 'AND' from Users where a is not null"
+        );
+    }
+
+    #[test]
+    fn long_statements_are_broken_by_clause() {
+        let narrow = FormatOptions { max_line_length: 30, ..FormatOptions::default() };
+        assert_eq!(
+            format("SELECT id, name, email, created_at FROM users WHERE id > 10 AND name LIKE 'a%' ORDER BY id", &narrow),
+            "SELECT\n    id,\n    name,\n    email,\n    created_at\nFROM users\nWHERE id > 10\n    AND name LIKE 'a%'\nORDER BY id"
+        );
+        assert_eq!(
+            format("SELECT u.name FROM users u JOIN orders o ON u.id = o.user_id WHERE o.total > 10", &narrow),
+            "SELECT u.name\nFROM users AS u\nJOIN orders AS o ON u.id = o.user_id\nWHERE o.total > 10"
+        );
+        assert_eq!(
+            format("CREATE TABLE t (id INT PRIMARY KEY, name TEXT NOT NULL) ENGINE=InnoDB", &narrow),
+            "CREATE TABLE t (\n    id INT PRIMARY KEY,\n    name TEXT NOT NULL\n) ENGINE=InnoDB"
+        );
+        assert_eq!(
+            format("INSERT INTO t (a, b) VALUES ('a long value', 'another long value')", &narrow),
+            "INSERT INTO t (a, b)\nVALUES (\n    'a long value',\n    'another long value'\n)"
+        );
+
+        let leading = FormatOptions { comma_style: CommaStyle::Leading, indent: 2, ..narrow };
+        assert_eq!(
+            format("SELECT id, name, email, created_at FROM users", &leading),
+            "SELECT\n  id\n, name\n, email\n, created_at\nFROM users"
+        );
+    }
+
+    #[test]
+    fn scripts_keep_their_comments() {
+        let sql = "-- first\nselect a from t; /* second */ select b\n-- inside\nfrom u;\n-- the end\n";
+        assert_eq!(
+            format_sql(sql, &FormatOptions::default()).unwrap(),
+            "-- first\nSELECT a FROM t;\n\n/* second */\n-- inside\nSELECT b FROM u;\n\n-- the end\n"
+        );
+        assert!(format_sql("SELECT FROM", &FormatOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// Registry of well-known SQL functions and the number of arguments they accept.
+
+pub struct BuiltinFunction 
+{
+    pub name: &'static str,
+    pub min_args: usize,
+    // None means any number of arguments from min_args upwards
+    pub max_args: Option<usize>,
+}
+
+const BUILTIN_FUNCTIONS: &[BuiltinFunction] = &[
+    BuiltinFunction { name: "COALESCE", min_args: 1, max_args: None },
+    BuiltinFunction { name: "NULLIF", min_args: 2, max_args: Some(2) },
+    BuiltinFunction { name: "GREATEST", min_args: 1, max_args: None },
+    BuiltinFunction { name: "LEAST", min_args: 1, max_args: None },
+    BuiltinFunction { name: "IFNULL", min_args: 2, max_args: Some(2) },
+];
+
+pub fn lookup_builtin(name: &str) -> Option<&'static BuiltinFunction> 
+{
+    BUILTIN_FUNCTIONS.iter().find(|f| f.name.eq_ignore_ascii_case(name))
+}
+
+// Checks a call against the registry; functions that are not registered are accepted as-is.
+pub fn check_arity(name: &str, arg_count: usize) -> Result<(), String> 
+{
+    match lookup_builtin(name) 
+    {
+        Some(function) => check_function(function, arg_count),
+        None => Ok(()),
+    }
+}
+
+fn check_function(function: &BuiltinFunction, arg_count: usize) -> Result<(), String> 
+{
+    let plural = |n: usize| if n == 1 { "argument" } else { "arguments" };
+    match function.max_args 
+    {
+        Some(max) if max == function.min_args && arg_count != max => 
+        {
+            Err(format!("{} expects {} {}, got {}", function.name, max, plural(max), arg_count))
+        }
+        Some(max) if arg_count < function.min_args || arg_count > max => 
+        {
+            Err(format!("{} expects {} to {} arguments, got {}", function.name, function.min_args, max, arg_count))
+        }
+        None if arg_count < function.min_args => 
+        {
+            let min = function.min_args;
+            Err(format!("{} expects at least {} {}, got {}", function.name, min, plural(min), arg_count))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests 
+{
+    use super::*;
+
+    #[test]
+    fn arity_errors_say_what_was_expected() 
+    {
+        assert_eq!(check_arity("nullif", 2), Ok(()));
+        assert_eq!(check_arity("NULLIF", 3), Err("NULLIF expects 2 arguments, got 3".to_string()));
+        assert_eq!(check_arity("COALESCE", 0), Err("COALESCE expects at least 1 argument, got 0".to_string()));
+        assert_eq!(check_arity("coalesce", 5), Ok(()));
+        // Unregistered functions take any number of arguments
+        assert_eq!(check_arity("my_function", 0), Ok(()));
+    }
+
+    #[test]
+    fn ranges_of_arguments_name_both_bounds() 
+    {
+        const ROUND: BuiltinFunction = BuiltinFunction { name: "ROUND", min_args: 1, max_args: Some(2) };
+        // The registry has no range yet, so this checks the message through a local entry
+        let check = |count: usize| check_function(&ROUND, count);
+        assert_eq!(check(1), Ok(()));
+        assert_eq!(check(2), Ok(()));
+        assert_eq!(check(0), Err("ROUND expects 1 to 2 arguments, got 0".to_string()));
+        assert_eq!(check(3), Err("ROUND expects 1 to 2 arguments, got 3".to_string()));
+    }
+}
@@ -0,0 +1,1151 @@
+// SQL tokenizer, parsers and AST. The REPL and the corpus runner in main.rs are built on this,
+// and other crates can depend on it the same way; parse_sql is the place to start.
+pub mod tokenizer;  // Handles breaking SQL input into tokens
+pub mod pratt;      // Handles expression parsing using Pratt parsing technique
+pub mod parser;     // Main SQL parser logic
+pub mod ast;        // Abstract Syntax Tree definitions
+pub mod formatter;  // Pretty-printing statements with configurable layout
+pub mod sexpr;      // S-expression rendering of statements
+pub mod builder;    // Fluent helpers for building statements in code
+pub mod stream;     // Lazy statement-by-statement parsing of long scripts
+pub mod analysis;   // Questions about parsed statements, such as the tables they use
+mod functions;      // Registry of builtin functions and their arity
+
+// The types most users need, so `joel_chirayath::Statement` works without knowing the modules
+pub use tokenizer::{Tokenizer, Token, Keyword, Span, Location, LexError, Dialect};
+pub use parser::SQLParser;
+pub use stream::StatementIterator;
+pub use analysis::{references, TableColumnRefs, ColumnRef, parameters, ParameterRef, ParameterStyle, Clause};
+pub use analysis::{validate, Schema, SchemaTable, SemanticError, TypeClass};
+pub use ast::*;
+
+use pratt::PrattParser;
+
+// === Begin custom ParseError definition ===
+
+// Every variant but InContext and InvalidToken ends with the Span of the source the error is
+// about. New variants may be added, so build errors with the constructors below.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    UnexpectedEnd(Span), // Input ended unexpectedly
+    Expected(Vec<String>, Option<Token>, Span), // None of the tokens that would have been valid was found
+    InvalidExpression(String, Span), // Expression syntax was invalid
+    UnknownStartOfStatement(String, Span), // Parser saw something unexpected at start
+    UnexpectedToken(Token, Span), // A completely unexpected token appeared
+    MisspelledKeyword(String, Keyword, Span), // A word where a keyword was expected, close enough to suggest it
+    InvalidToken(LexError), // The tokenizer could not read a token (stray character, unterminated string or comment)
+    InputTooLong(usize, Span), // The input is longer than Limits::max_input_length bytes
+    TooManyTokens(usize, Span), // The input has more than Limits::max_tokens tokens
+    TooDeep(usize, Span), // Expressions or types are nested more than Limits::max_depth deep
+    InContext(String, Box<ParseError>), // The error happened inside the named part of a statement
+    Nonstandard(String, Span), // Syntax outside standard SQL, which strict mode rejects
+    Unsupported(String, Dialect, Span), // An extension that the dialect being parsed does not have
+    UnbalancedDelimiter(char, Span), // A '(' or '[' never closed, or a ')' or ']' that closes nothing
+    General(String, Span), // A general error message
+}
+
+impl ParseError {
+    pub fn unexpected_end(span: Span) -> Self {
+        ParseError::UnexpectedEnd(span)
+    }
+
+    // `expected` names what would have been valid, e.g. "an identifier" or "','"
+    pub fn expected(expected: Vec<String>, found: Option<Token>, span: Span) -> Self {
+        ParseError::Expected(expected, found, span)
+    }
+
+    pub fn invalid_expression(message: impl Into<String>, span: Span) -> Self {
+        ParseError::InvalidExpression(message.into(), span)
+    }
+
+    pub fn unexpected_token(token: Token, span: Span) -> Self {
+        ParseError::UnexpectedToken(token, span)
+    }
+
+    pub fn general(message: impl Into<String>, span: Span) -> Self {
+        ParseError::General(message.into(), span)
+    }
+
+    // This error, said to have happened in `context`, e.g. "the WHERE clause of SELECT"
+    pub fn in_context(self, context: impl Into<String>) -> Self {
+        ParseError::InContext(context.into(), Box::new(self))
+    }
+
+    // A stable identifier for the kind of error, for tools that match on errors rather than
+    // on message text. Codes are never renumbered or reused; new variants get new codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::UnexpectedEnd(_) => "E0001",
+            ParseError::Expected(..) => "E0002",
+            ParseError::InvalidExpression(..) => "E0003",
+            ParseError::UnknownStartOfStatement(..) => "E0004",
+            ParseError::UnexpectedToken(..) => "E0005",
+            ParseError::MisspelledKeyword(..) => "E0006",
+            ParseError::InvalidToken(..) => "E0007",
+            ParseError::InputTooLong(..) => "E0008",
+            ParseError::TooManyTokens(..) => "E0009",
+            ParseError::General(..) => "E0010",
+            ParseError::TooDeep(..) => "E0011",
+            ParseError::Nonstandard(..) => "E0012",
+            ParseError::UnbalancedDelimiter(..) => "E0013",
+            ParseError::Unsupported(..) => "E0014",
+            // Context only helps find the error, so it keeps the code of what went wrong
+            ParseError::InContext(_, error) => error.code(),
+        }
+    }
+
+    // The error as one JSON object with its code, message and span, e.g.
+    // {"code":"E0002","message":"...","span":{"start":{"line":1,"column":10,"offset":9},"end":{...}}}
+    pub fn to_json(&self) -> String {
+        to_json(self.code(), &self.to_string(), self.span())
+    }
+
+    // Where in the source the error is
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedEnd(span)
+            | ParseError::Expected(_, _, span)
+            | ParseError::InvalidExpression(_, span)
+            | ParseError::UnknownStartOfStatement(_, span)
+            | ParseError::UnexpectedToken(_, span)
+            | ParseError::MisspelledKeyword(_, _, span)
+            | ParseError::InputTooLong(_, span)
+            | ParseError::TooManyTokens(_, span)
+            | ParseError::TooDeep(_, span)
+            | ParseError::Nonstandard(_, span)
+            | ParseError::Unsupported(_, _, span)
+            | ParseError::UnbalancedDelimiter(_, span)
+            | ParseError::General(_, span) => *span,
+            ParseError::InvalidToken(error) => error.span,
+            ParseError::InContext(_, error) => error.span(),
+        }
+    }
+
+    // The error with every layer of context taken off, for matching on what went wrong
+    pub fn innermost(&self) -> &ParseError {
+        match self {
+            ParseError::InContext(_, error) => error.innermost(),
+            error => error,
+        }
+    }
+
+    // The message, then the source line the error is on with the span underlined:
+    //
+    //   Unexpected word FORM, did you mean FROM? at line 1, column 10
+    //     SELECT a FORM t
+    //              ^^^^
+    pub fn render(&self, source: &str) -> String {
+        self.render_with(source, Verbosity::Normal)
+    }
+
+    pub fn render_with(&self, source: &str, verbosity: Verbosity) -> String {
+        let text = DiagnosticText {
+            code: self.code(),
+            message: self.to_string(),
+            span: self.span(),
+            hint: self.hint(),
+            example: self.example(),
+        };
+        text.render(source, verbosity)
+    }
+
+    // How to fix this kind of error, for learners
+    pub fn hint(&self) -> Option<&'static str> {
+        let hint = match self.innermost() {
+            ParseError::UnexpectedEnd(_) => "The statement stops before it is complete; look for a missing clause or closing ')'.",
+            ParseError::Expected(..) => "Put one of the expected items here, or check that nothing before it is missing.",
+            ParseError::InvalidExpression(..) => "Check the operators and the number of arguments in this expression.",
+            ParseError::UnknownStartOfStatement(..) => "Statements start with SELECT, CREATE TABLE or INSERT INTO.",
+            ParseError::UnexpectedToken(..) => "Remove this token or check what should come before it.",
+            ParseError::MisspelledKeyword(..) => "Check the spelling of the keyword.",
+            ParseError::InvalidToken(_) => "Close strings with ', quoted names with \" and block comments with */.",
+            ParseError::InputTooLong(..) | ParseError::TooManyTokens(..) => "Split the input into smaller statements.",
+            ParseError::UnbalancedDelimiter(..) => "Every '(' needs a matching ')' and every '[' a matching ']'.",
+            ParseError::Nonstandard(..) => "Use the standard SQL form, or parse in lenient mode to accept it with a warning.",
+            ParseError::Unsupported(..) => "Use this dialect's form, or parse with the dialect the statement was written for.",
+            ParseError::TooDeep(..) => "Nest parentheses, CASE and function calls less deeply, e.g. by splitting the expression.",
+            ParseError::General(..) | ParseError::InContext(..) => return None,
+        };
+        Some(hint)
+    }
+
+    // Valid SQL showing the construct the error is about
+    pub fn example(&self) -> Option<&'static str> {
+        let example = match self.innermost() {
+            ParseError::UnknownStartOfStatement(..) => "SELECT name FROM users WHERE id = 1",
+            ParseError::MisspelledKeyword(_, keyword, _) => match keyword {
+                Keyword::Create | Keyword::Table => "CREATE TABLE users (id INT PRIMARY KEY, name TEXT)",
+                Keyword::Insert | Keyword::Into | Keyword::Values => "INSERT INTO users (id, name) VALUES (1, 'Ada')",
+                _ => "SELECT name FROM users WHERE id = 1 ORDER BY name",
+            },
+            ParseError::InvalidToken(_) => "SELECT 'text', \"Quoted Name\" /* comment */ FROM t",
+            ParseError::InvalidExpression(..) => "SELECT price * 2, COALESCE(a, b) FROM items",
+            _ => return None,
+        };
+        Some(example)
+    }
+}
+
+// How much rendering says about an error or warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    // One line with position, code, message and the source near it, for scripts and CI logs:
+    //   1:10: E0006 Unexpected word FORM, did you mean FROM? near "SELECT a FORM t"
+    Terse,
+    // The message and the underlined source line, as render() gives
+    #[default]
+    Normal,
+    // Normal, then a hint and an example, for the REPL and people learning SQL
+    Verbose,
+}
+
+// What the renderings of errors and warnings are made from
+struct DiagnosticText {
+    code: &'static str,
+    message: String,
+    span: Span,
+    hint: Option<&'static str>,
+    example: Option<&'static str>,
+}
+
+impl DiagnosticText {
+    fn render(&self, source: &str, verbosity: Verbosity) -> String {
+        let start = self.span.start;
+        match verbosity {
+            Verbosity::Terse => {
+                let mut out = format!("{}:{}: {} {}", start.line, start.column, self.code, self.message);
+                if let Some(excerpt) = Excerpt::new(source, self.span) {
+                    out.push_str(&format!(" near \"{}\"", excerpt.text.trim()));
+                }
+                out
+            }
+            Verbosity::Normal => render(&self.message, self.span, source),
+            Verbosity::Verbose => {
+                let mut out = format!("[{}] {}", self.code, render(&self.message, self.span, source));
+                if let Some(hint) = self.hint {
+                    out.push_str(&format!("\n  help: {}", hint));
+                }
+                if let Some(example) = self.example {
+                    out.push_str(&format!("\n  for example: {}", example));
+                }
+                out
+            }
+        }
+    }
+}
+
+// `message` with its position, then the source line `span` is on with the span underlined
+fn render(message: &str, span: Span, source: &str) -> String {
+    let mut out = format!("{} at line {}, column {}", message, span.start.line, span.start.column);
+    let Some(excerpt) = Excerpt::new(source, span) else {
+        return out;
+    };
+    // Keep tabs in the padding so the carets line up under the same characters
+    let padding: String = excerpt.text.chars().take(excerpt.before).map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+    out.push_str(&format!("\n  {}\n  {}{}", excerpt.text, padding, "^".repeat(excerpt.width.max(1))));
+    out
+}
+
+// Characters of source shown on each side of what a diagnostic is about
+const EXCERPT_CONTEXT: usize = 20;
+
+// The part of the line a span starts on that is shown with a diagnostic: the span and up to
+// EXCERPT_CONTEXT characters either side, with "..." where the line was cut. Long lines, such
+// as a whole script piped in on one line, then still show what was near the problem.
+struct Excerpt {
+    text: String,
+    before: usize, // Characters of `text` before the span
+    width: usize, // Characters of the span in `text`
+}
+
+impl Excerpt {
+    fn new(source: &str, span: Span) -> Option<Excerpt> {
+        let Span { start, end } = span;
+        let line: Vec<char> = source.lines().nth(start.line.saturating_sub(1))?.chars().collect();
+        let before = start.column.saturating_sub(1).min(line.len());
+        let width = if end.line == start.line {
+            end.column.saturating_sub(start.column)
+        } else {
+            line.len() - before
+        };
+        let from = before.saturating_sub(EXCERPT_CONTEXT);
+        let to = (before + width + EXCERPT_CONTEXT).min(line.len());
+        let mut text = if from > 0 { "...".to_string() } else { String::new() };
+        let prefix = text.len();
+        text.extend(&line[from..to]);
+        if to < line.len() {
+            text.push_str("...");
+        }
+        Some(Excerpt { text, before: prefix + before - from, width })
+    }
+}
+
+// The JSON object shared by errors and warnings
+fn to_json(code: &str, message: &str, span: Span) -> String {
+    let location = |l: tokenizer::Location| {
+        format!("{{\"line\":{},\"column\":{},\"offset\":{}}}", l.line, l.column, l.offset)
+    };
+    format!(
+        "{{\"code\":\"{}\",\"message\":{},\"span\":{{\"start\":{},\"end\":{}}}}}",
+        code,
+        json_string(message),
+        location(span.start),
+        location(span.end)
+    )
+}
+
+// `text` as a quoted JSON string
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Implementing error messages
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd(_) => write!(f, "Unexpected end of input"),
+            ParseError::InvalidExpression(e, _) => write!(f, "Invalid expression: {}", e),
+            ParseError::UnknownStartOfStatement(t, _) => write!(f, "Unknown start of statement: {}", t),
+            ParseError::Expected(expected, found, _) => {
+                let found = found.as_ref().map_or("end of input".to_string(), Token::to_string);
+                match expected.split_last() {
+                    None => write!(f, "Unexpected {}", found),
+                    Some((last, [])) => write!(f, "Expected {}, but found {}", last, found),
+                    Some((last, rest)) => write!(f, "Expected {} or {}, but found {}", rest.join(", "), last, found),
+                }
+            }
+            ParseError::UnexpectedToken(token, _) => write!(f, "Unexpected {}", token),
+            ParseError::MisspelledKeyword(word, keyword, _) => write!(f, "Unexpected word {}, did you mean {}?", word, keyword),
+            ParseError::InvalidToken(error) => write!(f, "Invalid or unterminated token starting with '{}'", error.character),
+            ParseError::InputTooLong(max, _) => write!(f, "Input is longer than the limit of {} bytes", max),
+            ParseError::TooManyTokens(max, _) => write!(f, "Input has more than the limit of {} tokens", max),
+            ParseError::TooDeep(max, _) => write!(f, "Input is nested more than the limit of {} levels deep", max),
+            ParseError::General(e, _) => write!(f, "Error: {}", e),
+            ParseError::InContext(context, error) => write!(f, "{}, in {}", error, context),
+            ParseError::Nonstandard(what, _) => write!(f, "Not allowed in strict mode: {}", what),
+            ParseError::Unsupported(what, dialect, _) => write!(f, "Not supported in {}: {}", dialect, what),
+            ParseError::UnbalancedDelimiter(c @ ('(' | '['), _) => write!(f, "'{}' is never closed", c),
+            ParseError::UnbalancedDelimiter(c, _) => {
+                write!(f, "'{}' has no opening '{}'", c, if *c == ']' { '[' } else { '(' })
+            }
+        }
+    }
+}
+
+// Implement standard error 
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::InContext(_, error) => Some(error.as_ref()),
+            ParseError::InvalidToken(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<LexError> for ParseError {
+    fn from(error: LexError) -> Self {
+        ParseError::InvalidToken(error)
+    }
+}
+
+// === End custom ParseError definition ===
+
+// Things the parser accepted but that a linter may want to flag. They are returned alongside
+// the statement and never stop a parse. Like ParseError, every variant ends with its Span.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseWarning {
+    KeywordAsIdentifier(Keyword, Span), // A non-reserved keyword used as a name
+    NonstandardQuote(char, Span), // An identifier quoted with ` or [ instead of "
+    MissingComma(Span), // Column definitions or constraints not separated by ','
+    NonstandardSyntax(String, Span), // A vendor extension such as AUTO_INCREMENT or a MySQL table option
+}
+
+impl ParseWarning {
+    // Stable identifiers, numbered separately from the error codes
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseWarning::KeywordAsIdentifier(..) => "W0001",
+            ParseWarning::NonstandardQuote(..) => "W0002",
+            ParseWarning::MissingComma(_) => "W0003",
+            ParseWarning::NonstandardSyntax(..) => "W0004",
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            ParseWarning::KeywordAsIdentifier(_, span)
+            | ParseWarning::NonstandardQuote(_, span)
+            | ParseWarning::MissingComma(span)
+            | ParseWarning::NonstandardSyntax(_, span) => *span,
+        }
+    }
+
+    // The same JSON object as ParseError::to_json, with a W code
+    pub fn to_json(&self) -> String {
+        to_json(self.code(), &self.to_string(), self.span())
+    }
+
+    // The message and the underlined source line, as for ParseError::render
+    pub fn render(&self, source: &str) -> String {
+        self.render_with(source, Verbosity::Normal)
+    }
+
+    pub fn render_with(&self, source: &str, verbosity: Verbosity) -> String {
+        let text = DiagnosticText {
+            code: self.code(),
+            message: self.to_string(),
+            span: self.span(),
+            hint: Some(self.hint()),
+            example: None,
+        };
+        text.render(source, verbosity)
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ParseWarning::KeywordAsIdentifier(..) => "Quote the name, e.g. \"key\", so it cannot be read as a keyword.",
+            ParseWarning::NonstandardQuote(..) => "Standard SQL quotes names with double quotes, which every database accepts.",
+            ParseWarning::MissingComma(_) => "Separate column definitions and constraints with ','.",
+            ParseWarning::NonstandardSyntax(..) => "Other databases may reject this; strict mode reports it as an error.",
+        }
+    }
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseWarning::KeywordAsIdentifier(keyword, _) => write!(f, "Keyword {} used as an identifier", keyword),
+            ParseWarning::NonstandardQuote(quote, _) => write!(f, "Identifier quoted with {} instead of \"", quote),
+            ParseWarning::MissingComma(_) => write!(f, "Missing ',' before this definition"),
+            ParseWarning::NonstandardSyntax(what, _) => write!(f, "{} is not standard SQL", what),
+        }
+    }
+}
+
+// An error or a warning, for APIs that return both
+#[derive(Debug)]
+pub enum Diagnostic {
+    Error(ParseError),
+    Warning(ParseWarning),
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        matches!(self, Diagnostic::Error(_))
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Diagnostic::Error(error) => error.code(),
+            Diagnostic::Warning(warning) => warning.code(),
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Diagnostic::Error(error) => error.span(),
+            Diagnostic::Warning(warning) => warning.span(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            Diagnostic::Error(error) => error.to_json(),
+            Diagnostic::Warning(warning) => warning.to_json(),
+        }
+    }
+
+    pub fn render_with(&self, source: &str, verbosity: Verbosity) -> String {
+        match self {
+            Diagnostic::Error(error) => error.render_with(source, verbosity),
+            Diagnostic::Warning(warning) => warning.render_with(source, verbosity),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::Error(error) => error.fmt(f),
+            Diagnostic::Warning(warning) => warning.fmt(f),
+        }
+    }
+}
+
+// What the recovering APIs return: the value, even if incomplete, together with every warning
+// and error found while parsing it, in the order they were found. `value` is None only when
+// nothing could be parsed at all.
+#[derive(Debug)]
+pub struct ParseOutput<T> {
+    pub value: Option<T>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<T> ParseOutput<T> {
+    // True if the value is incomplete because parsing failed
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ParseError> {
+        self.diagnostics.iter().filter_map(|diagnostic| match diagnostic {
+            Diagnostic::Error(error) => Some(error),
+            Diagnostic::Warning(_) => None,
+        })
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ParseWarning> {
+        self.diagnostics.iter().filter_map(|diagnostic| match diagnostic {
+            Diagnostic::Warning(warning) => Some(warning),
+            Diagnostic::Error(_) => None,
+        })
+    }
+}
+
+// How the parser treats what it can read but standard SQL does not allow
+#[derive(Debug, Clone, Copy)]
+pub struct ParserOptions {
+    // The dialect to read: its identifier quotes, operators and extensions are accepted without
+    // a warning, and other dialects' extensions are errors. None reads the union of all of them.
+    pub dialect: Option<Dialect>,
+    // Reject vendor extensions (AUTO_INCREMENT, MySQL and Postgres table options, ` and [] quoted
+    // names, missing commas between definitions) instead of accepting them with a warning.
+    // With a dialect, only what that dialect does not have is rejected.
+    pub strict: bool,
+    // How deeply expressions and types may nest; parse_input_with_options uses the smaller of
+    // this and Limits::max_depth
+    pub max_depth: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions { dialect: None, strict: false, max_depth: Limits::default().max_depth }
+    }
+}
+
+// Upper bounds on what a single parse will accept, so oversized input fails with an error
+// instead of growing memory without limit
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub max_input_length: usize, // In bytes
+    pub max_tokens: usize, // Not counting the final Eof
+    // Parentheses, CASE, function calls and other expressions or types inside one another; a chain
+    // of operators such as NOT NOT a is not nesting (see PrattParser::with_max_depth). The default
+    // leaves room on a 2 MiB thread stack in unoptimized builds.
+    pub max_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits { max_input_length: 1024 * 1024, max_tokens: 100_000, max_depth: 50 }
+    }
+}
+
+/// Parses one SQL statement, the one-call entry point for other crates.
+///
+/// ```
+/// use joel_chirayath::{parse_sql, Expression, Statement};
+///
+/// let Statement::Select { columns, from, .. } = parse_sql("SELECT id FROM users").unwrap() else {
+///     panic!("not a SELECT");
+/// };
+/// assert_eq!(from.relation.name.value, "users");
+/// assert!(matches!(&columns[0], Expression::Identifier(name) if name.value == "id"));
+/// assert!(parse_sql("SELECT FROM").is_err());
+/// ```
+pub fn parse_sql(sql: &str) -> Result<Statement, ParseError> {
+    let limits = Limits::default();
+    let (tokens, spans) = read_tokens(sql, &limits, None)?;
+    SQLParser::new(&tokens).with_spans(&spans).with_source(sql).with_max_depth(limits.max_depth).parse_single_statement()
+}
+
+/// Parses an expression on its own, such as a filter or formula kept outside any statement.
+/// The whole string must be the expression.
+///
+/// ```
+/// use joel_chirayath::{parse_expression, BinaryOperator, Expression};
+///
+/// let Expression::BinaryOperation { operator, .. } = parse_expression("price * 1.2 > 100").unwrap() else {
+///     panic!("not a comparison");
+/// };
+/// assert_eq!(operator, BinaryOperator::GreaterThan);
+/// assert!(parse_expression("price >").is_err());
+/// ```
+pub fn parse_expression(sql: &str) -> Result<Expression, ParseError> {
+    let limits = Limits::default();
+    let (tokens, spans) = read_tokens(sql, &limits, None)?;
+    PrattParser::new(&tokens).with_spans(&spans).with_source(sql).with_max_depth(limits.max_depth).parse_single_expression()
+}
+
+// For editors and other tools that work on broken input, and for parsing in a dialect or in
+// strict mode: the statement with its warnings, and the error if parsing failed. After an error
+// the statement is what was parsed before it (see SQLParser::take_partial), or None if the input
+// could not be tokenized or the statement kind was not recognized.
+pub fn parse_input_with_options(input: &str, limits: &Limits, options: &ParserOptions) -> ParseOutput<Statement> {
+    let (tokens, spans) = match read_tokens(input, limits, options.dialect) {
+        Ok(read) => read,
+        Err(error) => return ParseOutput { value: None, diagnostics: vec![Diagnostic::Error(error)] },
+    };
+    let mut parser = SQLParser::new(&tokens)
+        .with_spans(&spans)
+        .with_source(input)
+        .with_options(*options)
+        .with_max_depth(limits.max_depth.min(options.max_depth));
+    let (value, error) = match parser.parse_single_statement() {
+        Ok(statement) => (Some(statement), None),
+        Err(error) => (parser.take_partial(), Some(error)),
+    };
+    let mut diagnostics: Vec<Diagnostic> = parser.take_warnings().into_iter().map(Diagnostic::Warning).collect();
+    diagnostics.extend(error.map(Diagnostic::Error));
+    ParseOutput { value, diagnostics }
+}
+
+// Parses every statement in a whole file, tokenizing it once. Statements are separated by
+// semicolons and empty ones are skipped. On failure every error is returned, one per failing
+// statement, together with the warnings, in the order they occur in the file.
+pub fn parse_script(sql: &str) -> Result<Vec<Statement>, Vec<Diagnostic>> {
+    let limits = Limits::default();
+    let (tokens, spans) = read_tokens(sql, &limits, None).map_err(|error| vec![Diagnostic::Error(error)])?;
+    let mut parser = SQLParser::new(&tokens).with_spans(&spans).with_source(sql).with_max_depth(limits.max_depth);
+    let (statements, errors) = parser.parse_statements();
+    if errors.is_empty() {
+        return Ok(statements);
+    }
+    let mut diagnostics: Vec<Diagnostic> = parser.take_warnings().into_iter().map(Diagnostic::Warning).collect();
+    diagnostics.extend(errors.into_iter().map(Diagnostic::Error));
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span().start.offset);
+    Err(diagnostics)
+}
+
+/// Parses a statement that makes up the whole string, the same as parse_sql, so
+/// `"SELECT a FROM t".parse::<Statement>()` works
+impl std::str::FromStr for Statement {
+    type Err = ParseError;
+
+    fn from_str(sql: &str) -> Result<Self, ParseError> {
+        parse_sql(sql)
+    }
+}
+
+/// Parses an expression that makes up the whole string, e.g. `"a + b * 2".parse::<Expression>()`
+impl std::str::FromStr for Expression {
+    type Err = ParseError;
+
+    fn from_str(sql: &str) -> Result<Self, ParseError> {
+        parse_expression(sql)
+    }
+}
+
+// Tokenizes all of `input` within `limits`, stopping at the first lexer error
+fn read_tokens(input: &str, limits: &Limits, dialect: Option<Dialect>) -> Result<(Vec<Token>, Vec<Span>), ParseError> {
+    read_tokens_at(input, Location::default(), limits, dialect)
+}
+
+// Like read_tokens, for `input` that starts at `start` in a larger text
+pub(crate) fn read_tokens_at(input: &str, start: Location, limits: &Limits, dialect: Option<Dialect>) -> Result<(Vec<Token>, Vec<Span>), ParseError> {
+    if input.len() > limits.max_input_length {
+        return Err(ParseError::InputTooLong(limits.max_input_length, Span { start, end: start }));
+    }
+
+    let tokenizer = match dialect {
+        Some(dialect) => Tokenizer::new(input).with_dialect(dialect),
+        None => Tokenizer::new(input),
+    };
+    let mut tokenizer = tokenizer.starting_at(start);
+    let mut tokens = Vec::new();
+    let mut spans = Vec::new();
+
+    // Collect all tokens until EOF
+    loop {
+        let spanned = tokenizer.next_spanned_token();
+        if spanned.token == Token::Eof {
+            tokens.push(spanned.token);
+            spans.push(spanned.span);
+            break;
+        }
+        if tokens.len() == limits.max_tokens {
+            return Err(ParseError::TooManyTokens(limits.max_tokens, spanned.span));
+        }
+        // Report lexer errors before the parser sees them
+        if let Token::Invalid(character) = spanned.token {
+            return Err(LexError { character, span: spanned.span }.into());
+        }
+        tokens.push(spanned.token);
+        spans.push(spanned.span);
+    }
+    Ok((tokens, spans))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_with_limits(sql: &str, limits: &Limits) -> Result<Statement, ParseError> {
+        let output = parse_input_with_options(sql, limits, &ParserOptions::default());
+        match output.diagnostics.into_iter().find(Diagnostic::is_error) {
+            Some(Diagnostic::Error(error)) => Err(error),
+            _ => Ok(output.value.unwrap()),
+        }
+    }
+
+    fn parse_with_warnings(sql: &str) -> ParseOutput<Statement> {
+        parse_input_with_options(sql, &Limits::default(), &ParserOptions::default())
+    }
+
+    #[test]
+    fn oversized_input_is_rejected() {
+        let limits = Limits { max_input_length: 20, max_tokens: 100, ..Limits::default() };
+        assert!(parse_with_limits("SELECT a FROM t", &limits).is_ok());
+        let long = format!("SELECT {} FROM t", "a".repeat(20));
+        assert!(matches!(parse_with_limits(&long, &limits), Err(ParseError::InputTooLong(20, _))));
+    }
+
+    #[test]
+    fn token_count_is_limited() {
+        let limits = Limits { max_input_length: 1000, max_tokens: 4, ..Limits::default() };
+        assert!(parse_with_limits("SELECT a FROM t", &limits).is_ok());
+        assert!(matches!(parse_with_limits("SELECT a, b FROM t", &limits), Err(ParseError::TooManyTokens(4, _))));
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error_not_a_stack_overflow() {
+        let nested = |open: &str, inner: &str, close: &str, depth: usize| {
+            format!("SELECT {}{}{} FROM t", open.repeat(depth), inner, close.repeat(depth))
+        };
+        let max_depth = Limits::default().max_depth;
+        assert!(parse_sql(&nested("(", "1", ")", max_depth - 1)).is_ok());
+        for sql in [
+            nested("(", "1", ")", 10_000),
+            nested("- ", "a", "", 10_000),
+            nested("NOT ", "a", "", 10_000),
+            nested("CASE WHEN a THEN ", "1", " END", 10_000),
+            nested("f(", "1", ")", 10_000),
+            format!("SELECT a::{}INT{} FROM t", "ARRAY<".repeat(10_000), ">".repeat(10_000)),
+        ] {
+            let error = parse_sql(&sql).unwrap_err();
+            assert!(matches!(error.innermost(), ParseError::TooDeep(50, _)), "{}", &sql[..40]);
+        }
+    }
+
+    #[test]
+    fn operator_chains_are_not_nesting() {
+        let max_depth = Limits::default().max_depth;
+        // Sixty operators in a row nest nothing, however the tree groups them
+        for sql in [
+            format!("SELECT {}a FROM t", "a ^ ".repeat(59)),
+            format!("SELECT {}a FROM t", "NOT ".repeat(60)),
+            format!("SELECT {}a FROM t", "- ".repeat(60)),
+            format!("SELECT {}a FROM t", "NOT - a ^ ".repeat(20)),
+        ] {
+            assert!(parse_sql(&sql).is_ok(), "{}", &sql[..30]);
+        }
+        // Chains have a longer limit of their own, which keeps the tree shallow enough to walk
+        let longest = max_depth * pratt::OPERATOR_CHAIN_FACTOR;
+        for (unit, end) in [("NOT ", "a"), ("a ^ ", "a")] {
+            let statement = parse_sql(&format!("SELECT {}{} FROM t", unit.repeat(longest), end)).unwrap();
+            assert_eq!(parse_sql(&statement.to_string()).unwrap(), statement);
+            sexpr::statement_to_sexpr(&statement);
+            formatter::format_statement(&statement, &formatter::FormatOptions::default());
+            references(&statement);
+            parameters(&statement);
+            validate(&statement, &Schema::default());
+            let error = parse_sql(&format!("SELECT {}{} FROM t", unit.repeat(longest + 1), end)).unwrap_err();
+            assert!(matches!(error.innermost(), ParseError::TooDeep(50, _)));
+        }
+    }
+
+    #[test]
+    fn truncated_input_never_panics() {
+        let sql = "CREATE TABLE t (id INT PRIMARY KEY, d ARRAY<STRUCT<x INT>>, n NUMERIC(10, 2) DEFAULT -1.5e3, \
+                   CONSTRAINT fk FOREIGN KEY (id) REFERENCES u (id) ON DELETE NO ACTION); \
+                   SELECT a::TEXT, b->>'k', CASE WHEN x IN (1, 2) THEN $1 END FROM t WHERE c LIKE E'\\'' /* c */";
+        // Every prefix ends somewhere awkward: inside a string, a comment, a type or a list
+        for (end, _) in sql.char_indices() {
+            let _ = parse_sql(&sql[..end]);
+            let _ = tokenizer::split_statements(&sql[..end]);
+        }
+    }
+
+    #[test]
+    fn errors_underline_the_offending_token() {
+        let sql = "SELECT a\nFROM t WHERE b = = 1";
+        let error = parse_sql(sql).unwrap_err();
+        let span = error.span();
+        assert_eq!((span.start.line, span.start.column), (2, 18));
+        assert_eq!(error.render(sql).lines().skip(1).collect::<Vec<_>>(), vec!["  FROM t WHERE b = = 1", "                   ^"]);
+
+        let sql = "SELECT a FROM t WHERE b = 'open";
+        let rendered = parse_sql(sql).unwrap_err().render(sql);
+        assert!(rendered.ends_with("\n  ... a FROM t WHERE b = 'open\n                         ^^^^^"), "{}", rendered);
+    }
+
+    #[test]
+    fn long_lines_are_cut_to_an_excerpt() {
+        let columns: Vec<String> = (0..50).map(|i| format!("c{}", i)).collect();
+        let sql = format!("SELECT {} FROM t WHERE a = = 1 ORDER BY {}", columns.join(", "), columns.join(", "));
+        let error = parse_sql(&sql).unwrap_err();
+        let lines: Vec<String> = error.render(&sql).lines().skip(1).map(String::from).collect();
+        assert_eq!(lines[0], "  ...49 FROM t WHERE a = = 1 ORDER BY c0, c1, ...");
+        assert_eq!(lines[1], format!("  {}^", " ".repeat(23)));
+        assert!(error.render_with(&sql, Verbosity::Terse).ends_with(" near \"...49 FROM t WHERE a = = 1 ORDER BY c0, c1, ...\""));
+    }
+
+    #[test]
+    fn misspelled_keywords_are_suggested() {
+        for (sql, word, keyword) in [
+            ("SELCT a FROM t", "SELCT", Keyword::Select),
+            ("SELECT a FORM t", "FORM", Keyword::From),
+            ("SELECT a FROM t WHER a = 1", "WHER", Keyword::Where),
+            ("SELECT a FROM t ODER BY a", "ODER", Keyword::Order),
+            ("CREATE TABEL t (a INT)", "TABEL", Keyword::Table),
+            ("INSERT INTO t (a) VALEUS (1)", "VALEUS", Keyword::Values),
+        ] {
+            match parse_sql(sql) {
+                Err(ParseError::MisspelledKeyword(found, suggestion, _)) => {
+                    assert_eq!((found.as_str(), suggestion), (word, keyword), "{}", sql)
+                }
+                other => panic!("{}: {:?}", sql, other),
+            }
+        }
+    }
+
+    #[test]
+    fn errors_list_every_expected_token() {
+        let cases = [
+            ("SELECT a b FROM t", "Expected an operator, ',' or FROM, but found identifier b"),
+            ("SELECT a FROM 1", "Expected an identifier, but found number 1"),
+            ("CREATE TABLE t (a INT 5)", "Expected a column option, a column definition, a table constraint, ',' or ')', but found number 5"),
+            ("CREATE TABLE t (a INT, 5)", "Expected a column definition or a table constraint, but found number 5"),
+            ("CREATE TABLE t (a INT 5)", "Expected a column option, a column definition, a table constraint, ',' or ')', but found number 5"),
+            ("INSERT INTO t (a, b VALUES) (1, 2)", "Expected ',' or ')', but found VALUES, in the column list of INSERT"),
+            ("SELECT a FROM t ORDER BY a b", "Expected an operator, ASC, DESC, ',', ';' or end of input, but found identifier b"),
+            ("SELECT a + FROM t", "Expected an expression, but found FROM, in column 1 of SELECT"),
+            ("SELECT f(a b) FROM t", "Expected an operator, ',' or ')', but found identifier b, in column 1 of SELECT"),
+            ("SELECT CAST(a AS 5) FROM t", "Expected a data type, but found number 5, in column 1 of SELECT"),
+        ];
+        for (sql, message) in cases {
+            assert_eq!(parse_sql(sql).unwrap_err().to_string(), message, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn errors_name_the_part_of_the_statement_they_are_in() {
+        for (sql, context) in [
+            ("SELECT a FROM t WHERE b = = 1", "the WHERE clause of SELECT"),
+            ("SELECT a FROM t ORDER BY a, = b", "item 2 of ORDER BY"),
+            ("CREATE TABLE t (a INT, b VARCHAR(x))", "column definition 2 of CREATE TABLE"),
+            ("CREATE TABLE t (a INT, CHECK (a >))", "constraint 1 of CREATE TABLE"),
+            ("INSERT INTO t (a, b) VALUES (1, 2 +)", "value 2 of INSERT"),
+        ] {
+            let error = parse_sql(sql).unwrap_err();
+            assert!(matches!(&error, ParseError::InContext(found, _) if found == context), "{}: {:?}", sql, error);
+            assert!(error.to_string().ends_with(&format!(", in {}", context)), "{}", error);
+            // The context does not change what the error is or where
+            assert_eq!(error.code(), error.innermost().code());
+            assert_eq!(error.span(), error.innermost().span());
+            assert!(std::error::Error::source(&error).is_some());
+        }
+    }
+
+    #[test]
+    fn lexer_errors_are_the_source() {
+        let error = parse_sql("SELECT a FROM t WHERE b = 'open").unwrap_err();
+        let source = std::error::Error::source(&error).and_then(|e| e.downcast_ref::<LexError>());
+        assert_eq!(source.map(|e| e.character), Some('\''));
+        assert_eq!(error.to_string(), "Invalid or unterminated token starting with '''");
+    }
+
+    #[test]
+    fn failed_parses_keep_what_was_parsed() {
+        let partial = |sql| {
+            let output = parse_with_warnings(sql);
+            assert_eq!(output.errors().count(), 1, "{}", sql);
+            output.value
+        };
+        let output = parse_with_warnings("SELECT a FROM `t`");
+        assert!(output.value.is_some() && !output.has_errors());
+        assert_eq!(output.warnings().count(), 1);
+
+        // Warnings found before the error are kept with it
+        let output = parse_with_warnings("SELECT a FROM key WHERE");
+        let codes: Vec<&str> = output.diagnostics.iter().map(Diagnostic::code).collect();
+        assert_eq!(codes, ["W0001", "E0002"]);
+
+        let Some(Statement::Select { columns, from, selection, .. }) = partial("SELECT a, b + FROM t") else { panic!() };
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[1], ast::Expression::Error);
+        assert_eq!(from.relation.name.value, "");
+        assert_eq!(selection, None);
+
+        let Some(Statement::Select { from, selection, .. }) = partial("SELECT a FROM t WHERE a =") else { panic!() };
+        assert_eq!((from.relation.name.value.as_str(), selection), ("t", Some(ast::Expression::Error)));
+
+        let Some(Statement::CreateTable { table_name, columns, .. }) = partial("CREATE TABLE t (a INT, b VARCHAR(x)") else { panic!() };
+        assert_eq!((table_name.value.as_str(), columns.len()), ("t", 1));
+
+        let Some(Statement::Insert { columns, values, .. }) = partial("INSERT INTO t (a, b) VALUES (1,") else { panic!() };
+        assert_eq!((columns.len(), values), (2, vec![ast::Expression::Literal(ast::Literal::Integer(1), ast::NodeSpan::default()), ast::Expression::Error]));
+
+        assert!(partial("DROP TABLE t").is_none());
+        assert!(partial("SELECT 'open").is_none());
+    }
+
+    #[test]
+    fn verbosity_decides_how_much_is_rendered() {
+        let sql = "SELECT a FORM t";
+        let error = parse_sql(sql).unwrap_err();
+        assert_eq!(error.render_with(sql, Verbosity::Terse), "1:10: E0006 Unexpected word FORM, did you mean FROM? near \"SELECT a FORM t\"");
+        assert_eq!(error.render_with(sql, Verbosity::Normal), error.render(sql));
+        let verbose = error.render_with(sql, Verbosity::Verbose);
+        assert!(verbose.starts_with("[E0006] "), "{}", verbose);
+        assert!(verbose.contains(&error.render(sql)));
+        assert!(verbose.contains("\n  help: Check the spelling of the keyword."), "{}", verbose);
+        assert!(verbose.contains("\n  for example: SELECT"), "{}", verbose);
+
+        let warnings: Vec<ParseWarning> = parse_with_warnings("SELECT a FROM `t`").warnings().cloned().collect();
+        assert_eq!(warnings[0].render_with("", Verbosity::Terse), "1:15: W0002 Identifier quoted with ` instead of \"");
+        assert!(warnings[0].render_with("SELECT a FROM `t`", Verbosity::Terse).ends_with("near \"SELECT a FROM `t`\""));
+    }
+
+    #[test]
+    fn strict_mode_rejects_what_lenient_mode_warns_about() {
+        let strict = ParserOptions { strict: true, ..ParserOptions::default() };
+        for (sql, warning) in [
+            ("CREATE TABLE t (id INT AUTO_INCREMENT)", "AUTO_INCREMENT is not standard SQL"),
+            ("CREATE TABLE t (id INT) ENGINE = InnoDB", "Table option ENGINE is not standard SQL"),
+            ("CREATE TABLE t (id INT) WITH (fillfactor = 70)", "WITH (...) after CREATE TABLE is not standard SQL"),
+            ("CREATE TABLE t (id INT) PARTITION BY HASH (id)", "PARTITION BY is not standard SQL"),
+            ("SELECT a FROM [t]", "Identifier quoted with [ instead of \""),
+            ("CREATE TABLE t (a INT b INT)", "Missing ',' before this definition"),
+        ] {
+            let lenient = parse_with_warnings(sql);
+            assert!(!lenient.has_errors(), "{}", sql);
+            assert_eq!(lenient.warnings().map(ToString::to_string).collect::<Vec<_>>(), [warning]);
+
+            let output = parse_input_with_options(sql, &Limits::default(), &strict);
+            let error = output.errors().next().unwrap().innermost();
+            assert!(matches!(error, ParseError::Nonstandard(..)), "{}: {:?}", sql, error);
+            assert_eq!(error.to_string(), format!("Not allowed in strict mode: {}", warning));
+        }
+        let output = parse_input_with_options("SELECT \"a\" FROM key", &Limits::default(), &strict);
+        assert!(!output.has_errors());
+    }
+
+    #[test]
+    fn dialects_accept_only_their_own_extensions() {
+        let parse = |sql: &str, dialect: Dialect| {
+            let options = ParserOptions { dialect: Some(dialect), strict: true, ..ParserOptions::default() };
+            parse_input_with_options(sql, &Limits::default(), &options)
+        };
+        for (sql, dialect) in [
+            ("CREATE TABLE t (id INT AUTO_INCREMENT) ENGINE = InnoDB", Dialect::MySql),
+            ("CREATE TABLE t (id INTEGER AUTOINCREMENT)", Dialect::Sqlite),
+            ("CREATE TABLE t (id INT) WITH (fillfactor = 70)", Dialect::Postgres),
+            ("SELECT a FROM `t`", Dialect::MySql),
+            ("SELECT a FROM [t]", Dialect::MsSql),
+        ] {
+            let output = parse(sql, dialect);
+            assert_eq!(output.diagnostics.len(), 0, "{}: {:?}", sql, output.diagnostics);
+        }
+
+        let output = parse("CREATE TABLE t (id INT AUTO_INCREMENT)", Dialect::Postgres);
+        let error = output.errors().next().unwrap();
+        assert_eq!(error.code(), "E0014");
+        assert_eq!(error.to_string(), "Not supported in PostgreSQL: AUTO_INCREMENT is not standard SQL, in column definition 1 of CREATE TABLE");
+
+        // Quoting and operators follow the dialect's tokenizer rules
+        let output = parse("SELECT \"Order\" FROM t WHERE a::INT > 1", Dialect::Postgres);
+        let Some(Statement::Select { columns, .. }) = output.value else { panic!("{:?}", output.diagnostics) };
+        assert_eq!(columns[0], Expression::Identifier(Ident::quoted("Order", '"')));
+        assert_eq!(parse("SELECT a || b FROM t", Dialect::MySql).errors().next().unwrap().code(), "E0007");
+        assert_eq!(parse("SELECT a FROM `t`", Dialect::Postgres).errors().next().unwrap().code(), "E0007");
+        assert!(!parse("SELECT a ILIKE 'x' FROM t", Dialect::Postgres).has_errors());
+        assert!(parse("SELECT a ILIKE 'x' FROM t", Dialect::Ansi).has_errors());
+    }
+
+    #[test]
+    fn unbalanced_delimiters_are_reported_where_they_are() {
+        for (sql, message, column) in [
+            ("SELECT f(a, (b + 1) FROM t WHERE c = 1", "'(' is never closed", 9),
+            ("SELECT a FROM t WHERE (b = 1", "'(' is never closed", 23),
+            ("SELECT a) FROM t", "')' has no opening '('", 9),
+            ("SELECT x[1) FROM t", "')' has no opening '('", 11),
+            ("INSERT INTO t (a) VALUES (ARRAY[1, 2)", "')' has no opening '('", 37),
+        ] {
+            let error = parse_sql(sql).unwrap_err();
+            assert_eq!((error.to_string().as_str(), error.span().start.column), (message, column), "{}", sql);
+        }
+        // An earlier mistake is still reported first
+        let error = parse_sql("SELECT a b FROM f WHERE (c").unwrap_err();
+        assert!(matches!(error, ParseError::Expected(..)), "{:?}", error);
+        // Unterminated strings are reported where they start
+        assert_eq!(parse_sql("SELECT 'a, b FROM t").unwrap_err().span().start.column, 8);
+    }
+
+    #[test]
+    fn errors_serialize_to_json() {
+        let error = parse_sql("SELECT a FROM 1").unwrap_err();
+        assert_eq!(error.code(), "E0002");
+        assert_eq!(
+            error.to_json(),
+            r#"{"code":"E0002","message":"Expected an identifier, but found number 1","span":{"start":{"line":1,"column":15,"offset":14},"end":{"line":1,"column":16,"offset":15}}}"#
+        );
+        assert_eq!(json_string("tab\there \u{1}"), r#""tab\there \u0001""#);
+    }
+
+    #[test]
+    fn accepted_oddities_are_warned_about() {
+        let warnings = |sql: &str| {
+            let output = parse_with_warnings(sql);
+            assert!(!output.has_errors(), "{}", sql);
+            output.warnings().cloned().collect::<Vec<_>>()
+        };
+        assert!(warnings("SELECT a FROM t").is_empty());
+
+        let found = warnings("CREATE TABLE `t` (key INT PRIMARY KEY b TEXT)");
+        let codes: Vec<&str> = found.iter().map(ParseWarning::code).collect();
+        assert_eq!(codes, ["W0002", "W0001", "W0003"]);
+        assert_eq!(found[0].to_string(), "Identifier quoted with ` instead of \"");
+        assert_eq!(found[1].to_string(), "Keyword KEY used as an identifier");
+        assert_eq!((found[2].span().start.line, found[2].span().start.column), (1, 39));
+
+        // Keywords used as names in expressions are warned about too
+        let found = warnings("SELECT key FROM t WHERE t.order > 1 ORDER BY Order");
+        let spans: Vec<(&str, usize)> = found.iter().map(|w| (w.code(), w.span().start.column)).collect();
+        assert_eq!(spans, [("W0001", 8), ("W0001", 46)]);
+        assert!(warnings(&parse_sql("SELECT key FROM t").unwrap().to_string()).iter().all(|w| w.code() != "W0001"));
+    }
+
+    #[test]
+    fn scripts_are_split_into_statements() {
+        let statements = parse_script(";\nSELECT a FROM t;;\n\nINSERT INTO t (a) VALUES (1);\nSELECT b FROM u ORDER BY b").unwrap();
+        assert_eq!(statements.len(), 3);
+        assert!(matches!(statements[1], Statement::Insert { .. }));
+        assert_eq!(parse_script("").unwrap().len(), 0);
+        assert_eq!(parse_script(" ; ;").unwrap().len(), 0);
+
+        // Every failing statement is reported, and the ones around them are still parsed
+        let diagnostics = parse_script("SELECT FROM t;\nSELECT a FROM t;\nSELECT (a FROM t;\nSELECT b FROM u c d").unwrap_err();
+        let found: Vec<(&str, usize)> = diagnostics.iter().map(|d| (d.code(), d.span().start.line)).collect();
+        assert_eq!(found, [("E0002", 1), ("E0013", 3), ("E0002", 4)]);
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "Expected ';' or end of input, but found identifier d"
+        );
+
+        let diagnostics = parse_script("SELECT a FROM t; SELECT #").unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code(), "E0007");
+    }
+
+    #[test]
+    fn nodes_know_where_they_were_read_from() {
+        let sql = "SELECT a + 1, COUNT(*) FROM t\nWHERE t.b IS NULL ORDER BY CAST(c AS INT) DESC;\nINSERT INTO u (x) VALUES (-2)";
+        let source = |span: Span| &sql[span.start.offset..span.end.offset];
+        let statements = parse_script(sql).unwrap();
+        assert_eq!(source(statements[0].span()), "SELECT a + 1, COUNT(*) FROM t\nWHERE t.b IS NULL ORDER BY CAST(c AS INT) DESC");
+        assert_eq!(source(statements[1].span()), "INSERT INTO u (x) VALUES (-2)");
+
+        let Statement::Select { columns, from, selection, order_by, .. } = &statements[0] else { panic!() };
+        let found: Vec<&str> = columns.iter().map(|column| source(column.span())).collect();
+        assert_eq!(found, ["a + 1", "COUNT(*)"]);
+        assert_eq!(source(from.relation.name.span.0), "t");
+        let selection = selection.as_ref().unwrap();
+        assert_eq!(source(selection.span()), "t.b IS NULL");
+        assert_eq!(selection.span().start.line, 2);
+        let order_by = &order_by.as_ref().unwrap()[0];
+        assert_eq!((source(order_by.span.0), source(order_by.expr.span())), ("CAST(c AS INT) DESC", "CAST(c AS INT)"));
+
+        let Statement::Insert { columns, values, .. } = &statements[1] else { panic!() };
+        assert_eq!((source(columns[0].span.0), source(values[0].span())), ("x", "-2"));
+
+        let sql = "CREATE TABLE t (a INT REFERENCES u (id) ON DELETE CASCADE, b STRUCT<x INT, y TEXT>) ENGINE = InnoDB";
+        let source = |span: Span| &sql[span.start.offset..span.end.offset];
+        let Statement::CreateTable { columns, options, .. } = parse_sql(sql).unwrap() else { panic!() };
+        let ColumnOption::References(reference) = &columns[0].options[0] else { panic!() };
+        assert_eq!(source(reference.span.0), "REFERENCES u (id) ON DELETE CASCADE");
+        let DataType::Struct(fields) = &columns[1].data_type else { panic!() };
+        assert_eq!(source(fields[1].span.0), "y TEXT");
+        assert_eq!(source(options[0].span.0), "ENGINE = InnoDB");
+
+        let sql = "SELECT a FROM t WHERE s.t.b NOT LIKE 'x' AND c BETWEEN 1 AND 2";
+        let source = |span: Span| &sql[span.start.offset..span.end.offset];
+        let Statement::Select { selection: Some(Expression::BinaryOperation { left_operand, right_operand, span, .. }), .. } =
+            parse_sql(sql).unwrap() else { panic!() };
+        assert_eq!(source(span.0), "s.t.b NOT LIKE 'x' AND c BETWEEN 1 AND 2");
+        let Expression::Like { expr, span, .. } = *left_operand else { panic!() };
+        assert_eq!((source(span.0), source(expr.span())), ("s.t.b NOT LIKE 'x'", "s.t.b"));
+        assert_eq!(source(right_operand.span()), "c BETWEEN 1 AND 2");
+
+        // Nodes built in code have no source, and spans never affect equality
+        assert_eq!(statements[1], parse_sql("INSERT INTO u(x)VALUES(-2)").unwrap());
+        assert_eq!(builder::col("a").span(), Span::default());
+    }
+
+    #[test]
+    fn trailing_tokens_are_rejected() {
+        assert!(parse_sql("SELECT a FROM t;").is_ok());
+        let error = parse_sql("SELECT a FROM t 42 more tokens here").unwrap_err();
+        assert_eq!((error.code(), error.span().start.offset), ("E0002", 16));
+        assert!(error.to_string().contains("end of input"), "{}", error);
+
+        let output = parse_input_with_options("SELECT a FROM t u junk", &Limits::default(), &ParserOptions::default());
+        assert!(output.has_errors());
+        assert_eq!(output.value, Some(parse_sql("SELECT a FROM t u").unwrap()));
+        assert!(parse_with_warnings("INSERT INTO t (a) VALUES (1) 2").has_errors());
+    }
+
+    #[test]
+    fn strings_parse_into_whole_statements_and_expressions() {
+        let statement: Statement = "SELECT a FROM t;".parse().unwrap();
+        assert_eq!(statement, parse_sql("SELECT a FROM t").unwrap());
+        let error = "SELECT a FROM t x y".parse::<Statement>().unwrap_err();
+        assert!(matches!(&error, ParseError::Expected(expected, Some(Token::Identifier(found)), _)
+            if found == "y" && expected.contains(&Token::Eof.to_string())), "{:?}", error);
+        assert!("SELECT a FROM t; SELECT b FROM u".parse::<Statement>().is_err());
+
+        let expression: Expression = "a + b * 2".parse().unwrap();
+        assert_eq!(expression.to_string(), "a + b * 2");
+        let Expression::BinaryOperation { right_operand, .. } = &expression else { panic!("{:?}", expression) };
+        assert_eq!(right_operand.span().start.offset, 4);
+        let error = "a + b c".parse::<Expression>().unwrap_err();
+        assert_eq!((error.code(), error.span().start.offset), ("E0002", 6));
+        assert!("".parse::<Expression>().is_err());
+    }
+
+    #[test]
+    fn statements_are_classified_by_kind() {
+        let classify = |sql: &str| {
+            let statement = parse_sql(sql).unwrap();
+            let written: Vec<String> = statement.tables_written().iter().map(|table| table.value.clone()).collect();
+            (statement.kind(), statement.is_read_only(), statement.is_ddl(), written)
+        };
+        assert_eq!(classify("SELECT a FROM t"), (StatementKind::Select, true, false, vec![]));
+        assert_eq!(classify("INSERT INTO t (a) VALUES (1)"), (StatementKind::Insert, false, false, vec!["t".to_string()]));
+        assert_eq!(classify("CREATE TABLE u (a INT)"), (StatementKind::CreateTable, false, true, vec!["u".to_string()]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn statements_round_trip_through_json() {
+        let statement = parse_sql("CREATE TABLE t (a INT[] DEFAULT ARRAY[1, -2], CHECK (a IS NOT NULL)) ENGINE=InnoDB").unwrap();
+        let json = serde_json::to_string(&statement).unwrap();
+        assert!(json.starts_with(r#"{"CreateTable":{"table_name":{"value":"t","quote_style":null,"span":{"start":{"line":1,"column":14,"offset":13}"#), "{}", json);
+        assert_eq!(serde_json::from_str::<Statement>(&json).unwrap(), statement);
+    }
+}
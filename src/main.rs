@@ -1,57 +1,144 @@
-
-mod tokenizer;  // Handles breaking SQL input into tokens
-mod pratt;      // Handles expression parsing using Pratt parsing technique
-mod parser;     // Main SQL parser logic
-mod ast;        // Abstract Syntax Tree definitions
+mod corpus;     // Parse-rate report over external SQL corpora (--corpus)
 
 // Import standard I/O modules
-use std::io::{self, Write};
-
-// Bring in Tokenizer and Token from tokenizer module
-use tokenizer::{Tokenizer, Token};
-
-// Bring in the SQLParser struct from parser module
-use parser::SQLParser;
-
-// === Begin custom ParseError definition ===
-
-#[derive(Debug)]
-pub enum ParseError {
-    UnexpectedEnd, // Input ended unexpectedly
-    ExpectedKeyword(String), // A specific keyword was expected but not found
-    ExpectedIdentifier, // An identifier (e.g., table name) was expected
-    InvalidExpression(String), // Expression syntax was invalid
-    UnknownStartOfStatement(String), // Parser saw something unexpected at start
-    ExpectedToken(String, Option<Token>), // Expected a token, but got something else
-    UnexpectedToken(Token), // A completely unexpected token appeared
-    General(String), // A general error message
+use std::io::{self, BufRead, Read, Write};
+
+use joel_chirayath::formatter::{format_sql, CommaStyle, FormatOptions, KeywordCase};
+use joel_chirayath::sexpr::statement_to_sexpr;
+use joel_chirayath::{parse_input_with_options, Dialect, Limits, ParserOptions, Statement, Verbosity};
+
+// Discards input up to and including the next newline
+fn skip_line(reader: &mut impl BufRead) {
+    loop {
+        let (done, used) = match reader.fill_buf() {
+            Ok([]) | Err(_) => return,
+            Ok(buf) => match buf.iter().position(|&b| b == b'\n') {
+                Some(i) => (true, i + 1),
+                None => (false, buf.len()),
+            },
+        };
+        reader.consume(used);
+        if done {
+            return;
+        }
+    }
 }
 
-// Implementing error messages
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+// How the REPL prints a parsed statement
+#[derive(Clone, Copy)]
+enum OutputFormat {
+    Debug,
+    Json,
+    Sexpr,
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "debug" => Some(OutputFormat::Debug),
+            "json" => Some(OutputFormat::Json),
+            "sexpr" => Some(OutputFormat::Sexpr),
+            _ => None,
+        }
+    }
+
+    fn render(self, statement: &Statement) -> String {
         match self {
-            ParseError::UnexpectedEnd => write!(f, "Unexpected end of input"),
-            ParseError::ExpectedKeyword(k) => write!(f, "Expected keyword: {}", k),
-            ParseError::ExpectedIdentifier => write!(f, "Expected an identifier"),
-            ParseError::InvalidExpression(e) => write!(f, "Invalid expression: {}", e),
-            ParseError::UnknownStartOfStatement(t) => write!(f, "Unknown start of statement: {}", t),
-            ParseError::ExpectedToken(expected, actual) => match actual {
-                Some(t) => write!(f, "Expected token: {}, but found: {:?}", expected, t),
-                None => write!(f, "Expected token: {}, but found end of input", expected),
-            },
-            ParseError::UnexpectedToken(token) => write!(f, "Unexpected token: {:?}", token),
-            ParseError::General(e) => write!(f, "Error: {}", e),
+            OutputFormat::Debug => format!("{:#?}", statement),
+            OutputFormat::Sexpr => statement_to_sexpr(statement),
+            OutputFormat::Json => json(statement),
         }
     }
 }
 
-// Implement standard error 
-impl std::error::Error for ParseError {}
+#[cfg(feature = "serde")]
+fn json(statement: &Statement) -> String {
+    serde_json::to_string(statement).unwrap_or_else(|e| format!("Could not serialize the statement: {}", e))
+}
 
-// === End custom ParseError definition ===
+#[cfg(not(feature = "serde"))]
+fn json(_statement: &Statement) -> String {
+    "JSON output needs the serde feature: cargo run --features serde".to_string()
+}
+
+// The value after `flag` in the arguments, as in `--indent 2`
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let position = args.iter().position(|arg| arg == flag)?;
+    args.get(position + 1).map(String::as_str)
+}
+
+// Formats the script on stdin to stdout, or prints its errors; true if it parsed
+fn run_sqlfmt(args: &[String]) -> bool {
+    let defaults = FormatOptions::default();
+    let options = FormatOptions {
+        keyword_case: match flag_value(args, "--keyword-case") {
+            Some("lower") => KeywordCase::Lower,
+            _ => KeywordCase::Upper,
+        },
+        indent: flag_value(args, "--indent").and_then(|n| n.parse().ok()).unwrap_or(defaults.indent),
+        max_line_length: flag_value(args, "--max-line-length").and_then(|n| n.parse().ok()).unwrap_or(defaults.max_line_length),
+        comma_style: if args.iter().any(|arg| arg == "--leading-commas") { CommaStyle::Leading } else { CommaStyle::Trailing },
+    };
+    let mut source = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut source) {
+        eprintln!("❌Error: could not read stdin: {}", e);
+        return false;
+    }
+    match format_sql(&source, &options) {
+        Ok(formatted) => {
+            print!("{}", formatted);
+            true
+        }
+        Err(diagnostics) => {
+            for diagnostic in &diagnostics {
+                eprintln!("{}", diagnostic.render_with(&source, Verbosity::Terse));
+            }
+            false
+        }
+    }
+}
 
 fn main() {
+    // `--corpus <paths>` reports parse rates instead of starting the REPL
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--corpus") {
+        let ok = corpus::run(&args[1..]);
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+    // `--sqlfmt` formats the script on stdin instead of starting the REPL
+    if args.iter().any(|arg| arg == "--sqlfmt") {
+        std::process::exit(if run_sqlfmt(&args) { 0 } else { 1 });
+    }
+    // `--json-errors` prints each error as one line of JSON, for editors and CI tools
+    let json_errors = args.iter().any(|arg| arg == "--json-errors");
+    // Errors come with hints and examples unless `--terse` asks for one line each
+    let verbosity = if args.iter().any(|arg| arg == "--terse") { Verbosity::Terse } else { Verbosity::Verbose };
+    // `--strict` rejects vendor extensions that are otherwise accepted with a warning
+    let strict = args.iter().any(|arg| arg == "--strict");
+    // `--dialect <name>` reads one dialect's quotes, operators and extensions only
+    let dialect = match flag_value(&args, "--dialect") {
+        None => None,
+        Some(name) => match Dialect::from_name(name) {
+            Some(dialect) => Some(dialect),
+            None => {
+                eprintln!("Unknown dialect '{}': use ansi, postgres, mysql, sqlite or mssql", name);
+                std::process::exit(2);
+            }
+        },
+    };
+    let options = ParserOptions { dialect, strict, ..ParserOptions::default() };
+    // `--format json|sexpr|debug` picks how statements are printed; `\format <name>` changes it in the REPL
+    let mut format = match flag_value(&args, "--format") {
+        None => OutputFormat::Debug,
+        Some(name) => match OutputFormat::from_name(name) {
+            Some(format) => format,
+            None => {
+                eprintln!("Unknown format '{}': use json, sexpr or debug", name);
+                std::process::exit(2);
+            }
+        },
+    };
+
     println!("🔷 Welcome to SQL Parser :) ");
     println!("Enter your SQL command (type 'exit' to leave):\n");
 
@@ -61,12 +148,23 @@ fn main() {
         print!("sql> ");
         io::stdout().flush().unwrap(); // Ensure the prompt is displayed
 
-        // Read user input
+        // Read user input, at most one byte past the length limit
+        let limit = Limits::default().max_input_length;
+        let mut stdin = io::stdin().lock();
         let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            // If there's an input error, show message and restart loop
-            eprintln!("Trouble reading your input — please try again.");
-            continue;
+        match stdin.by_ref().take(limit as u64 + 1).read_line(&mut input) {
+            Ok(0) => break, // End of input
+            Ok(_) => {}
+            Err(_) => {
+                // If there's an input error (such as invalid UTF-8), drop the line and restart loop
+                skip_line(&mut stdin);
+                eprintln!("Trouble reading your input — please try again.");
+                continue;
+            }
+        }
+        if input.len() > limit && !input.ends_with('\n') {
+            // Throw away the rest of the oversized line without buffering it
+            skip_line(&mut stdin);
         }
 
         // Trim whitespace and check for exit command
@@ -76,30 +174,29 @@ fn main() {
             break;
         }
 
-        // Tokenizing input string
-        let mut tokenizer = Tokenizer::new(input);
-        let mut tokens = Vec::new();
-
-        // Collect all tokens until EOF
-        loop {
-            let token = tokenizer.next_token();
-            if token == Token::Eof {
-                tokens.push(token);
-                break;
+        if let Some(name) = input.strip_prefix("\\format") {
+            match OutputFormat::from_name(name.trim()) {
+                Some(new_format) => {
+                    format = new_format;
+                    println!("Statements are now printed as {}\n", name.trim());
+                }
+                None => eprintln!("Unknown format '{}': use json, sexpr or debug\n", name.trim()),
             }
-            tokens.push(token);
+            continue;
         }
 
-        // Create parser with token stream
-        let mut parser = SQLParser::new(&tokens);
-
         // Try parsing statement and print result or error
-        match parser.parse_statement() {
-            Ok(statement) => {
-                println!("✅ Your parsed Statement is:\n{:#?}\n", statement);
-            }
-            Err(e) => {
-                eprintln!("❌Error: {}\n", e);
+        let output = parse_input_with_options(input, &Limits::default(), &options);
+        if let (Some(statement), false) = (&output.value, output.has_errors()) {
+            println!("✅ Your parsed Statement is:\n{}\n", format.render(statement));
+        }
+        for diagnostic in &output.diagnostics {
+            if json_errors {
+                eprintln!("{}", diagnostic.to_json());
+            } else if diagnostic.is_error() {
+                eprintln!("❌Error: {}\n", diagnostic.render_with(input, verbosity));
+            } else {
+                eprintln!("⚠️ Warning: {}\n", diagnostic.render_with(input, verbosity));
             }
         }
     }
@@ -1,225 +1,1297 @@
-use crate::tokenizer::{Token, Keyword};
-use crate::ast::{Statement, Expression, ColumnDef, DataType};
+use crate::tokenizer::{suggest_keyword, Token, Keyword, Span, Dialect};
+use crate::ast::{NodeSpan, Ident, Statement, Expression, OrderByExpr, ColumnDef, ColumnOption, ConflictAction, DataType, ForeignKeyReference, GeneratedStorage, Join, JoinConstraint, JoinOperator, ReferentialAction, TableConstraint, TableFactor, TableOption, TableWithJoins};
 use crate::pratt::PrattParser;
-use crate::ParseError;
+use crate::{ParseError, ParseWarning, ParserOptions};
+
+// Words that start a JOIN, and so end the table before them rather than naming it
+const JOIN_WORDS: &[&str] = &["JOIN", "INNER", "LEFT", "RIGHT", "FULL", "CROSS", "USING"];
 
 pub struct SQLParser<'a> {
     tokens: &'a [Token],
+    // Where each token is in the source, parallel to `tokens`; empty if unknown
+    spans: &'a [Span],
+    // The text the tokens were read from, which starts at offset `source_start` of the spans;
+    // empty if unknown. Keywords used as names are spelled from it.
+    source: &'a str,
+    source_start: usize,
     position: usize,
+    // Everything looked for at `expected_at` and not found, so an error there can list it all
+    expected: Vec<String>,
+    expected_at: usize,
+    // Accepted but questionable syntax, in source order
+    warnings: Vec<ParseWarning>,
+    // Passed on to the Pratt parser, which does all the nesting
+    max_depth: usize,
+    // What was parsed of a statement that failed, with Error placeholders where it stopped
+    partial: Option<Statement>,
+    options: ParserOptions,
+}
+
+/// Where a speculative parse started, see `checkpoint`
+struct Checkpoint {
+    position: usize,
+    warnings: usize,
 }
 
 impl<'a> SQLParser<'a> {
     pub fn new(tokens: &'a [Token]) -> Self {
-        Self { tokens, position: 0 }
+        Self { tokens, spans: &[], source: "", source_start: 0, position: 0, expected: Vec::new(), expected_at: 0, warnings: Vec::new(), max_depth: usize::MAX, partial: None, options: ParserOptions::default() }
+    }
+
+    /// Lets errors point into the source; `spans[i]` is where `tokens[i]` was read from
+    pub fn with_spans(mut self, spans: &'a [Span]) -> Self {
+        self.spans = spans;
+        self
+    }
+
+    /// Lets keywords used as names keep the case they were written in; the spans must count
+    /// from the start of `source`
+    pub fn with_source(self, source: &'a str) -> Self {
+        self.with_source_at(source, 0)
+    }
+
+    // Like with_source, for `source` that starts at offset `start` in a larger text
+    pub(crate) fn with_source_at(mut self, source: &'a str, start: usize) -> Self {
+        self.source = source;
+        self.source_start = start;
+        self
+    }
+
+    /// The dialect to accept, whether vendor extensions are errors (strict) or warnings
+    /// (lenient, the default), and the nesting limit, which replaces any from with_max_depth
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self.max_depth = options.max_depth;
+        self
+    }
+
+    /// Limits how deeply expressions and types may nest, see PrattParser::with_max_depth
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// After parse_statement fails: what it had parsed, for tools like completion that work on
+    /// broken input. Names it never reached are empty, and an expression that failed is
+    /// Expression::Error.
+    pub fn take_partial(&mut self) -> Option<Statement> {
+        self.partial.take()
+    }
+
+    /// The warnings collected so far, leaving none behind
+    pub fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.peek_nth(0)
     }
 
-    fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.position)
+    /// Looks `n` tokens ahead without consuming anything; peek_nth(0) is the next token
+    fn peek_nth(&self, n: usize) -> Option<&'a Token> {
+        self.tokens.get(self.position + n)
     }
 
-    fn advance(&mut self) -> Option<&Token> {
+    /// The span of token `index`; past the end this is the last token's (Eof) span
+    fn span_at(&self, index: usize) -> Span {
+        self.spans.get(index).or(self.spans.last()).copied().unwrap_or_default()
+    }
+
+    /// The span of the next token, for errors found by peeking
+    fn current_span(&self) -> Span {
+        self.span_at(self.position)
+    }
+
+    /// The span of the token just consumed, for errors found after advance()
+    fn previous_span(&self) -> Span {
+        self.span_at(self.position.saturating_sub(1))
+    }
+
+    /// A keyword used as a name at token `index`, spelled as in the source; in lower case if
+    /// the source is unknown
+    fn keyword_name(&self, index: usize, keyword: &Keyword) -> Ident {
+        let span = self.span_at(index);
+        let written = self.source.get(span.start.offset.wrapping_sub(self.source_start)..span.end.offset.wrapping_sub(self.source_start));
+        let name = match written {
+            Some(written) if written.eq_ignore_ascii_case(keyword.as_str()) => written.to_string(),
+            _ => keyword.as_str().to_lowercase(),
+        };
+        Ident::new(name).with_span(span)
+    }
+
+    /// From token `start` to the token just consumed, for the node parsed in between
+    fn span_from(&self, start: usize) -> NodeSpan {
+        NodeSpan(Span { start: self.span_at(start).start, end: self.previous_span().end })
+    }
+
+    /// Remembers the current position so a speculative parse can be undone with `rollback`
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { position: self.position, warnings: self.warnings.len() }
+    }
+
+    fn rollback(&mut self, checkpoint: Checkpoint) {
+        self.position = checkpoint.position;
+        self.warnings.truncate(checkpoint.warnings);
+    }
+
+    fn advance(&mut self) -> Option<&'a Token> {
         let token = self.tokens.get(self.position);
         self.position += 1;
         token
     }
 
+    /// `error`, unless `token` is a word close to one of the `expected` keywords; then a
+    /// MisspelledKeyword error at the same place that suggests it
+    fn or_misspelled_keyword(&self, error: ParseError, token: Option<&Token>, expected: &[Keyword]) -> ParseError {
+        if let Some(Token::Identifier(word)) = token {
+            if let Some(keyword) = suggest_keyword(word, expected) {
+                return ParseError::MisspelledKeyword(word.clone(), keyword, error.span());
+            }
+        }
+        error
+    }
+
+    /// Accepts syntax outside standard SQL, described by `warning`: a warning in lenient mode
+    /// and an error in strict mode
+    fn nonstandard(&mut self, warning: ParseWarning) -> Result<(), ParseError> {
+        if self.options.strict {
+            return Err(ParseError::Nonstandard(warning.to_string(), warning.span()));
+        }
+        self.warnings.push(warning);
+        Ok(())
+    }
+
+    /// Accepts a vendor extension that the dialects in `supported_by` have. With no dialect set
+    /// this is `nonstandard`; otherwise it is accepted silently in those dialects and an
+    /// Unsupported error in the rest
+    fn vendor_syntax(&mut self, warning: ParseWarning, supported_by: &[Dialect]) -> Result<(), ParseError> {
+        match self.options.dialect {
+            None => self.nonstandard(warning),
+            Some(dialect) if supported_by.contains(&dialect) => Ok(()),
+            Some(dialect) => Err(ParseError::Unsupported(warning.to_string(), dialect, warning.span())),
+        }
+    }
+
+    /// Records that `what` would have been accepted at the current position
+    fn note_expected(&mut self, what: impl Into<String>) {
+        if self.expected_at != self.position {
+            self.expected.clear();
+            self.expected_at = self.position;
+        }
+        let what = what.into();
+        if !self.expected.contains(&what) {
+            self.expected.push(what);
+        }
+    }
+
+    /// An Expected error at the next token, listing everything noted as valid there
+    fn expected_error(&self) -> ParseError {
+        let expected = if self.expected_at == self.position { self.expected.clone() } else { Vec::new() };
+        ParseError::Expected(expected, self.peek().cloned(), self.current_span())
+    }
+
+    /// Consumes the next token if it is `keyword`
+    fn parse_keyword(&mut self, keyword: Keyword) -> bool {
+        self.parse_token(&Token::Keyword(keyword))
+    }
+
+    /// Consumes the next token if it is `token`
+    fn parse_token(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.advance();
+            true
+        } else {
+            self.note_expected(token.to_string());
+            false
+        }
+    }
+
     fn expect_keyword(&mut self, keyword: Keyword) -> Result<(), ParseError> {
-        match self.advance() {
-            Some(Token::Keyword(k)) if *k == keyword => Ok(()),
-            Some(_) => Err(ParseError::ExpectedKeyword(format!("{:?}", keyword))),
-            None => Err(ParseError::UnexpectedEnd),
+        if self.parse_keyword(keyword.clone()) {
+            return Ok(());
         }
+        Err(self.or_misspelled_keyword(self.expected_error(), self.peek(), &[keyword]))
     }
 
-    fn expect_identifier(&mut self) -> Result<String, ParseError> {
-        match self.advance() {
-            Some(Token::Identifier(name)) => Ok(name.clone()),
-            Some(_) => Err(ParseError::ExpectedIdentifier),
-            None => Err(ParseError::UnexpectedEnd),
+    fn expect_token(&mut self, expected: Token) -> Result<(), ParseError> {
+        if self.parse_token(&expected) {
+            Ok(())
+        } else {
+            Err(self.expected_error())
+        }
+    }
+
+    fn expect_identifier(&mut self) -> Result<Ident, ParseError> {
+        let ident = match self.peek() {
+            Some(Token::Identifier(name)) => Ident::new(name.clone()).with_span(self.current_span()),
+            Some(Token::QuotedIdentifier { value, quote }) => {
+                if *quote != '"' {
+                    let supported_by = if *quote == '`' { [Dialect::MySql, Dialect::Sqlite] } else { [Dialect::MsSql, Dialect::Sqlite] };
+                    self.vendor_syntax(ParseWarning::NonstandardQuote(*quote, self.current_span()), &supported_by)?;
+                }
+                Ident::quoted(value.clone(), *quote).with_span(self.current_span())
+            }
+            Some(Token::Keyword(keyword)) if !keyword.is_reserved() => {
+                self.warnings.push(ParseWarning::KeywordAsIdentifier(keyword.clone(), self.current_span()));
+                self.keyword_name(self.position, keyword)
+            }
+            _ => {
+                self.note_expected("an identifier");
+                return Err(self.expected_error());
+            }
+        };
+        self.advance();
+        Ok(ident)
+    }
+
+    /// True if the token `n` ahead is the identifier `word` (case-insensitive)
+    fn peek_word(&self, n: usize, word: &str) -> bool {
+        matches!(self.peek_nth(n), Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(word))
+    }
+
+    /// Consumes the next token if it is the identifier `word` (case-insensitive)
+    fn parse_word(&mut self, word: &str) -> bool {
+        let found = self.peek_word(0, word);
+        if found {
+            self.advance();
+        } else {
+            self.note_expected(word.to_uppercase());
+        }
+        found
+    }
+
+    /// Consumes the identifier `word` (case-insensitive) or fails with an Expected error
+    fn expect_word(&mut self, word: &str) -> Result<(), ParseError> {
+        if self.parse_word(word) {
+            Ok(())
+        } else {
+            Err(self.expected_error())
+        }
+    }
+
+    /// After an item in a parenthesized list: true after a ',', false after the closing ')'
+    fn parse_list_separator(&mut self) -> Result<bool, ParseError> {
+        if self.parse_token(&Token::Comma) {
+            Ok(true)
+        } else if self.parse_token(&Token::RightParentheses) {
+            Ok(false)
+        } else {
+            Err(self.expected_error())
+        }
+    }
+
+    /// Parses a parenthesized, comma-separated list of column names
+    fn parse_identifier_list(&mut self) -> Result<Vec<Ident>, ParseError> {
+        self.expect_token(Token::LeftParentheses)?;
+        let mut names = Vec::new();
+        loop {
+            names.push(self.expect_identifier()?);
+            if !self.parse_list_separator()? {
+                break;
+            }
+        }
+        Ok(names)
+    }
+
+    /// Runs `parse`, and says in any error it returns that it happened in `context`, e.g.
+    /// "the WHERE clause of SELECT"
+    fn in_context<T>(
+        &mut self,
+        context: impl Into<String>,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        parse(self).map_err(|error| error.in_context(context))
+    }
+
+    /// Runs `parse` with a Pratt parser over the remaining tokens and moves past what it consumed.
+    /// Errors become `error`, pointing at the last token the Pratt parser read.
+    fn run_pratt<T>(&mut self, parse: impl FnOnce(&mut PrattParser<'a>) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        // advance() steps past the end on purpose, so the position may be out of range here
+        let remaining_tokens = self.tokens.get(self.position..).unwrap_or(&[]);
+        let remaining_spans = self.spans.get(self.position..).unwrap_or(&[]);
+        let mut expr_parser = PrattParser::new(remaining_tokens)
+            .with_spans(remaining_spans)
+            .with_source_at(self.source, self.source_start)
+            .with_max_depth(self.max_depth);
+        let result = parse(&mut expr_parser)?;
+        self.position += expr_parser.position();
+        self.warnings.extend(expr_parser.take_warnings());
+        // What could have continued the expression is also valid here
+        for what in expr_parser.expected() {
+            self.note_expected(what.clone());
+        }
+        Ok(result)
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, ParseError> {
+        self.run_pratt(|p| p.parse_expression(1))
+    }
+
+    /// Parses an expression in `context` into `slot`, which keeps its Error placeholder if this fails
+    fn parse_expression_into(&mut self, slot: &mut Expression, context: impl Into<String>) -> Result<(), ParseError> {
+        *slot = self.in_context(context, |p| p.parse_expression())?;
+        Ok(())
+    }
+
+    /// `statement` if `result` is Ok, and otherwise the error, keeping `statement` as the part
+    /// that was parsed for `take_partial`
+    fn finish(&mut self, result: Result<(), ParseError>, statement: Statement) -> Result<Statement, ParseError> {
+        match result {
+            Ok(()) => Ok(statement),
+            Err(error) => {
+                self.partial = Some(statement);
+                Err(error)
+            }
         }
     }
 
     /// Parses a single top-level SQL statement by dispatching to the appropriate handler
     pub fn parse_statement(&mut self) -> Result<Statement, ParseError> {
+        let start = self.position;
+        self.parse_statement_kind().map_err(|error| self.explain_failure(error, start))
+    }
+
+    /// Parses one statement that must make up the whole input, optionally followed by a
+    /// semicolon. If something follows it, the statement is kept for `take_partial`.
+    pub fn parse_single_statement(&mut self) -> Result<Statement, ParseError> {
+        let statement = self.parse_statement()?;
+        self.parse_token(&Token::Semicolon);
+        if self.peek_end_of_input() {
+            Ok(statement)
+        } else {
+            self.partial = Some(statement);
+            Err(self.expected_error())
+        }
+    }
+
+    /// Parses statements separated by semicolons up to the end of the input, skipping empty
+    /// ones. After an error, parsing resumes after the next semicolon, so every statement that
+    /// fails is reported.
+    pub fn parse_statements(&mut self) -> (Vec<Statement>, Vec<ParseError>) {
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+        loop {
+            while self.parse_token(&Token::Semicolon) {}
+            if matches!(self.peek(), None | Some(Token::Eof)) {
+                break;
+            }
+            let result = self.parse_statement().and_then(|statement| {
+                // Anything else before the semicolon would otherwise be silently dropped
+                if self.parse_token(&Token::Semicolon) || self.peek_end_of_input() {
+                    Ok(statement)
+                } else {
+                    Err(self.expected_error())
+                }
+            });
+            match result {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    errors.push(error);
+                    while !matches!(self.advance(), None | Some(Token::Semicolon) | Some(Token::Eof)) {}
+                }
+            }
+        }
+        (statements, errors)
+    }
+
+    /// True at the end of the input; otherwise notes that it was expected
+    fn peek_end_of_input(&mut self) -> bool {
+        if matches!(self.peek(), None | Some(Token::Eof)) {
+            return true;
+        }
+        self.note_expected(Token::Eof.to_string());
+        false
+    }
+
+    /// A statement that fails at or after an unbalanced delimiter most likely failed because of
+    /// it, so that is reported instead of the "Expected ')'" or end of input found much later
+    fn explain_failure(&self, error: ParseError, start: usize) -> ParseError {
+        let tokens = self.tokens.get(start..).unwrap_or(&[]);
+        let end = tokens.iter().position(|token| *token == Token::Semicolon).unwrap_or(tokens.len());
+        let spans = self.spans.get(start..).unwrap_or(&[]);
+        match unbalanced_delimiter(&tokens[..end], spans) {
+            Some(delimiter) if delimiter.span().start.offset <= error.span().start.offset => delimiter,
+            _ => error,
+        }
+    }
+
+    fn parse_statement_kind(&mut self) -> Result<Statement, ParseError> {
         // Peek at the current token to decide which kind of statement we're dealing with
         match self.peek() {
             Some(Token::Keyword(Keyword::Select)) => self.parse_select(),         // Handle SELECT
             Some(Token::Keyword(Keyword::Create)) => self.parse_create_table(),   // Handle CREATE TABLE
             Some(Token::Keyword(Keyword::Insert)) => self.parse_insert(),         // Handle INSERT INTO
-            Some(tok) => Err(ParseError::UnknownStartOfStatement(format!("{:?}", tok))), // Unknown keyword
-            None => Err(ParseError::General("Empty input".to_string())),         // No tokens to parse
+            Some(tok) => {
+                // Unknown keyword
+                let error = ParseError::UnknownStartOfStatement(tok.to_string(), self.current_span());
+                Err(self.or_misspelled_keyword(error, Some(tok), &[Keyword::Select, Keyword::Create, Keyword::Insert]))
+            }
+            None => Err(ParseError::General("Empty input".to_string(), self.current_span())),         // No tokens to parse
         }
     }
 
     fn parse_select(&mut self) -> Result<Statement, ParseError> {
-        self.expect_keyword(Keyword::Select)?;
-
+        let start = self.position;
         let mut columns = Vec::new();
+        let mut from = Box::new(TableWithJoins::new(TableFactor::new(Ident::new(""))));
+        let mut selection = None;
+        let mut order_by = None;
+        let result = self.parse_select_into(&mut columns, &mut from, &mut selection, &mut order_by);
+        let span = self.span_from(start);
+        self.finish(result, Statement::Select { columns, from, selection, order_by, span })
+    }
+
+    fn parse_select_into(
+        &mut self,
+        columns: &mut Vec<Expression>,
+        from: &mut TableWithJoins,
+        selection: &mut Option<Expression>,
+        order_by: &mut Option<Vec<OrderByExpr>>,
+    ) -> Result<(), ParseError> {
+        self.expect_keyword(Keyword::Select)?;
 
         loop {
-            match self.advance() {
-                Some(Token::Identifier(name)) => columns.push(name.clone()),
-                Some(Token::Comma) => continue,
-                Some(Token::Keyword(Keyword::From)) => break,
-                Some(tok) => {
-                    return Err(ParseError::General(format!("Unexpected token in column list: {:?}", tok)))
-                }
-                None => {
-                    return Err(ParseError::General("Unexpected end of input while reading columns.".to_string()))
-                }
+            if self.peek() == Some(&Token::Multiply) {
+                self.advance();
+                columns.push(Expression::Wildcard(NodeSpan(self.previous_span())));
+            } else {
+                let context = format!("column {} of SELECT", columns.len() + 1);
+                columns.push(Expression::Error);
+                self.parse_expression_into(columns.last_mut().unwrap(), context)?;
             }
+            if self.parse_token(&Token::Comma) {
+                continue;
+            }
+            if self.parse_keyword(Keyword::From) {
+                break;
+            }
+            return Err(self.or_misspelled_keyword(self.expected_error(), self.peek(), &[Keyword::From]));
         }
 
-        let table = self.expect_identifier()?;
+        self.parse_from_into(from)?;
+
+        // A misspelled WHERE or ORDER would otherwise be left unread without complaint
+        if let Some(Token::Identifier(word)) = self.peek() {
+            if let Some(keyword) = suggest_keyword(word, &[Keyword::Where, Keyword::Order]) {
+                return Err(ParseError::MisspelledKeyword(word.clone(), keyword, self.current_span()));
+            }
+        }
 
-        let mut selection = None;
         if let Some(Token::Keyword(Keyword::Where)) = self.peek() {
             self.advance();
-            let remaining_tokens = &self.tokens[self.position..];
-            let mut expr_parser = PrattParser::new(remaining_tokens);
-            let expr = expr_parser
-                .parse_expression(1)
-                .map_err(ParseError::InvalidExpression)?;
-            selection = Some(expr);
+            let selection = selection.insert(Expression::Error);
+            self.parse_expression_into(selection, "the WHERE clause of SELECT")?;
         }
 
-        let mut order_by = None;
         if let Some(Token::Keyword(Keyword::Order)) = self.peek() {
             self.advance();
             self.expect_keyword(Keyword::By)?;
 
-            let mut columns = Vec::new();
+            let exprs = order_by.insert(Vec::new());
             loop {
-                match self.advance() {
-                    Some(Token::Identifier(name)) => columns.push(name.clone()),
-                    Some(Token::Comma) => continue,
-                    Some(Token::Semicolon) | Some(Token::Eof) => break,
-                    Some(tok) => {
-                        return Err(ParseError::General(format!("Unexpected token in ORDER BY: {:?}", tok)))
-                    }
-                    None => {
-                        return Err(ParseError::UnexpectedEnd);
-                    }
+                let context = format!("item {} of ORDER BY", exprs.len() + 1);
+                exprs.push(OrderByExpr { expr: Expression::Error, asc: None, span: NodeSpan::default() });
+                let item = self.in_context(context, |p| p.run_pratt(|p| p.parse_order_by_expr()))?;
+                *exprs.last_mut().unwrap() = item;
+                if self.parse_token(&Token::Comma) {
+                    continue;
                 }
+                // The statement ends here; parse_statements consumes the ';'
+                self.note_expected(Token::Semicolon.to_string());
+                if self.peek() == Some(&Token::Semicolon) || self.peek_end_of_input() {
+                    break;
+                }
+                return Err(self.expected_error());
             }
-            order_by = Some(columns);
         }
+        Ok(())
+    }
 
-        Ok(Statement::Select {
-            columns,
-            table,
-            selection,
-            order_by,
-        })
+    /// The FROM clause, with its JOINs as far as they parse for the partial statement
+    fn parse_from_into(&mut self, from: &mut TableWithJoins) -> Result<(), ParseError> {
+        from.relation = self.parse_table_factor()?;
+        while let Some(join) = self.parse_join()? {
+            from.joins.push(join);
+        }
+        Ok(())
+    }
+
+    /// A table name in FROM or JOIN, then its alias with or without AS
+    fn parse_table_factor(&mut self) -> Result<TableFactor, ParseError> {
+        let start = self.position;
+        let name = self.expect_identifier()?;
+        // Not noted as expected, so errors after a table do not list AS
+        let alias = if self.peek() == Some(&Token::Keyword(Keyword::As)) {
+            self.advance();
+            Some(self.expect_identifier()?)
+        } else if self.peek_alias() {
+            Some(self.expect_identifier()?)
+        } else {
+            None
+        };
+        Ok(TableFactor { name, alias, span: self.span_from(start) })
+    }
+
+    /// True if the next token is an alias written without AS: a name that neither starts a JOIN
+    /// nor looks like a misspelled WHERE or ORDER
+    fn peek_alias(&self) -> bool {
+        match self.peek() {
+            Some(Token::QuotedIdentifier { .. }) => true,
+            Some(Token::Identifier(word)) => {
+                !JOIN_WORDS.iter().any(|join| word.eq_ignore_ascii_case(join))
+                    && suggest_keyword(word, &[Keyword::Where, Keyword::Order]).is_none()
+            }
+            _ => false,
+        }
+    }
+
+    /// A JOIN and the table it joins, or None if the FROM clause has no more. JOIN and the words
+    /// before it are matched as names, so LEFT and RIGHT still call functions elsewhere.
+    fn parse_join(&mut self) -> Result<Option<Join>, ParseError> {
+        let start = self.position;
+        let word = match self.peek() {
+            Some(Token::Identifier(word)) => word.to_uppercase(),
+            _ => return Ok(None),
+        };
+        // None for a CROSS JOIN, which takes no condition
+        let join_operator: Option<fn(JoinConstraint) -> JoinOperator> = match word.as_str() {
+            "JOIN" | "INNER" => Some(JoinOperator::Inner),
+            "LEFT" => Some(JoinOperator::LeftOuter),
+            "RIGHT" => Some(JoinOperator::RightOuter),
+            "FULL" => Some(JoinOperator::FullOuter),
+            "CROSS" => None,
+            _ => return Ok(None),
+        };
+        self.advance();
+        if matches!(word.as_str(), "LEFT" | "RIGHT" | "FULL") {
+            self.parse_word("OUTER");
+        }
+        if word != "JOIN" {
+            self.expect_word("JOIN")?;
+        }
+        let relation = self.parse_table_factor()?;
+        let Some(join_operator) = join_operator else {
+            return Ok(Some(Join { relation, join_operator: JoinOperator::Cross, span: self.span_from(start) }));
+        };
+        let constraint = if self.parse_keyword(Keyword::On) {
+            JoinConstraint::On(self.in_context("the ON condition of JOIN", |p| p.parse_expression())?)
+        } else if self.parse_word("USING") {
+            JoinConstraint::Using(self.parse_identifier_list()?)
+        } else {
+            return Err(self.expected_error());
+        };
+        Ok(Some(Join { relation, join_operator: join_operator(constraint), span: self.span_from(start) }))
     }
 
     fn parse_create_table(&mut self) -> Result<Statement, ParseError> {
+        let start = self.position;
+        let mut table_name = Ident::new("");
+        let mut columns = Vec::new();
+        let mut constraints = Vec::new();
+        let mut options = Vec::new();
+        let result = self.parse_create_table_into(&mut table_name, &mut columns, &mut constraints, &mut options);
+        let span = self.span_from(start);
+        self.finish(result, Statement::CreateTable { table_name, columns, constraints, options, span })
+    }
+
+    /// A definition that fails to parse is left out of the partial statement
+    fn parse_create_table_into(
+        &mut self,
+        table_name: &mut Ident,
+        columns: &mut Vec<ColumnDef>,
+        constraints: &mut Vec<TableConstraint>,
+        options: &mut Vec<TableOption>,
+    ) -> Result<(), ParseError> {
         self.expect_keyword(Keyword::Create)?;
         self.expect_keyword(Keyword::Table)?;
 
-        let table_name = self.expect_identifier()?;
+        *table_name = self.expect_identifier()?;
 
-        self.expect_keyword(Keyword::LeftParen)?;
+        self.expect_token(Token::LeftParentheses)?;
 
-        let mut columns = Vec::new();
+        // Whether the next element is due, after the '(' or a ','; the list may not be empty and
+        // no element may be left out, as in (a INT,, b INT)
+        let mut element_due = true;
         loop {
-            match self.advance() {
-                Some(Token::Identifier(col_name)) => {
-                    let name = col_name.clone(); // clone to end borrow
-                    let col_type = self.parse_column_type()?;
-                        columns.push(ColumnDef {
-                        name,
-                        data_type: col_type
-                    });
+            let starts_definition = match self.peek() {
+                Some(Token::Identifier(_)) | Some(Token::QuotedIdentifier { .. }) => true,
+                Some(Token::Keyword(keyword)) => !keyword.is_reserved() || matches!(
+                    keyword,
+                    Keyword::Constraint | Keyword::Primary | Keyword::Unique | Keyword::Check | Keyword::Foreign
+                ),
+                _ => false,
+            };
+            if starts_definition && !element_due {
+                self.vendor_syntax(ParseWarning::MissingComma(self.current_span()), &[])?;
+            }
+            match self.peek() {
+                Some(Token::Keyword(
+                    Keyword::Constraint | Keyword::Primary | Keyword::Unique | Keyword::Check | Keyword::Foreign,
+                )) => {
+                    let context = format!("constraint {} of CREATE TABLE", constraints.len() + 1);
+                    constraints.push(self.in_context(context, |p| p.parse_table_constraint())?);
+                    element_due = false;
                 }
-                Some(Token::Comma) => continue,
-                Some(Token::RightParen) => break,
-                Some(tok) => {
-                    return Err(ParseError::General(format!("Unexpected token: {:?}", tok)))
+                Some(Token::Identifier(_)) | Some(Token::QuotedIdentifier { .. }) => {
+                    let context = format!("column definition {} of CREATE TABLE", columns.len() + 1);
+                    columns.push(self.in_context(context, |p| p.parse_column_def())?);
+                    element_due = false;
                 }
-                None => {
-                    return Err(ParseError::UnexpectedEnd);
+                Some(Token::Keyword(keyword)) if !keyword.is_reserved() => {
+                    let context = format!("column definition {} of CREATE TABLE", columns.len() + 1);
+                    columns.push(self.in_context(context, |p| p.parse_column_def())?);
+                    element_due = false;
+                }
+                Some(Token::Comma) if !element_due => {
+                    self.advance();
+                    element_due = true;
+                }
+                Some(Token::RightParentheses) if !element_due => {
+                    self.advance();
+                    break;
+                }
+                _ if element_due => {
+                    self.note_expected("a column definition");
+                    self.note_expected("a table constraint");
+                    return Err(self.expected_error());
+                }
+                _ => {
+                    self.note_expected("a column definition");
+                    self.note_expected("a table constraint");
+                    self.note_expected(Token::Comma.to_string());
+                    self.note_expected(Token::RightParentheses.to_string());
+                    return Err(self.expected_error());
                 }
             }
         }
 
-        Ok(Statement::CreateTable {
-            table_name,
-            columns,
+        *options = self.in_context("the table options of CREATE TABLE", |p| p.parse_table_options())?;
+        Ok(())
+    }
+
+    fn parse_column_def(&mut self) -> Result<ColumnDef, ParseError> {
+        let start = self.position;
+        let name = self.expect_identifier()?;
+        let data_type = self.parse_column_type()?;
+        let options = self.parse_column_options()?;
+        Ok(ColumnDef {
+            name,
+            data_type,
+            options,
+            span: self.span_from(start),
         })
     }
 
-    fn parse_column_type(&mut self) -> Result<DataType, ParseError> {
+    /// Parses a table-level constraint, optionally introduced by CONSTRAINT name
+    fn parse_table_constraint(&mut self) -> Result<TableConstraint, ParseError> {
+        let start = self.position;
+        let mut name = None;
+        if let Some(Token::Keyword(Keyword::Constraint)) = self.peek() {
+            self.advance();
+            name = Some(self.expect_identifier()?);
+        }
+
         match self.advance() {
-            Some(Token::Keyword(Keyword::Int)) => Ok(DataType::Int),
-            Some(Token::Keyword(Keyword::Varchar)) => {
-                if let Some(Token::LeftParen) = self.peek() {
+            Some(Token::Keyword(Keyword::Primary)) => {
+                self.expect_keyword(Keyword::Key)?;
+                let columns = self.parse_identifier_list()?;
+                Ok(TableConstraint::PrimaryKey { name, columns, span: self.span_from(start) })
+            }
+            Some(Token::Keyword(Keyword::Unique)) => {
+                let columns = self.parse_identifier_list()?;
+                Ok(TableConstraint::Unique { name, columns, span: self.span_from(start) })
+            }
+            Some(Token::Keyword(Keyword::Check)) => {
+                self.expect_token(Token::LeftParentheses)?;
+                let expr = self.parse_expression()?;
+                self.expect_token(Token::RightParentheses)?;
+                Ok(TableConstraint::Check { name, expr, span: self.span_from(start) })
+            }
+            Some(Token::Keyword(Keyword::Foreign)) => {
+                self.expect_keyword(Keyword::Key)?;
+                let columns = self.parse_identifier_list()?;
+                self.expect_keyword(Keyword::References)?;
+                let references = self.parse_references()?;
+                Ok(TableConstraint::ForeignKey { name, columns, references, span: self.span_from(start) })
+            }
+            Some(tok) => Err(ParseError::General(format!("Expected a table constraint, found {}", tok), self.previous_span())),
+            None => Err(ParseError::UnexpectedEnd(self.previous_span())),
+        }
+    }
+
+    /// Column types share the type grammar used by `::` casts
+    fn parse_column_type(&mut self) -> Result<DataType, ParseError> {
+        self.run_pratt(|p| p.parse_data_type())
+    }
+
+    /// Reads the options after a column's type until the next ',' or ')'
+    fn parse_column_options(&mut self) -> Result<Vec<ColumnOption>, ParseError> {
+        let mut options = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Keyword(Keyword::Not)) => {
                     self.advance();
-                    if let Some(Token::Number(n)) = self.advance() {
-                        let size = *n;
-                        if let Some(Token::RightParen) = self.advance() {
-                            return Ok(DataType::Varchar(size as usize));
-                        }
+                    self.expect_keyword(Keyword::Null)?;
+                    options.push(ColumnOption::NotNull);
+                }
+                Some(Token::Keyword(Keyword::Null)) => {
+                    self.advance();
+                    options.push(ColumnOption::Null);
+                }
+                Some(Token::Keyword(Keyword::Primary)) => {
+                    self.advance();
+                    self.expect_keyword(Keyword::Key)?;
+                    options.push(ColumnOption::PrimaryKey);
+                }
+                Some(Token::Keyword(Keyword::Unique)) => {
+                    self.advance();
+                    options.push(ColumnOption::Unique);
+                }
+                Some(Token::Keyword(Keyword::Default)) => {
+                    self.advance();
+                    options.push(ColumnOption::Default(self.parse_expression()?));
+                }
+                Some(Token::Keyword(Keyword::Check)) => {
+                    self.advance();
+                    self.expect_token(Token::LeftParentheses)?;
+                    options.push(ColumnOption::Check(self.parse_expression()?));
+                    self.expect_token(Token::RightParentheses)?;
+                }
+                Some(Token::Keyword(Keyword::References)) => {
+                    self.advance();
+                    options.push(ColumnOption::References(self.parse_references()?));
+                }
+                Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("GENERATED") => {
+                    self.advance();
+                    let always = if self.parse_word("ALWAYS") {
+                        true
+                    } else if self.parse_keyword(Keyword::By) {
+                        self.expect_keyword(Keyword::Default)?;
+                        false
+                    } else {
+                        return Err(self.expected_error());
+                    };
+                    self.expect_keyword(Keyword::As)?;
+                    if self.parse_word("IDENTITY") {
+                        options.push(self.parse_identity(always)?);
+                    } else if always {
+                        options.push(self.parse_generated_column()?);
+                    } else {
+                        return Err(self.expected_error());
                     }
                 }
-                Err(ParseError::General("Expected size for Varchar".to_string()))
+                Some(Token::Keyword(Keyword::As)) => {
+                    self.advance();
+                    options.push(self.parse_generated_column()?);
+                }
+                Some(Token::Identifier(word))
+                    if word.eq_ignore_ascii_case("AUTO_INCREMENT") || word.eq_ignore_ascii_case("AUTOINCREMENT") =>
+                {
+                    // MySQL spells it AUTO_INCREMENT and SQLite AUTOINCREMENT
+                    let supported_by = if word.contains('_') { Dialect::MySql } else { Dialect::Sqlite };
+                    self.vendor_syntax(ParseWarning::NonstandardSyntax(word.to_uppercase(), self.current_span()), &[supported_by])?;
+                    self.advance();
+                    options.push(ColumnOption::AutoIncrement);
+                }
+                _ => {
+                    self.note_expected("a column option");
+                    break;
+                }
             }
-            Some(Token::Keyword(Keyword::Boolean)) => Ok(DataType::Boolean),
-            Some(tok) => Err(ParseError::General(format!("Unexpected column type: {:?}", tok))),
-            None => Err(ParseError::UnexpectedEnd),
         }
+        Ok(options)
     }
 
-    fn parse_insert(&mut self) -> Result<Statement, ParseError> {
-        self.expect_keyword(Keyword::Insert)?;
-        self.expect_keyword(Keyword::Into)?;
+    /// Parses the (expr) [STORED | VIRTUAL] that follows AS in a generated column
+    fn parse_generated_column(&mut self) -> Result<ColumnOption, ParseError> {
+        self.expect_token(Token::LeftParentheses)?;
+        let expr = self.parse_expression()?;
+        self.expect_token(Token::RightParentheses)?;
 
-        let table_name = self.expect_identifier()?;
+        let storage = if self.parse_word("STORED") {
+            Some(GeneratedStorage::Stored)
+        } else if self.parse_word("VIRTUAL") {
+            Some(GeneratedStorage::Virtual)
+        } else {
+            None
+        };
+        Ok(ColumnOption::Generated { expr, storage })
+    }
 
-        self.expect_keyword(Keyword::LeftParen)?;
+    /// Parses the optional (START WITH n INCREMENT BY n) after AS IDENTITY
+    fn parse_identity(&mut self, always: bool) -> Result<ColumnOption, ParseError> {
+        let mut start = None;
+        let mut increment = None;
+        if self.peek() == Some(&Token::LeftParentheses) {
+            self.advance();
+            loop {
+                if self.parse_word("START") {
+                    self.parse_word("WITH");
+                    start = Some(self.parse_sequence_number()?);
+                } else if self.parse_word("INCREMENT") {
+                    if self.peek() == Some(&Token::Keyword(Keyword::By)) {
+                        self.advance();
+                    }
+                    increment = Some(self.parse_sequence_number()?);
+                } else {
+                    break;
+                }
+            }
+            self.expect_token(Token::RightParentheses)?;
+        }
+        Ok(ColumnOption::Identity { always, start, increment })
+    }
 
-        let mut columns = Vec::new();
+    fn parse_sequence_number(&mut self) -> Result<i64, ParseError> {
+        let negative = self.peek() == Some(&Token::Minus);
+        if negative {
+            self.advance();
+        }
+        match self.advance() {
+            Some(Token::Number(n)) => {
+                let text = if negative { format!("-{}", n) } else { n.clone() };
+                text.parse::<i64>()
+                    .map_err(|_| ParseError::General(format!("Invalid sequence value: {}", text), self.previous_span()))
+            }
+            Some(tok) => Err(ParseError::General(format!("Expected a number, found {}", tok), self.previous_span())),
+            None => Err(ParseError::UnexpectedEnd(self.previous_span())),
+        }
+    }
+
+    /// Parses the options after the closing ')' of CREATE TABLE until something else appears
+    fn parse_table_options(&mut self) -> Result<Vec<TableOption>, ParseError> {
+        let mut options = Vec::new();
         loop {
-            match self.advance() {
-                Some(Token::Identifier(col_name)) => columns.push(col_name.to_string()),
-                Some(Token::Comma) => continue,
-                Some(Token::RightParen) => break,
-                Some(tok) => {
-                    return Err(ParseError::General(format!("Unexpected token in column list: {:?}", tok)))
+            let start = self.position;
+            let span = self.current_span();
+            if self.parse_word("WITH") {
+                // Postgres storage parameters: WITH (name = value, ...)
+                self.vendor_syntax(ParseWarning::NonstandardSyntax("WITH (...) after CREATE TABLE".to_string(), span), &[Dialect::Postgres])?;
+                self.expect_token(Token::LeftParentheses)?;
+                loop {
+                    let start = self.position;
+                    let name = self.expect_identifier()?.value;
+                    self.expect_token(Token::Equal)?;
+                    let value = self.parse_option_value()?;
+                    options.push(TableOption { name, value, span: self.span_from(start) });
+                    if !self.parse_list_separator()? {
+                        break;
+                    }
                 }
-                None => {
-                    return Err(ParseError::UnexpectedEnd);
+            } else if self.parse_word("PARTITION") {
+                self.vendor_syntax(ParseWarning::NonstandardSyntax("PARTITION BY".to_string(), span), &[Dialect::Postgres, Dialect::MySql])?;
+                self.expect_keyword(Keyword::By)?;
+                let method = self.expect_identifier()?.value.to_uppercase();
+                let columns: Vec<String> = self.parse_identifier_list()?.into_iter().map(|column| column.value).collect();
+                options.push(TableOption {
+                    name: "PARTITION BY".to_string(),
+                    value: format!("{} ({})", method, columns.join(", ")),
+                    span: self.span_from(start),
+                });
+            } else {
+                match self.peek() {
+                    Some(Token::Comma) => {
+                        // MySQL allows commas between table options
+                        self.advance();
+                    }
+                    Some(Token::Identifier(_))
+                    | Some(Token::Keyword(Keyword::Default))
+                    | Some(Token::Keyword(Keyword::Collate)) => {
+                        // Words without a following `=` are not a table option; leave them unread
+                        let checkpoint = self.checkpoint();
+                        match self.parse_table_option() {
+                            Ok(option) => {
+                                let what = format!("Table option {}", option.name);
+                                self.vendor_syntax(ParseWarning::NonstandardSyntax(what, span), &[Dialect::MySql])?;
+                                options.push(option);
+                            }
+                            Err(ParseError::Expected(..)) => {
+                                self.rollback(checkpoint);
+                                break;
+                            }
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    _ => break,
                 }
             }
         }
+        Ok(options)
+    }
 
-        self.expect_keyword(Keyword::Values)?;
+    /// Parses a MySQL style `[DEFAULT] NAME [WORDS...] = value` option
+    fn parse_table_option(&mut self) -> Result<TableOption, ParseError> {
+        let start = self.position;
+        let mut words = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Identifier(word)) => words.push(word.to_uppercase()),
+                Some(Token::Keyword(Keyword::Default)) => words.push("DEFAULT".to_string()),
+                Some(Token::Keyword(Keyword::Collate)) => words.push("COLLATE".to_string()),
+                _ => {
+                    self.expect_token(Token::Equal)?;
+                    break;
+                }
+            }
+            self.advance();
+        }
+        let value = self.parse_option_value()?;
+        Ok(TableOption {
+            name: words.join(" "),
+            value,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_option_value(&mut self) -> Result<String, ParseError> {
+        match self.advance() {
+            Some(Token::Identifier(value)) | Some(Token::Number(value)) | Some(Token::String(value)) => Ok(value.clone()),
+            Some(Token::Keyword(Keyword::True)) => Ok("true".to_string()),
+            Some(Token::Keyword(Keyword::False)) => Ok("false".to_string()),
+            Some(tok) => Err(ParseError::General(format!("Unexpected option value: {}", tok), self.previous_span())),
+            None => Err(ParseError::UnexpectedEnd(self.previous_span())),
+        }
+    }
+
+    /// Parses what follows REFERENCES: table [(columns)] [ON DELETE action] [ON UPDATE action]
+    fn parse_references(&mut self) -> Result<ForeignKeyReference, ParseError> {
+        // The span starts at the REFERENCES keyword just consumed
+        let start = self.position - 1;
+        let table = self.expect_identifier()?;
+        let columns = if self.peek() == Some(&Token::LeftParentheses) {
+            self.parse_identifier_list()?
+        } else {
+            Vec::new()
+        };
+
+        let mut on_delete = None;
+        let mut on_update = None;
+        while self.peek() == Some(&Token::Keyword(Keyword::On)) {
+            self.advance();
+            if self.parse_word("DELETE") {
+                on_delete = Some(self.parse_referential_action()?);
+            } else if self.parse_word("UPDATE") {
+                on_update = Some(self.parse_referential_action()?);
+            } else {
+                return Err(self.expected_error());
+            }
+        }
+
+        Ok(ForeignKeyReference {
+            table,
+            columns,
+            on_delete,
+            on_update,
+            span: self.span_from(start),
+        })
+    }
 
-        self.expect_keyword(Keyword::LeftParen)?;
+    fn parse_referential_action(&mut self) -> Result<ReferentialAction, ParseError> {
+        if self.parse_word("CASCADE") {
+            Ok(ReferentialAction::Cascade)
+        } else if self.parse_word("RESTRICT") {
+            Ok(ReferentialAction::Restrict)
+        } else if self.peek_word(0, "NO") && self.peek_word(1, "ACTION") {
+            self.position += 2;
+            Ok(ReferentialAction::NoAction)
+        } else if self.parse_word("SET") {
+            if self.parse_keyword(Keyword::Null) {
+                Ok(ReferentialAction::SetNull)
+            } else if self.parse_keyword(Keyword::Default) {
+                Ok(ReferentialAction::SetDefault)
+            } else {
+                Err(self.expected_error())
+            }
+        } else {
+            self.note_expected("NO ACTION");
+            Err(self.expected_error())
+        }
+    }
 
+    fn parse_insert(&mut self) -> Result<Statement, ParseError> {
+        let start = self.position;
+        let mut or = None;
+        let mut table_name = Ident::new("");
+        let mut columns = Vec::new();
         let mut values = Vec::new();
+        let result = self.parse_insert_into(&mut or, &mut table_name, &mut columns, &mut values);
+        let span = self.span_from(start);
+        self.finish(result, Statement::Insert { or, table_name, columns, values, span })
+    }
+
+    /// SQLite's OR REPLACE, OR IGNORE and so on after INSERT. OR is only read when the word after
+    /// it is one of the actions, so anything else is reported at the OR as a missing INTO.
+    fn parse_conflict_action(&mut self) -> Result<Option<ConflictAction>, ParseError> {
+        if self.peek() != Some(&Token::Keyword(Keyword::Or)) {
+            return Ok(None);
+        }
+        let action = match self.peek_nth(1) {
+            Some(Token::Identifier(word)) => match word.to_uppercase().as_str() {
+                "REPLACE" => ConflictAction::Replace,
+                "IGNORE" => ConflictAction::Ignore,
+                "ABORT" => ConflictAction::Abort,
+                "FAIL" => ConflictAction::Fail,
+                "ROLLBACK" => ConflictAction::Rollback,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let span = self.current_span();
+        self.vendor_syntax(ParseWarning::NonstandardSyntax(format!("INSERT OR {}", action), span), &[Dialect::Sqlite])?;
+        self.position += 2;
+        Ok(Some(action))
+    }
+
+    fn parse_insert_into(
+        &mut self,
+        or: &mut Option<ConflictAction>,
+        table_name: &mut Ident,
+        columns: &mut Vec<Ident>,
+        values: &mut Vec<Expression>,
+    ) -> Result<(), ParseError> {
+        self.expect_keyword(Keyword::Insert)?;
+        *or = self.parse_conflict_action()?;
+        self.expect_keyword(Keyword::Into)?;
+
+        *table_name = self.expect_identifier()?;
+
+        *columns = self.in_context("the column list of INSERT", |p| p.parse_identifier_list())?;
+
+        self.expect_keyword(Keyword::Values)?;
+
+        self.expect_token(Token::LeftParentheses)?;
+
         loop {
-            match self.advance() {
-                Some(Token::Number(n)) => values.push(Expression::Number(*n)),
-                Some(Token::String(s)) => values.push(Expression::String(s.to_string())),
-                Some(Token::Boolean(b)) => values.push(Expression::Boolean(*b)),
-                Some(Token::Null) => values.push(Expression::Null),
-                Some(Token::Identifier(s)) => values.push(Expression::Identifier(s.to_string())),
-                Some(Token::Comma) => continue,
-                Some(Token::RightParen) => break,
-                Some(tok) => {
-                    return Err(ParseError::General(format!("Unexpected token in VALUES: {:?}", tok)))
-                }
-                None => {
-                    return Err(ParseError::UnexpectedEnd);
+            let context = format!("value {} of INSERT", values.len() + 1);
+            values.push(Expression::Error);
+            self.parse_expression_into(values.last_mut().unwrap(), context)?;
+            if !self.parse_list_separator()? {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The first ')' or ']' that does not close the innermost open delimiter, or else the earliest
+/// '(' or '[' still open at the end
+fn unbalanced_delimiter(tokens: &[Token], spans: &[Span]) -> Option<ParseError> {
+    let mut open = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        let span = spans.get(index).copied().unwrap_or_default();
+        match token {
+            Token::LeftParentheses => open.push(('(', span)),
+            Token::LeftBracket => open.push(('[', span)),
+            Token::RightParentheses | Token::RightBracket => {
+                let (opening, closing) = if *token == Token::RightBracket { ('[', ']') } else { ('(', ')') };
+                match open.pop() {
+                    Some((c, _)) if c == opening => {}
+                    _ => return Some(ParseError::UnbalancedDelimiter(closing, span)),
                 }
             }
+            _ => {}
         }
+    }
+    open.first().map(|&(c, span)| ParseError::UnbalancedDelimiter(c, span))
+}
 
-        Ok(Statement::Insert {
-            table_name,
-            columns,
-            values,
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::BinaryOperator;
+    use crate::parse_sql;
+
+    #[test]
+    fn qualified_wildcards_mix_with_other_columns() {
+        let Statement::Select { columns, .. } = parse_sql("SELECT t.*, a, t.b, * FROM t").unwrap() else { panic!() };
+        assert!(matches!(&columns[0], Expression::QualifiedWildcard(parts, _) if parts[0].value == "t"));
+        assert!(matches!(&columns[1], Expression::Identifier(name) if name.value == "a"));
+        assert!(matches!(&columns[2], Expression::CompoundIdentifier(parts, _) if parts.len() == 2));
+        assert!(matches!(&columns[3], Expression::Wildcard(_)));
+
+        let sql = "SELECT u.*, o.total FROM users u JOIN orders o ON u.id = o.user_id";
+        let Statement::Select { columns, from, .. } = parse_sql(sql).unwrap() else { panic!() };
+        assert!(matches!(&columns[0], Expression::QualifiedWildcard(parts, _) if parts[0].value == "u"));
+        assert!(matches!(&columns[1], Expression::CompoundIdentifier(parts, _) if parts[1].value == "total"));
+        assert_eq!((from.relation.name.value.as_str(), from.relation.alias.as_ref().unwrap().value.as_str()), ("users", "u"));
+        let [join] = &from.joins[..] else { panic!() };
+        assert_eq!(join.relation.name.value, "orders");
+        let JoinOperator::Inner(JoinConstraint::On(Expression::BinaryOperation { operator: BinaryOperator::Equals, .. })) = &join.join_operator else { panic!() };
+        assert_eq!(&sql[join.span.0.start.offset..join.span.0.end.offset], "JOIN orders o ON u.id = o.user_id");
+    }
+
+    #[test]
+    fn joins_and_table_aliases() {
+        let Statement::Select { from, .. } = parse_sql(
+            "SELECT * FROM a AS x LEFT OUTER JOIN b ON x.id = b.id RIGHT JOIN c USING (id, k) FULL JOIN d ON TRUE CROSS JOIN e INNER JOIN f `F` ON 1 = 1",
+        ).unwrap() else { panic!() };
+        assert_eq!(from.relation.alias.as_ref().unwrap().value, "x");
+        let kinds: Vec<&str> = from.joins.iter().map(|join| match &join.join_operator {
+            JoinOperator::Inner(_) => "inner",
+            JoinOperator::LeftOuter(_) => "left",
+            JoinOperator::RightOuter(_) => "right",
+            JoinOperator::FullOuter(_) => "full",
+            JoinOperator::Cross => "cross",
+        }).collect();
+        assert_eq!(kinds, ["left", "right", "full", "cross", "inner"]);
+        assert!(matches!(from.joins[1].join_operator.constraint(), Some(JoinConstraint::Using(columns)) if columns.len() == 2));
+        assert_eq!(from.joins[4].relation.alias.as_ref().map(|alias| (alias.value.as_str(), alias.quote_style)), Some(("F", Some('`'))));
+
+        // LEFT and RIGHT stay function names outside FROM
+        assert!(parse_sql("SELECT left(name, 2) FROM t WHERE right(name, 1) = 'x'").is_ok());
+        // A JOIN needs its condition, and a misspelled WHERE is not taken for an alias
+        let error = parse_sql("SELECT * FROM a JOIN b WHERE a.x = 1").unwrap_err();
+        assert!(error.to_string().contains("Expected ON or USING"), "{}", error);
+        assert!(matches!(parse_sql("SELECT * FROM a JOIN b").unwrap_err(), ParseError::Expected(..)));
+        assert!(matches!(parse_sql("SELECT * FROM t whre a = 1").unwrap_err(), ParseError::MisspelledKeyword(_, Keyword::Where, _)));
+    }
+
+    #[test]
+    fn column_checks_stay_with_their_column() {
+        let sql = "CREATE TABLE t (a INT CHECK (a > 0) NOT NULL, b INT)";
+        let options = ParserOptions { strict: true, ..ParserOptions::default() };
+        let output = crate::parse_input_with_options(sql, &crate::Limits::default(), &options);
+        assert!(output.diagnostics.is_empty(), "{:?}", output.diagnostics);
+        let Some(Statement::CreateTable { columns, constraints, .. }) = output.value else { panic!() };
+        assert_eq!(columns.len(), 2);
+        assert!(constraints.is_empty());
+        assert!(matches!(&columns[0].options[..], [ColumnOption::Check(Expression::BinaryOperation { .. }), ColumnOption::NotNull]));
+    }
+
+    #[test]
+    fn lookahead_reads_ahead_without_consuming() {
+        let tokens: Vec<Token> = crate::tokenizer::tokenize("a key b").unwrap().into_iter().map(|t| t.token).collect();
+        let mut parser = SQLParser::new(&tokens);
+        assert_eq!(parser.peek_nth(2), Some(&Token::Identifier("b".to_string())));
+        assert_eq!(parser.peek_nth(3), Some(&Token::Eof));
+        assert_eq!(parser.peek_nth(4), None);
+        assert!(parser.peek_word(2, "B"));
+
+        // Rolling back undoes both the tokens read and the warnings given since the checkpoint
+        parser.advance();
+        let checkpoint = parser.checkpoint();
+        assert_eq!(parser.expect_identifier().unwrap().value, "key");
+        assert_eq!(parser.warnings.len(), 1);
+        parser.rollback(checkpoint);
+        assert_eq!(parser.peek(), Some(&Token::Keyword(Keyword::Key)));
+        assert!(parser.warnings.is_empty());
+    }
+
+    #[test]
+    fn insert_or_reads_the_conflict_action() {
+        let Statement::Insert { or, .. } = parse_sql("INSERT OR replace INTO t (a) VALUES (1)").unwrap() else { panic!() };
+        assert_eq!(or, Some(ConflictAction::Replace));
+
+        // OR followed by anything else is left for INTO to reject
+        let error = parse_sql("INSERT OR t (a) VALUES (1)").unwrap_err();
+        assert!(matches!(&error, ParseError::Expected(_, Some(Token::Keyword(Keyword::Or)), _)), "{:?}", error);
+
+        let parse = |dialect| {
+            let options = ParserOptions { dialect: Some(dialect), ..ParserOptions::default() };
+            crate::parse_input_with_options("INSERT OR IGNORE INTO t (a) VALUES (1)", &crate::Limits::default(), &options)
+        };
+        assert!(parse(Dialect::Sqlite).diagnostics.is_empty());
+        assert_eq!(parse(Dialect::Postgres).diagnostics[0].code(), "E0014");
+    }
+
+    #[test]
+    fn keywords_name_columns_where_the_grammar_allows() {
+        let sql = "CREATE TABLE t (key INT, value TEXT, order INT, values INT)";
+        let Statement::CreateTable { columns, .. } = parse_sql(sql).unwrap() else { panic!() };
+        let names: Vec<&str> = columns.iter().map(|column| column.name.value.as_str()).collect();
+        assert_eq!(names, ["key", "value", "order", "values"]);
+
+        let statement = parse_sql("SELECT key, order FROM order WHERE order > 1 ORDER BY order DESC").unwrap();
+        let Statement::Select { columns, from, selection, order_by, .. } = &statement else { panic!() };
+        assert!(matches!(&columns[1], Expression::Identifier(name) if name.value == "order"));
+        assert_eq!(from.relation.name.value, "order");
+        assert!(selection.is_some());
+        assert!(matches!(&order_by.as_ref().unwrap()[0].expr, Expression::Identifier(name) if name.value == "order"));
+        // Printed quoted, they cannot be read back as keywords
+        assert_eq!(statement.to_string(), "SELECT `key`, `order` FROM `order` WHERE `order` > 1 ORDER BY `order` DESC");
+
+        // Reserved words still need quotes
+        assert!(parse_sql("CREATE TABLE t (select INT)").is_err());
+    }
+
+    #[test]
+    fn auto_increment_is_a_column_option() {
+        let Statement::CreateTable { columns, .. } = parse_sql("CREATE TABLE t (a INT AUTO_INCREMENT, b SERIAL)").unwrap() else { panic!() };
+        assert_eq!(columns[0].options, [ColumnOption::AutoIncrement]);
+        assert!(columns[1].options.is_empty());
+    }
+
+    #[test]
+    fn not_null_and_primary_key_columns() {
+        let Statement::CreateTable { columns, .. } = parse_sql("CREATE TABLE t (id INT NOT NULL PRIMARY KEY, name TEXT NULL)").unwrap() else { panic!() };
+        assert_eq!(columns[0].options, [ColumnOption::NotNull, ColumnOption::PrimaryKey]);
+        assert_eq!(columns[1].options, [ColumnOption::Null]);
+    }
+
+    #[test]
+    fn unique_and_default_columns() {
+        let Statement::CreateTable { columns, .. } = parse_sql("CREATE TABLE t (name TEXT UNIQUE DEFAULT 'x', n INT DEFAULT -1)").unwrap() else { panic!() };
+        assert!(matches!(&columns[0].options[..], [ColumnOption::Unique, ColumnOption::Default(Expression::Literal(..))]));
+        assert!(matches!(&columns[1].options[..], [ColumnOption::Default(_)]));
+    }
+
+    #[test]
+    fn references_and_referential_actions() {
+        let sql = "CREATE TABLE t (p INT REFERENCES u (id) ON DELETE CASCADE ON UPDATE SET NULL, q INT REFERENCES v)";
+        let Statement::CreateTable { columns, .. } = parse_sql(sql).unwrap() else { panic!() };
+        let [ColumnOption::References(reference)] = &columns[0].options[..] else { panic!() };
+        assert_eq!((reference.table.value.as_str(), reference.columns.len()), ("u", 1));
+        assert_eq!((&reference.on_delete, &reference.on_update), (&Some(ReferentialAction::Cascade), &Some(ReferentialAction::SetNull)));
+        let [ColumnOption::References(reference)] = &columns[1].options[..] else { panic!() };
+        assert!(reference.columns.is_empty() && reference.on_delete.is_none());
+    }
+
+    #[test]
+    fn table_constraints() {
+        let sql = "CREATE TABLE t (a INT, b INT, CONSTRAINT pk PRIMARY KEY (a, b), UNIQUE (b), \
+                   FOREIGN KEY (b) REFERENCES u ON DELETE NO ACTION)";
+        let Statement::CreateTable { constraints, .. } = parse_sql(sql).unwrap() else { panic!() };
+        let TableConstraint::PrimaryKey { name, columns, .. } = &constraints[0] else { panic!() };
+        assert_eq!((name.as_ref().unwrap().value.as_str(), columns.len()), ("pk", 2));
+        assert!(matches!(&constraints[1], TableConstraint::Unique { name: None, .. }));
+        let TableConstraint::ForeignKey { references, .. } = &constraints[2] else { panic!() };
+        assert!(references.columns.is_empty());
+        assert_eq!(references.on_delete, Some(ReferentialAction::NoAction));
+    }
+
+    #[test]
+    fn table_elements_cannot_be_left_out() {
+        for sql in ["CREATE TABLE t (a INT,, b INT)", "CREATE TABLE t (, a INT)", "CREATE TABLE t ()", "CREATE TABLE t (a INT,)"] {
+            let error = parse_sql(sql).unwrap_err();
+            assert_eq!(error.code(), "E0002", "{}", sql);
+            assert!(error.to_string().contains("a column definition"), "{}: {}", sql, error);
+        }
+    }
+
+    #[test]
+    fn generated_columns() {
+        let sql = "CREATE TABLE t (id INT, g INT GENERATED ALWAYS AS (id * 2) VIRTUAL, s INT AS (id) STORED, d INT AS (id))";
+        let Statement::CreateTable { columns, .. } = parse_sql(sql).unwrap() else { panic!() };
+        assert!(matches!(&columns[1].options[..], [ColumnOption::Generated { storage: Some(GeneratedStorage::Virtual), .. }]));
+        assert!(matches!(&columns[2].options[..], [ColumnOption::Generated { storage: Some(GeneratedStorage::Stored), .. }]));
+        assert!(matches!(&columns[3].options[..], [ColumnOption::Generated { storage: None, .. }]));
+    }
+
+    #[test]
+    fn identity_columns() {
+        let sql = "CREATE TABLE t (i BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 10 INCREMENT BY 5), j INT GENERATED ALWAYS AS IDENTITY)";
+        let Statement::CreateTable { columns, .. } = parse_sql(sql).unwrap() else { panic!() };
+        assert_eq!(columns[0].options, [ColumnOption::Identity { always: false, start: Some(10), increment: Some(5) }]);
+        assert_eq!(columns[1].options, [ColumnOption::Identity { always: true, start: None, increment: None }]);
+    }
+
+    #[test]
+    fn table_options_follow_the_column_list() {
+        let Statement::CreateTable { options, .. } = parse_sql("CREATE TABLE t (a INT) ENGINE=InnoDB DEFAULT CHARSET = utf8mb4").unwrap() else { panic!() };
+        let options: Vec<(&str, &str)> = options.iter().map(|option| (option.name.as_str(), option.value.as_str())).collect();
+        assert_eq!(options, [("ENGINE", "InnoDB"), ("DEFAULT CHARSET", "utf8mb4")]);
+
+        // A word that is not followed by '=' ends the options instead of starting one
+        assert!(parse_sql("CREATE TABLE t (a INT) ENGINE=InnoDB oops").is_err());
     }
 }
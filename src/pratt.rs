@@ -1,139 +1,1785 @@
-use crate::tokenizer::{Token, Keyword};
-use crate::ast::{Expression, BinaryOperator, UnaryOperator};
+use crate::tokenizer::{Token, Keyword, Span};
+use crate::{ParseError, ParseWarning};
+use crate::functions::check_arity;
+use crate::ast::{Expression, BinaryOperator, UnaryOperator, TrimSide, IntervalUnit, DataType, Subscript, RegexSyntax, FunctionArg, OrderByExpr, IsTest, CaseWhen, Literal, Ident, NodeSpan};
+
+mod data_type;  // Type grammar shared by casts and column definitions
 
 pub struct PrattParser<'a> 
 {
     tokens: &'a [Token],
+    // Where each token was read from, for error positions; may be empty
+    spans: &'a [Span],
+    // The text the tokens were read from, which starts at offset `source_start` of the spans;
+    // empty if unknown. Keywords used as names are spelled from it.
+    source: &'a str,
+    source_start: usize,
+    position: usize,
+    // The second half of a >> token already consumed while closing a nested ARRAY<...> or STRUCT<...>
+    pending_greater_than: bool,
+    // What was looked for at `expected_at` and not found, for the caller's error messages
+    expected: Vec<String>,
+    expected_at: usize,
+    // How many expressions and types are being parsed inside one another, and the most allowed
+    depth: usize,
+    max_depth: usize,
+    // How many chained operators are being read, see chain_operator
+    chain_depth: usize,
+    // Accepted but questionable syntax, in source order, for the caller to collect
+    warnings: Vec<ParseWarning>,
+}
+
+// Where to return to when a guess at the grammar does not work out.
+struct Checkpoint 
+{
     position: usize,
+    pending_greater_than: bool,
+    warnings: usize,
 }
+
 impl<'a> PrattParser<'a> 
 {
     pub fn new(tokens: &'a [Token]) -> Self 
     {
-        Self { tokens, position: 0 }
+        Self { tokens, spans: &[], source: "", source_start: 0, position: 0, pending_greater_than: false, expected: Vec::new(), expected_at: 0, depth: 0, max_depth: usize::MAX, chain_depth: 0, warnings: Vec::new() }
+    }
+
+    /// Lets errors point into the source; `spans[i]` is where `tokens[i]` was read from.
+    pub fn with_spans(mut self, spans: &'a [Span]) -> Self 
+    {
+        self.spans = spans;
+        self
+    }
+
+    /// Lets keywords used as names keep the case they were written in; the spans must count
+    /// from the start of `source`.
+    pub fn with_source(self, source: &'a str) -> Self 
+    {
+        self.with_source_at(source, 0)
+    }
+
+    // Like with_source, for `source` that starts at offset `start` in a larger text.
+    pub(crate) fn with_source_at(mut self, source: &'a str, start: usize) -> Self 
+    {
+        self.source = source;
+        self.source_start = start;
+        self
+    }
+
+    /// Fails with TooDeep instead of nesting expressions or types more than `max_depth` deep,
+    /// so adversarial input cannot overflow the stack. Parentheses, function calls, CASE and
+    /// types count as nesting; chains of operators such as a ^ b ^ c or NOT NOT a do not, and
+    /// may be up to four times `max_depth` operators long.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self 
+    {
+        self.max_depth = max_depth;
+        self
+    }
+
+    // Runs `parse` one nesting level deeper.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, ParseError>) -> Result<T, ParseError> 
+    {
+        if self.depth >= self.max_depth 
+        {
+            return Err(ParseError::TooDeep(self.max_depth, self.current_span()));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn checkpoint(&self) -> Checkpoint 
+    {
+        Checkpoint { position: self.position, pending_greater_than: self.pending_greater_than, warnings: self.warnings.len() }
+    }
+
+    fn rollback(&mut self, checkpoint: Checkpoint) 
+    {
+        self.position = checkpoint.position;
+        self.pending_greater_than = checkpoint.pending_greater_than;
+        self.warnings.truncate(checkpoint.warnings);
+    }
+
+    /// The warnings for what was accepted so far, such as keywords used as names.
+    pub fn take_warnings(&mut self) -> Vec<ParseWarning> 
+    {
+        std::mem::take(&mut self.warnings)
     }
 
-    fn peek(&self) -> Option<&Token>   
+    /// Number of tokens consumed so far, so the caller can continue after the expression.
+    pub fn position(&self) -> usize 
+    {
+        self.position
+    }
+
+    /// What would also have been valid at the current position, e.g. "an operator" after a
+    /// complete expression, so the caller can list it if it fails there.
+    pub fn expected(&self) -> &[String] 
+    {
+        if self.expected_at == self.position { &self.expected } else { &[] }
+    }
+
+    fn note_expected(&mut self, what: &str) 
+    {
+        if self.expected_at != self.position 
+        {
+            self.expected.clear();
+            self.expected_at = self.position;
+        }
+        if !self.expected.iter().any(|noted| noted == what) 
+        {
+            self.expected.push(what.to_string());
+        }
+    }
+
+    fn peek(&self) -> Option<&'a Token>   
     {
         self.tokens.get(self.position)
     }
 
-    fn advance(&mut self) -> Option<&Token> 
+    fn advance(&mut self) -> Option<&'a Token> 
     {
         let token = self.tokens.get(self.position);
         self.position += 1;
         token
     }
 
-    fn expect(&mut self, expected: &Token) -> Result<(), String> 
+    // True if the tokens starting `offset` ahead are identifiers spelling `words` (case-insensitive).
+    fn peek_words(&self, offset: usize, words: &[&str]) -> bool 
     {
-        if let Some(tok) = self.peek() 
+        words.iter().enumerate().all(|(i, word)| 
         {
-            if tok == expected 
-            {
-                self.advance();
-                Ok(())
-            } 
-            else 
-            {
-                Err(format!("Expected {:?}, found {:?}", expected, tok))
-            }
+            matches!(self.tokens.get(self.position + offset + i), Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(word))
+        })
+    }
+
+    // Recognizes every spelling of a regex match operator.
+    // Returns (tokens to consume, syntax, negated, case_insensitive).
+    fn peek_regex_operator(&self) -> Option<(usize, RegexSyntax, bool, bool)> 
+    {
+        match self.peek() 
+        {
+            Some(Token::Tilde) => return Some((1, RegexSyntax::Posix, false, false)),
+            Some(Token::TildeStar) => return Some((1, RegexSyntax::Posix, false, true)),
+            Some(Token::NotTilde) => return Some((1, RegexSyntax::Posix, true, false)),
+            Some(Token::NotTildeStar) => return Some((1, RegexSyntax::Posix, true, true)),
+            _ => {}
+        }
+
+        let negated = self.peek() == Some(&Token::Keyword(Keyword::Not));
+        let skip = usize::from(negated);
+        if self.peek_words(skip, &["SIMILAR", "TO"]) 
+        {
+            Some((skip + 2, RegexSyntax::SimilarTo, negated, false))
+        } 
+        else if self.peek_words(skip, &["REGEXP"]) || self.peek_words(skip, &["RLIKE"]) 
+        {
+            Some((skip + 1, RegexSyntax::Posix, negated, false))
+        } 
+        else 
+        {
+            None
+        }
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), ParseError> 
+    {
+        if self.peek() == Some(expected) 
+        {
+            self.advance();
+            Ok(())
         } 
         else 
         {
-            Err("Unexpected end of tokens".to_string())
+            self.note_expected(&expected.to_string());
+            Err(self.expected_error())
+        }
+    }
+
+    // Past the end of the tokens this is the last span, which is where the input ran out.
+    fn span_at(&self, index: usize) -> Span 
+    {
+        self.spans.get(index).or(self.spans.last()).copied().unwrap_or_default()
+    }
+
+    fn current_span(&self) -> Span 
+    {
+        self.span_at(self.position)
+    }
+
+    fn previous_span(&self) -> Span 
+    {
+        self.span_at(self.position.saturating_sub(1))
+    }
+
+    // A keyword used as a name at token `index`, spelled as in the source; in lower case if the
+    // source is unknown.
+    fn keyword_name(&self, index: usize, keyword: &Keyword) -> Ident 
+    {
+        let span = self.span_at(index);
+        let written = self.source.get(span.start.offset.wrapping_sub(self.source_start)..span.end.offset.wrapping_sub(self.source_start));
+        let name = match written 
+        {
+            Some(written) if written.eq_ignore_ascii_case(keyword.as_str()) => written.to_string(),
+            _ => keyword.as_str().to_lowercase(),
+        };
+        Ident::new(name).with_span(span)
+    }
+
+    // From the start of `first` to the end of the last token consumed.
+    fn span_since(&self, first: Span) -> NodeSpan 
+    {
+        NodeSpan(Span { start: first.start, end: self.previous_span().end })
+    }
+
+    // From the token at index `start` to the last token consumed.
+    fn span_from(&self, start: usize) -> NodeSpan 
+    {
+        self.span_since(self.span_at(start))
+    }
+
+    // Everything noted as valid at the current token, and the token found instead.
+    fn expected_error(&self) -> ParseError 
+    {
+        ParseError::Expected(self.expected().to_vec(), self.peek().cloned(), self.current_span())
+    }
+
+    // For a token that was already advanced past: steps back onto it and reports what was
+    // wanted there instead.
+    fn expected_before(&mut self, wanted: &[&str]) -> ParseError 
+    {
+        self.position = self.position.saturating_sub(1);
+        for what in wanted 
+        {
+            self.note_expected(what);
         }
+        self.expected_error()
     }
 }
+// Binding powers, loosest first: OR < AND < NOT < IS < comparisons < IN/LIKE/BETWEEN < || -> ->> << >> < + - < * / < ^ < unary minus < ...
+const OR_PRECEDENCE: u8 = 1;
+const AND_PRECEDENCE: u8 = 2;
+pub(crate) const NOT_PRECEDENCE: u8 = 3;
+pub(crate) const IS_PRECEDENCE: u8 = 4;
+const COMPARISON_PRECEDENCE: u8 = 5;
+pub(crate) const PATTERN_PRECEDENCE: u8 = 6;
+// ||, <<, >> and the JSON operators -> ->> #> #>>: like Postgres, looser than arithmetic but
+// tighter than LIKE / IN / BETWEEN, so a ->> 'k' || 'x' reads left to right and a -> 'k' + 1 is a -> ('k' + 1)
+const OTHER_OPERATOR_PRECEDENCE: u8 = 7;
+const ADDITIVE_PRECEDENCE: u8 = 8;
+const MULTIPLICATIVE_PRECEDENCE: u8 = 9;
+const EXPONENT_PRECEDENCE: u8 = 10;
+const FACTORIAL_PRECEDENCE: u8 = 11;
+pub(crate) const NEGATE_PRECEDENCE: u8 = 12;
+const AT_TIME_ZONE_PRECEDENCE: u8 = 13;
+const COLLATE_PRECEDENCE: u8 = 14;
+const SUBSCRIPT_PRECEDENCE: u8 = 15;
+const CAST_PRECEDENCE: u8 = 16;
+// How many times max_depth a chain of operators may run to, see PrattParser::with_max_depth
+pub(crate) const OPERATOR_CHAIN_FACTOR: usize = 4;
+
+// Precedence of a token in operator position; 0 means it does not continue an expression.
 fn get_precedence(token: &Token) -> u8 
 {
     match token 
     {
-        Token::Keyword(Keyword::Or) => 1,
-        Token::Keyword(Keyword::And) => 2,
-        Token::Equal | Token::NotEqual => 3,
+        Token::Keyword(Keyword::Or) => OR_PRECEDENCE,
+        Token::Keyword(Keyword::And) => AND_PRECEDENCE,
+        Token::Equal | Token::NotEqual |
         Token::GreaterThan | Token::GreaterThanOrEqual |
-        Token::LessThan | Token::LessThanOrEqual => 4,
-        Token::Plus | Token::Minus => 5,
-        Token::Multiply | Token::Divide => 6,
+        Token::LessThan | Token::LessThanOrEqual => COMPARISON_PRECEDENCE,
+        Token::Keyword(Keyword::In) | Token::Keyword(Keyword::Like) |
+        Token::Keyword(Keyword::ILike) | Token::Keyword(Keyword::Between) => PATTERN_PRECEDENCE,
+        Token::Concat | Token::ShiftLeft | Token::ShiftRight |
+        Token::Arrow | Token::LongArrow | Token::HashArrow | Token::HashLongArrow => OTHER_OPERATOR_PRECEDENCE,
+        Token::Plus | Token::Minus => ADDITIVE_PRECEDENCE,
+        Token::Multiply | Token::Divide => MULTIPLICATIVE_PRECEDENCE,
+        Token::Caret => EXPONENT_PRECEDENCE,
+        _ => 0,
+    }
+}
+// Precedence of a binary operator, the same as get_precedence gives the token spelling it.
+pub(crate) fn binary_operator_precedence(operator: &BinaryOperator) -> u8 
+{
+    match operator 
+    {
+        BinaryOperator::Or => OR_PRECEDENCE,
+        BinaryOperator::And => AND_PRECEDENCE,
+        BinaryOperator::Equals | BinaryOperator::NotEquals |
+        BinaryOperator::GreaterThan | BinaryOperator::GreaterThanOrEqual |
+        BinaryOperator::LessThan | BinaryOperator::LessThanOrEqual => COMPARISON_PRECEDENCE,
+        BinaryOperator::StringConcat | BinaryOperator::BitwiseShiftLeft | BinaryOperator::BitwiseShiftRight |
+        BinaryOperator::JsonGet | BinaryOperator::JsonGetText |
+        BinaryOperator::JsonGetPath | BinaryOperator::JsonGetPathText => OTHER_OPERATOR_PRECEDENCE,
+        BinaryOperator::Add | BinaryOperator::Subtract => ADDITIVE_PRECEDENCE,
+        BinaryOperator::Multiply | BinaryOperator::Divide => MULTIPLICATIVE_PRECEDENCE,
+        BinaryOperator::Exponent => EXPONENT_PRECEDENCE,
+    }
+}
+// Precedence of the operator `expr` is built from, i.e. the loosest operator an expression
+// can be printed next to without parentheses. Expressions that are closed on both sides,
+// such as names, literals and function calls, get u8::MAX.
+pub(crate) fn expression_precedence(expr: &Expression) -> u8 
+{
+    match expr 
+    {
+        Expression::BinaryOperation { operator, .. } => binary_operator_precedence(operator),
+        Expression::UnaryOperation { operator: UnaryOperator::Not, .. } => NOT_PRECEDENCE,
+        Expression::UnaryOperation { operator: UnaryOperator::Factorial, .. } => FACTORIAL_PRECEDENCE,
+        Expression::UnaryOperation { operator: UnaryOperator::Negate, .. } => NEGATE_PRECEDENCE,
+        Expression::Is { .. } => IS_PRECEDENCE,
+        Expression::InList { .. } | Expression::Like { .. } |
+        Expression::Between { .. } | Expression::RegexMatch { .. } => PATTERN_PRECEDENCE,
+        Expression::AtTimeZone { .. } => AT_TIME_ZONE_PRECEDENCE,
+        Expression::Collate { .. } => COLLATE_PRECEDENCE,
+        Expression::Subscript { .. } => SUBSCRIPT_PRECEDENCE,
+        _ => u8::MAX,
+    }
+}
+// Integers that fit i128 become Integer and longer ones are kept exactly as Decimal;
+// numbers with a fraction or exponent become Float.
+pub(crate) fn parse_number(text: &str) -> Result<Literal, String> 
+{
+    if let Ok(n) = text.parse::<i128>() 
+    {
+        return Ok(Literal::Integer(n));
+    }
+    if text.trim_start_matches('-').chars().all(|c| c.is_ascii_digit()) 
+    {
+        return Ok(Literal::Decimal(text.to_string()));
+    }
+    match text.parse::<f64>() 
+    {
+        // Too large for a float, such as 1e400; kept as written so it prints back unchanged
+        Ok(n) if !n.is_finite() => Ok(Literal::Decimal(text.to_string())),
+        Ok(n) => Ok(Literal::Float(n)),
+        Err(_) => Err(format!("Invalid number: {}", text)),
+    }
+}
+fn get_typed_literal_type(name: &str) -> Option<DataType> 
+{
+    match name.to_uppercase().as_str() 
+    {
+        "DATE" => Some(DataType::Date),
+        "TIME" => Some(DataType::Time { precision: None, with_time_zone: false }),
+        "TIMESTAMP" => Some(DataType::Timestamp { precision: None, with_time_zone: false }),
+        "TIMESTAMPTZ" => Some(DataType::Timestamp { precision: None, with_time_zone: true }),
+        _ => None,
+    }
+}
+fn get_interval_unit(token: Option<&Token>) -> Option<IntervalUnit> 
+{
+    let word = match token 
+    {
+        Some(Token::Identifier(word)) => word.to_uppercase(),
+        _ => return None,
+    };
+    match word.as_str() 
+    {
+        "YEAR" | "YEARS" => Some(IntervalUnit::Year),
+        "MONTH" | "MONTHS" => Some(IntervalUnit::Month),
+        "DAY" | "DAYS" => Some(IntervalUnit::Day),
+        "HOUR" | "HOURS" => Some(IntervalUnit::Hour),
+        "MINUTE" | "MINUTES" => Some(IntervalUnit::Minute),
+        "SECOND" | "SECONDS" => Some(IntervalUnit::Second),
+        _ => None,
+    }
+}
+// Precedence of a postfix operator, which takes only the operand to its left; 0 if `token` is not one.
+fn get_postfix_precedence(token: &Token) -> u8 
+{
+    match token 
+    {
+        Token::Keyword(Keyword::Is) => IS_PRECEDENCE,
+        Token::Exclamation => FACTORIAL_PRECEDENCE,
+        Token::Keyword(Keyword::Collate) => COLLATE_PRECEDENCE,
+        Token::LeftBracket => SUBSCRIPT_PRECEDENCE,
+        Token::DoubleColon => CAST_PRECEDENCE,
         _ => 0,
     }
 }
+
+#[derive(Debug, PartialEq)]
+enum Associativity 
+{
+    Left,
+    Right,
+}
+
+// How a chain of equal-precedence operators groups: a - b - c is (a - b) - c, 2 ^ 3 ^ 2 is 2 ^ (3 ^ 2).
+fn get_associativity(token: &Token) -> Associativity 
+{
+    match token 
+    {
+        Token::Caret => Associativity::Right,
+        _ => Associativity::Left,
+    }
+}
+
 impl<'a> PrattParser<'a> 
 {
-    pub fn parse_expression(&mut self, min_precedence: u8) -> Result<Expression, String> {
-        let mut left = match self.advance() 
+    /// Parses an expression made of operators that bind at least as tightly as `min_precedence`.
+    pub fn parse_expression(&mut self, min_precedence: u8) -> Result<Expression, ParseError> 
+    {
+        self.nested(|parser| parser.parse_nested_expression(min_precedence))
+    }
+
+    /// Parses an expression that must make up all of the tokens, as for an expression given on
+    /// its own rather than inside a statement.
+    pub fn parse_single_expression(&mut self) -> Result<Expression, ParseError> 
+    {
+        let expression = self.parse_expression(1)?;
+        if matches!(self.peek(), None | Some(Token::Eof)) 
+        {
+            return Ok(expression);
+        }
+        self.note_expected(&Token::Eof.to_string());
+        Err(self.expected_error())
+    }
+
+    fn parse_nested_expression(&mut self, min_precedence: u8) -> Result<Expression, ParseError> {
+        let chain_depth = self.chain_depth;
+        let result = self.parse_prefixed_expression(min_precedence);
+        self.chain_depth = chain_depth;
+        result
+    }
+
+    // NOT and unary minus are read in a loop rather than by recursion, so NOT NOT ... a costs no
+    // stack however long it is. Each applies to its operand once the operators binding at least
+    // as tightly as it does have been read.
+    fn parse_prefixed_expression(&mut self, min_precedence: u8) -> Result<Expression, ParseError> 
+    {
+        let mut prefixes = Vec::new();
+        while let Some((operator, precedence)) = self.peek_prefix_operator() 
+        {
+            self.chain_operator()?;
+            prefixes.push((operator, precedence, self.position));
+            self.advance();
+        }
+        let mut left = self.parse_primary()?;
+        loop 
+        {
+            let operand_precedence = prefixes.last().map_or(min_precedence, |(_, precedence, _)| *precedence);
+            left = self.parse_operators(left, operand_precedence)?;
+            let Some((operator, _, start)) = prefixes.pop() else { return Ok(left) };
+            left = Expression::UnaryOperation 
+            {
+                operator,
+                operand: Box::new(left),
+                span: self.span_from(start),
+            };
+        }
+    }
+
+    // The prefix operator at the current token and how tightly it binds its operand.
+    fn peek_prefix_operator(&self) -> Option<(UnaryOperator, u8)> 
+    {
+        match self.peek()? 
+        {
+            // NOT binds looser than comparisons: NOT a = b is NOT (a = b)
+            Token::Keyword(Keyword::Not) => Some((UnaryOperator::Not, NOT_PRECEDENCE)),
+            // A minus directly before a number is part of the literal, see parse_primary
+            Token::Minus if !matches!(self.tokens.get(self.position + 1), Some(Token::Number(_))) => Some((UnaryOperator::Negate, NEGATE_PRECEDENCE)),
+            _ => None,
+        }
+    }
+
+    // Counts one more operator in a chain such as NOT NOT a or a ^ b ^ c. Chains are read in a
+    // loop, but each operator still makes the tree one level deeper for whatever walks it later.
+    fn chain_operator(&mut self) -> Result<(), ParseError> 
+    {
+        if self.chain_depth >= self.max_depth.saturating_mul(OPERATOR_CHAIN_FACTOR) 
+        {
+            return Err(ParseError::TooDeep(self.max_depth, self.current_span()));
+        }
+        self.chain_depth += 1;
+        Ok(())
+    }
+
+    // A name, literal, parenthesized expression or other operand that does not start with an operator.
+    fn parse_primary(&mut self) -> Result<Expression, ParseError> 
+    {
+        let start = self.position;
+        let primary = match self.advance() 
         {
-            Some(Token::Identifier(name)) => Expression::Identifier(name.clone()),
-            Some(Token::Number(n)) => Expression::Number(*n),
-            Some(Token::String(s)) => Expression::String(s.clone()),
-            Some(Token::Keyword(Keyword::True)) => Expression::Boolean(true),
-            Some(Token::Keyword(Keyword::False)) => Expression::Boolean(false),
-            Some(Token::Keyword(Keyword::Not)) => 
+            Some(Token::Identifier(name)) => 
             {
-                let expr = self.parse_expression(6)?; // high precedence for unary NOT
-                Expression::UnaryOperation 
+                let name = name.clone();
+                if self.peek() == Some(&Token::LeftParentheses) 
+                {
+                    self.advance();
+                    self.parse_function_call(Ident::new(name).with_span(self.span_at(start)), start)?
+                } 
+                else if let (Some(data_type), Some(Token::String(value))) = (get_typed_literal_type(&name), self.peek()) 
                 {
-                    operator: UnaryOperator::Not,
-                    operand: Box::new(expr),
+                    // DATE '2024-01-01', TIME '10:00', TIMESTAMP '2024-01-01 10:00:00'
+                    let value = value.clone();
+                    self.advance();
+                    Expression::TypedString { data_type, value, span: self.span_from(start) }
+                } 
+                else if self.peek() == Some(&Token::Period) 
+                {
+                    self.parse_compound_identifier(Ident::new(name).with_span(self.span_at(start)))?
+                } 
+                else 
+                {
+                    Expression::Identifier(Ident::new(name).with_span(self.span_at(start)))
                 }
             }
-            Some(Token::Minus) => 
+            Some(Token::QuotedIdentifier { value, quote }) => 
             {
-                let expr = self.parse_expression(6)?;
-                Expression::UnaryOperation 
+                // Quoted names never start typed literals or special forms
+                let name = Ident::quoted(value.clone(), *quote).with_span(self.span_at(start));
+                if self.peek() == Some(&Token::LeftParentheses) 
+                {
+                    self.advance();
+                    self.parse_function_call(name, start)?
+                } 
+                else if self.peek() == Some(&Token::Period) 
                 {
-                    operator: UnaryOperator::Negate,
-                    operand: Box::new(expr),
+                    self.parse_compound_identifier(name)?
+                } 
+                else 
+                {
+                    Expression::Identifier(name)
                 }
             }
+            Some(Token::Number(n)) => Expression::Literal(parse_number(n).map_err(|e| ParseError::InvalidExpression(e, self.previous_span()))?, self.span_from(start)),
+            Some(Token::Placeholder(text)) => Expression::Placeholder(text.clone(), self.span_from(start)),
+            Some(Token::String(s)) => Expression::Literal(Literal::String(s.clone()), self.span_from(start)),
+            Some(Token::EscapedString { value, .. }) => Expression::Literal(Literal::String(value.clone()), self.span_from(start)),
+            Some(Token::HexString(digits)) => Expression::Literal(Literal::HexString(digits.clone()), self.span_from(start)),
+            Some(Token::BitString(bits)) => Expression::Literal(Literal::BitString(bits.clone()), self.span_from(start)),
+            Some(Token::NationalString(value)) => Expression::Literal(Literal::NationalString(value.clone()), self.span_from(start)),
+            Some(Token::RawString(value)) => Expression::Literal(Literal::String(value.clone()), self.span_from(start)),
+            Some(Token::Keyword(Keyword::True)) => Expression::Literal(Literal::Boolean(true), self.span_from(start)),
+            Some(Token::Keyword(Keyword::False)) => Expression::Literal(Literal::Boolean(false), self.span_from(start)),
+            Some(Token::Keyword(Keyword::Null)) => Expression::Literal(Literal::Null, self.span_from(start)),
+            Some(Token::Minus) => 
+            {
+                // Only before a number (see peek_prefix_operator): the minus is part of the literal,
+                // so -5 is Integer(-5), not Negate(5) as -a is, and -2 ^ 2 is (-2) ^ 2 (see Literal)
+                let Some(Token::Number(n)) = self.peek() else { return Err(self.expected_before(&["an expression"])) };
+                let literal = parse_number(&format!("-{}", n)).map_err(|e| ParseError::InvalidExpression(e, self.previous_span()))?;
+                self.advance();
+                Expression::Literal(literal, self.span_from(start))
+            }
+            Some(Token::Keyword(Keyword::Interval)) => self.parse_interval(start)?,
+            Some(Token::Keyword(Keyword::Case)) => self.parse_case(start)?,
+            Some(Token::Keyword(Keyword::Array)) => 
+            {
+                self.expect(&Token::LeftBracket)?;
+                self.parse_array(start)?
+            }
             Some(Token::LeftParentheses) => 
             {
                 let expr = self.parse_expression(1)?;
-                self.expect(&Token::RightParentheses)?;
-                Expression::Grouped(Box::new(expr))
+                if self.peek() == Some(&Token::Comma) 
+                {
+                    let mut elements = vec![expr];
+                    while self.peek() == Some(&Token::Comma) 
+                    {
+                        self.advance();
+                        elements.push(self.parse_expression(1)?);
+                    }
+                    self.expect(&Token::RightParentheses)?;
+                    Expression::Tuple(elements, self.span_from(start))
+                } 
+                else 
+                {
+                    self.expect(&Token::RightParentheses)?;
+                    Expression::Grouped(Box::new(expr), self.span_from(start))
+                }
+            }
+            Some(Token::Keyword(keyword)) if !keyword.is_reserved() => 
+            {
+                // Non-reserved keywords such as KEY can name columns
+                self.warnings.push(ParseWarning::KeywordAsIdentifier(keyword.clone(), self.span_at(start)));
+                let name = self.keyword_name(start, keyword);
+                if self.peek() == Some(&Token::Period) 
+                {
+                    self.parse_compound_identifier(name)?
+                } 
+                else 
+                {
+                    Expression::Identifier(name)
+                }
             }
-            Some(t) => return Err(format!("Unexpected token: {:?}", t)),
-            None => return Err("Unexpected end of input".to_string()),
+            _ => return Err(self.expected_before(&["an expression"])),
         };
+        Ok(primary)
+    }
 
-        loop {
-            let op = match self.peek() 
+    // Applies the postfix and infix operators that follow `left` and bind at least as tightly as
+    // `min_precedence`.
+    fn parse_operators(&mut self, mut left: Expression, min_precedence: u8) -> Result<Expression, ParseError> 
+    {
+        loop 
+        {
+            let postfix_precedence = self.peek().map_or(0, get_postfix_precedence);
+            if postfix_precedence > 0 
             {
-                Some(tok) if get_precedence(tok) >= min_precedence => tok.clone(),
-                _ => break,
-            };
+                if postfix_precedence < min_precedence 
+                {
+                    break;
+                }
+                left = self.parse_postfix(left)?;
+                continue;
+            }
+
+            let precedence = self.peek_precedence();
+            if precedence == 0 
+            {
+                self.note_expected("an operator");
+                break;
+            }
+            if precedence < min_precedence 
+            {
+                break;
+            }
+            left = self.parse_infix(left, precedence)?;
+        }
+
+        Ok(left)
+    }
+}
+
+impl<'a> PrattParser<'a> 
+{
+    // Called after the opening parenthesis of `name(` has been consumed.
+    // `start` is the index of the name token.
+    fn parse_function_call(&mut self, name: Ident, start: usize) -> Result<Expression, ParseError> 
+    {
+        let special = |form: &str| name.quote_style.is_none() && name.value.eq_ignore_ascii_case(form);
+        if special("POSITION") 
+        {
+            return self.parse_position(start);
+        }
+        if special("TRIM") 
+        {
+            return self.parse_trim(start);
+        }
+        if special("CAST") 
+        {
+            return self.parse_cast(start);
+        }
 
-            let precedence = get_precedence(&op);
+        let mut args = Vec::new();
+        let mut order_by = Vec::new();
+        if self.peek() == Some(&Token::RightParentheses) 
+        {
             self.advance();
+        } 
+        else 
+        {
+            self.parse_function_args(&mut args, &mut order_by)?;
+        }
 
-            let right = self.parse_expression(precedence + 1)?;
-
-            let operator = match op 
-            {
-                Token::Equal => BinaryOperator::Equals,
-                Token::NotEqual => BinaryOperator::NotEquals,
-                Token::GreaterThan => BinaryOperator::GreaterThan,
-                Token::GreaterThanOrEqual => BinaryOperator::GreaterThanOrEqual,
-                Token::LessThan => BinaryOperator::LessThan,
-                Token::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
-                Token::Plus => BinaryOperator::Add,
-                Token::Minus => BinaryOperator::Subtract,
-                Token::Multiply => BinaryOperator::Multiply,
-                Token::Divide => BinaryOperator::Divide,
-                Token::Keyword(Keyword::And) => BinaryOperator::And,
-                Token::Keyword(Keyword::Or) => BinaryOperator::Or,
-                _ => return Err(format!("Unknown operator {:?}", op)),
-            };
+        check_arity(&name.value, args.len()).map_err(|e| ParseError::InvalidExpression(e, self.previous_span()))?;
+        let filter = self.parse_filter()?;
+        Ok(Expression::Function { name, args, order_by, filter, span: self.span_from(start) })
+    }
 
-            left = Expression::BinaryOperation 
+    // Reads the arguments (and a trailing ORDER BY list) up to and including the closing parenthesis.
+    fn parse_function_args(&mut self, args: &mut Vec<FunctionArg>, order_by: &mut Vec<OrderByExpr>) -> Result<(), ParseError> 
+    {
+        loop 
+        {
+            args.push(self.parse_function_arg()?);
+            if self.peek() == Some(&Token::Keyword(Keyword::Order)) 
             {
-                left_operand: Box::new(left),
-                operator,
-                right_operand: Box::new(right),
-            };
+                self.advance();
+                self.expect(&Token::Keyword(Keyword::By))?;
+                loop 
+                {
+                    order_by.push(self.parse_order_by_expr()?);
+                    if self.peek() != Some(&Token::Comma) 
+                    {
+                        break;
+                    }
+                    self.advance();
+                }
+                self.expect(&Token::RightParentheses)?;
+                break;
+            }
+            match self.advance() 
+            {
+                Some(Token::Comma) => continue,
+                Some(Token::RightParentheses) => break,
+                _ => return Err(self.expected_before(&["','", "')'"])),
+            }
+        }
+        Ok(())
+    }
+
+    // Optional FILTER (WHERE condition) after an aggregate call.
+    fn parse_filter(&mut self) -> Result<Option<Box<Expression>>, ParseError> 
+    {
+        if !self.peek_words(0, &["FILTER"]) || self.tokens.get(self.position + 1) != Some(&Token::LeftParentheses) 
+        {
+            return Ok(None);
         }
+        self.position += 2;
+        self.expect(&Token::Keyword(Keyword::Where))?;
+        let condition = self.parse_expression(1)?;
+        self.expect(&Token::RightParentheses)?;
+        Ok(Some(Box::new(condition)))
+    }
 
-        Ok(left)
+    /// Parses `expr [ASC | DESC]`
+    pub fn parse_order_by_expr(&mut self) -> Result<OrderByExpr, ParseError> 
+    {
+        let start = self.position;
+        let expr = self.parse_expression(1)?;
+        let asc = match self.peek() 
+        {
+            Some(Token::Keyword(Keyword::Asc)) => Some(true),
+            Some(Token::Keyword(Keyword::Desc)) => Some(false),
+            _ => None,
+        };
+        if asc.is_some() 
+        {
+            self.advance();
+        } 
+        else 
+        {
+            self.note_expected("ASC");
+            self.note_expected("DESC");
+        }
+        Ok(OrderByExpr { expr, asc, span: self.span_from(start) })
+    }
+
+    // Precedence of the operator at the current position, including multi-token operators
+    // such as NOT IN, NOT BETWEEN and AT TIME ZONE.
+    fn peek_precedence(&self) -> u8 
+    {
+        if self.peek_regex_operator().is_some() 
+        {
+            return PATTERN_PRECEDENCE;
+        }
+        // AT TIME ZONE is matched contextually so AT, TIME and ZONE stay usable as names.
+        if self.peek_words(0, &["AT", "TIME", "ZONE"]) 
+        {
+            return AT_TIME_ZONE_PRECEDENCE;
+        }
+        match self.peek() 
+        {
+            Some(Token::Keyword(Keyword::Not)) => match self.tokens.get(self.position + 1) 
+            {
+                Some(Token::Keyword(Keyword::In)) | Some(Token::Keyword(Keyword::Like)) |
+                Some(Token::Keyword(Keyword::ILike)) | Some(Token::Keyword(Keyword::Between)) => PATTERN_PRECEDENCE,
+                _ => 0,
+            },
+            Some(tok) => get_precedence(tok),
+            None => 0,
+        }
+    }
+
+    // Parses the operands of a mixfix construct: one operand, then for each keyword in
+    // `separators` that keyword followed by another operand. BETWEEN's `low AND high` is
+    // parse_mixfix_operands(&[AND], ..) and a CASE arm's `condition THEN result` is
+    // parse_mixfix_operands(&[THEN], ..). Operands are parsed at `min_precedence`.
+    fn parse_mixfix_operands(&mut self, separators: &[Token], min_precedence: u8) -> Result<Vec<Expression>, ParseError> 
+    {
+        let mut operands = vec![self.parse_expression(min_precedence)?];
+        for separator in separators 
+        {
+            self.expect(separator)?;
+            operands.push(self.parse_expression(min_precedence)?);
+        }
+        Ok(operands)
+    }
+
+    // Called with the first part of a dotted name when the next token is a period:
+    // a.b.c becomes CompoundIdentifier, a.* becomes QualifiedWildcard.
+    fn parse_compound_identifier(&mut self, first: Ident) -> Result<Expression, ParseError> 
+    {
+        let start = first.span.0;
+        let mut parts = vec![first];
+        while self.peek() == Some(&Token::Period) 
+        {
+            self.advance();
+            match self.advance() 
+            {
+                Some(Token::Identifier(name)) => parts.push(Ident::new(name.clone()).with_span(self.previous_span())),
+                Some(Token::QuotedIdentifier { value, quote }) => parts.push(Ident::quoted(value.clone(), *quote).with_span(self.previous_span())),
+                // After a period even reserved words are names, as in t.order
+                Some(Token::Keyword(keyword)) => parts.push(self.keyword_name(self.position - 1, keyword)),
+                Some(Token::Multiply) => return Ok(Expression::QualifiedWildcard(parts, self.span_since(start))),
+                _ => return Err(self.expected_before(&["a name", "'*'"])),
+            }
+        }
+        Ok(Expression::CompoundIdentifier(parts, self.span_since(start)))
+    }
+
+    // Called after CASE, at index `start`, has been consumed.
+    fn parse_case(&mut self, start: usize) -> Result<Expression, ParseError> 
+    {
+        let operand = if self.peek() == Some(&Token::Keyword(Keyword::When)) 
+        {
+            None
+        } 
+        else 
+        {
+            Some(Box::new(self.parse_expression(1)?))
+        };
+
+        let mut branches = Vec::new();
+        while self.peek() == Some(&Token::Keyword(Keyword::When)) 
+        {
+            let when = self.position;
+            self.advance();
+            let operands = self.parse_mixfix_operands(&[Token::Keyword(Keyword::Then)], 1)?;
+            let [condition, result] = <[Expression; 2]>::try_from(operands)
+                .map_err(|_| ParseError::InvalidExpression("Expected WHEN condition THEN result".to_string(), self.previous_span()))?;
+            branches.push(CaseWhen { condition, result, span: self.span_from(when) });
+        }
+        if branches.is_empty() 
+        {
+            self.note_expected("WHEN");
+            return Err(self.expected_error());
+        }
+
+        let else_result = if self.peek() == Some(&Token::Keyword(Keyword::Else)) 
+        {
+            self.advance();
+            Some(Box::new(self.parse_expression(1)?))
+        } 
+        else 
+        {
+            None
+        };
+
+        self.expect(&Token::Keyword(Keyword::End))?;
+        Ok(Expression::Case { operand, branches, else_result, span: self.span_from(start) })
+    }
+
+    // Consumes a postfix operator (one whose only operand is `left`).
+    fn parse_postfix(&mut self, left: Expression) -> Result<Expression, ParseError> 
+    {
+        let start = left.span();
+        let op = match self.advance() 
+        {
+            Some(tok) => tok.clone(),
+            None => return Err(ParseError::UnexpectedEnd(self.previous_span())),
+        };
+
+        match op 
+        {
+            Token::Keyword(Keyword::Is) => 
+            {
+                let negated = self.peek() == Some(&Token::Keyword(Keyword::Not));
+                if negated 
+                {
+                    self.advance();
+                }
+                let test = match self.advance() 
+                {
+                    Some(Token::Keyword(Keyword::Null)) => IsTest::Null,
+                    Some(Token::Keyword(Keyword::True)) => IsTest::True,
+                    Some(Token::Keyword(Keyword::False)) => IsTest::False,
+                    Some(Token::Identifier(word)) if word.eq_ignore_ascii_case("UNKNOWN") => IsTest::Unknown,
+                    _ => return Err(self.expected_before(&["NULL", "TRUE", "FALSE", "UNKNOWN"])),
+                };
+                Ok(Expression::Is 
+                {
+                    expr: Box::new(left),
+                    test,
+                    negated,
+                    span: self.span_since(start),
+                })
+            }
+            Token::Exclamation => Ok(Expression::UnaryOperation 
+            {
+                operator: UnaryOperator::Factorial,
+                operand: Box::new(left),
+                span: self.span_since(start),
+            }),
+            Token::Keyword(Keyword::Collate) => 
+            {
+                let collation = match self.advance() 
+                {
+                    Some(Token::Identifier(name)) => Ident::new(name.clone()),
+                    Some(Token::QuotedIdentifier { value, quote }) => Ident::quoted(value.clone(), *quote),
+                    Some(Token::String(name)) => Ident::quoted(name.clone(), '\''),
+                    _ => return Err(self.expected_before(&["a collation name"])),
+                };
+                let collation = collation.with_span(self.previous_span());
+                Ok(Expression::Collate 
+                {
+                    expr: Box::new(left),
+                    collation,
+                    span: self.span_since(start),
+                })
+            }
+            Token::LeftBracket => 
+            {
+                let subscript = self.parse_subscript()?;
+                Ok(Expression::Subscript 
+                {
+                    expr: Box::new(left),
+                    subscript: Box::new(subscript),
+                    span: self.span_since(start),
+                })
+            }
+            Token::DoubleColon => 
+            {
+                let data_type = self.parse_data_type()?;
+                Ok(Expression::Cast 
+                {
+                    expr: Box::new(left),
+                    data_type,
+                    span: self.span_since(start),
+                })
+            }
+            _ => Err(ParseError::General(format!("Unknown postfix operator {}", op), self.previous_span())),
+        }
+    }
+
+    // Consumes the operator at the current position and whatever operands follow it.
+    fn parse_infix(&mut self, left: Expression, precedence: u8) -> Result<Expression, ParseError> 
+    {
+        let start = left.span();
+        if let Some((length, syntax, negated, case_insensitive)) = self.peek_regex_operator() 
+        {
+            self.position += length;
+            let pattern = self.parse_expression(precedence + 1)?;
+            return Ok(Expression::RegexMatch 
+            {
+                expr: Box::new(left),
+                pattern: Box::new(pattern),
+                syntax,
+                negated,
+                case_insensitive,
+                span: self.span_since(start),
+            });
+        }
+
+        let negated = self.peek() == Some(&Token::Keyword(Keyword::Not));
+        if negated 
+        {
+            self.advance();
+        }
+
+        let op = match self.advance() 
+        {
+            Some(tok) => tok.clone(),
+            None => return Err(ParseError::UnexpectedEnd(self.previous_span())),
+        };
+
+        let operator = match op 
+        {
+            Token::Identifier(_) => 
+            {
+                // AT has been consumed; skip TIME ZONE
+                self.position += 2;
+                let time_zone = self.parse_expression(precedence + 1)?;
+                return Ok(Expression::AtTimeZone 
+                {
+                    timestamp: Box::new(left),
+                    time_zone: Box::new(time_zone),
+                    span: self.span_since(start),
+                });
+            }
+            Token::Keyword(Keyword::In) => 
+            {
+                self.expect(&Token::LeftParentheses)?;
+                let list = self.parse_expression_list()?;
+                return Ok(Expression::InList 
+                {
+                    expr: Box::new(left),
+                    list,
+                    negated,
+                    span: self.span_since(start),
+                });
+            }
+            Token::Keyword(Keyword::Like) | Token::Keyword(Keyword::ILike) => 
+            {
+                let pattern = self.parse_expression(precedence + 1)?;
+                return Ok(Expression::Like 
+                {
+                    expr: Box::new(left),
+                    pattern: Box::new(pattern),
+                    negated,
+                    case_insensitive: op == Token::Keyword(Keyword::ILike),
+                    span: self.span_since(start),
+                });
+            }
+            Token::Keyword(Keyword::Between) => 
+            {
+                // Bounds are parsed above AND so the separating AND is not taken as a conjunction.
+                let operands = self.parse_mixfix_operands(&[Token::Keyword(Keyword::And)], precedence + 1)?;
+                let [low, high] = <[Expression; 2]>::try_from(operands)
+                    .map_err(|_| ParseError::InvalidExpression("Expected BETWEEN low AND high".to_string(), self.previous_span()))?;
+                return Ok(Expression::Between 
+                {
+                    expr: Box::new(left),
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    negated,
+                    span: self.span_since(start),
+                });
+            }
+            Token::Equal => BinaryOperator::Equals,
+            Token::NotEqual => BinaryOperator::NotEquals,
+            Token::GreaterThan => BinaryOperator::GreaterThan,
+            Token::GreaterThanOrEqual => BinaryOperator::GreaterThanOrEqual,
+            Token::LessThan => BinaryOperator::LessThan,
+            Token::LessThanOrEqual => BinaryOperator::LessThanOrEqual,
+            Token::Plus => BinaryOperator::Add,
+            Token::Minus => BinaryOperator::Subtract,
+            Token::Multiply => BinaryOperator::Multiply,
+            Token::Divide => BinaryOperator::Divide,
+            Token::Caret => BinaryOperator::Exponent,
+            Token::Concat => BinaryOperator::StringConcat,
+            Token::ShiftLeft => BinaryOperator::BitwiseShiftLeft,
+            Token::ShiftRight => BinaryOperator::BitwiseShiftRight,
+            Token::Arrow => BinaryOperator::JsonGet,
+            Token::LongArrow => BinaryOperator::JsonGetText,
+            Token::HashArrow => BinaryOperator::JsonGetPath,
+            Token::HashLongArrow => BinaryOperator::JsonGetPathText,
+            Token::Keyword(Keyword::And) => BinaryOperator::And,
+            Token::Keyword(Keyword::Or) => BinaryOperator::Or,
+            _ => return Err(ParseError::General(format!("Unknown operator {}", op), self.previous_span())),
+        };
+
+        if get_associativity(&op) == Associativity::Right 
+        {
+            return self.parse_right_associative_chain(left, &op, operator, precedence);
+        }
+        let right = self.parse_expression(precedence + 1)?;
+        Ok(Expression::BinaryOperation 
+        {
+            left_operand: Box::new(left),
+            operator,
+            right_operand: Box::new(right),
+            span: self.span_since(start),
+        })
+    }
+
+    // Reads a ^ b ^ c, whose operator `op` has been consumed after `first`, in a loop rather than
+    // by recursion and groups it from the right as a ^ (b ^ c), so a long chain costs no stack.
+    fn parse_right_associative_chain(&mut self, first: Expression, op: &Token, operator: BinaryOperator, precedence: u8) -> Result<Expression, ParseError> 
+    {
+        // On an error parse_nested_expression puts chain_depth back
+        let chain_depth = self.chain_depth;
+        let mut operands = vec![first];
+        loop 
+        {
+            self.chain_operator()?;
+            operands.push(self.parse_expression(precedence + 1)?);
+            if self.peek() != Some(op) 
+            {
+                break;
+            }
+            self.advance();
+        }
+        self.chain_depth = chain_depth;
+
+        let mut right = operands.pop().expect("a chain has at least two operands");
+        while let Some(left) = operands.pop() 
+        {
+            right = Expression::BinaryOperation 
+            {
+                span: self.span_since(left.span()),
+                left_operand: Box::new(left),
+                operator: operator.clone(),
+                right_operand: Box::new(right),
+            };
+        }
+        Ok(right)
+    }
+
+    // A positional argument `value` or a named argument `name => value`.
+    fn parse_function_arg(&mut self) -> Result<FunctionArg, ParseError> 
+    {
+        let start = self.position;
+        if let (Some(Token::Identifier(name)), Some(Token::FatArrow)) = (self.peek(), self.tokens.get(self.position + 1)) 
+        {
+            let name = Ident::new(name.clone()).with_span(self.current_span());
+            self.position += 2;
+            let value = self.parse_expression(1)?;
+            return Ok(FunctionArg { name: Some(name), value, span: self.span_from(start) });
+        }
+        if self.peek() == Some(&Token::Multiply) && self.tokens.get(self.position + 1) == Some(&Token::RightParentheses) 
+        {
+            self.advance();
+            let span = self.span_from(start);
+            return Ok(FunctionArg { name: None, value: Expression::Wildcard(span), span });
+        }
+        let value = self.parse_expression(1)?;
+        Ok(FunctionArg { name: None, value, span: self.span_from(start) })
+    }
+
+    // CAST(expr AS type)
+    fn parse_cast(&mut self, start: usize) -> Result<Expression, ParseError> 
+    {
+        let expr = self.parse_expression(1)?;
+        self.expect(&Token::Keyword(Keyword::As))?;
+        let data_type = self.parse_data_type()?;
+        self.expect(&Token::RightParentheses)?;
+        Ok(Expression::Cast 
+        {
+            expr: Box::new(expr),
+            data_type,
+            span: self.span_from(start),
+        })
+    }
+
+    // POSITION(substring IN string)
+    fn parse_position(&mut self, start: usize) -> Result<Expression, ParseError> 
+    {
+        // Parse above IN's precedence so the IN separator is not taken as an IN list.
+        let substring = self.parse_expression(PATTERN_PRECEDENCE + 1)?;
+        self.expect(&Token::Keyword(Keyword::In))?;
+        let string = self.parse_expression(1)?;
+        self.expect(&Token::RightParentheses)?;
+        Ok(Expression::Position 
+        {
+            substring: Box::new(substring),
+            string: Box::new(string),
+            span: self.span_from(start),
+        })
+    }
+
+    // TRIM([LEADING | TRAILING | BOTH] [characters] FROM string) or TRIM(string)
+    fn parse_trim(&mut self, start: usize) -> Result<Expression, ParseError> 
+    {
+        let side = match self.peek() 
+        {
+            Some(Token::Keyword(Keyword::Leading)) => Some(TrimSide::Leading),
+            Some(Token::Keyword(Keyword::Trailing)) => Some(TrimSide::Trailing),
+            Some(Token::Keyword(Keyword::Both)) => Some(TrimSide::Both),
+            _ => None,
+        };
+        if side.is_some() 
+        {
+            self.advance();
+        }
+
+        let (characters, expr) = if side.is_some() && self.peek() == Some(&Token::Keyword(Keyword::From)) 
+        {
+            // TRIM(LEADING FROM string)
+            self.advance();
+            (None, self.parse_expression(1)?)
+        } 
+        else 
+        {
+            let first = self.parse_expression(1)?;
+            if self.peek() == Some(&Token::Keyword(Keyword::From)) 
+            {
+                self.advance();
+                (Some(Box::new(first)), self.parse_expression(1)?)
+            } 
+            else 
+            {
+                (None, first)
+            }
+        };
+
+        self.expect(&Token::RightParentheses)?;
+        Ok(Expression::Trim 
+        {
+            side,
+            characters,
+            expr: Box::new(expr),
+            span: self.span_from(start),
+        })
+    }
+
+    // INTERVAL 'value' [unit [(precision)] [TO unit]]
+    fn parse_interval(&mut self, start: usize) -> Result<Expression, ParseError> 
+    {
+        let value = match self.advance() 
+        {
+            Some(Token::String(s)) => s.clone(),
+            _ => return Err(self.expected_before(&["a string"])),
+        };
+
+        let unit = get_interval_unit(self.peek());
+        let mut precision = None;
+        let mut last_unit = None;
+        if unit.is_some() 
+        {
+            self.advance();
+            if self.peek() == Some(&Token::LeftParentheses) 
+            {
+                self.advance();
+                match self.advance() 
+                {
+                    Some(Token::Number(n)) => precision = Some(n.parse::<u64>().map_err(|_| ParseError::InvalidExpression(format!("Invalid interval precision: {}", n), self.previous_span()))?),
+                    _ => return Err(self.expected_before(&["a number"])),
+                }
+                self.expect(&Token::RightParentheses)?;
+            }
+            if let Some(Token::Identifier(word)) = self.peek() 
+            {
+                if word.eq_ignore_ascii_case("TO") 
+                {
+                    self.advance();
+                    last_unit = get_interval_unit(self.peek());
+                    if last_unit.is_none() 
+                    {
+                        self.note_expected("an interval unit");
+                        return Err(self.expected_error());
+                    }
+                    self.advance();
+                }
+            }
+        }
+
+        Ok(Expression::Interval 
+        {
+            value,
+            unit,
+            precision,
+            last_unit,
+            span: self.span_from(start),
+        })
+    }
+
+    // Called after ARRAY[ has been consumed; `start` is the index of ARRAY.
+    fn parse_array(&mut self, start: usize) -> Result<Expression, ParseError> 
+    {
+        let mut elements = Vec::new();
+        if self.peek() == Some(&Token::RightBracket) 
+        {
+            self.advance();
+            return Ok(Expression::Array(elements, self.span_from(start)));
+        }
+
+        loop 
+        {
+            elements.push(self.parse_expression(1)?);
+            match self.advance() 
+            {
+                Some(Token::Comma) => continue,
+                Some(Token::RightBracket) => break,
+                _ => return Err(self.expected_before(&["','", "']'"])),
+            }
+        }
+
+        Ok(Expression::Array(elements, self.span_from(start)))
+    }
+
+    // Called after the opening bracket has been consumed: [index], [lower:upper], [:upper], [lower:]
+    fn parse_subscript(&mut self) -> Result<Subscript, ParseError> 
+    {
+        let lower = match self.peek() 
+        {
+            Some(Token::Colon) => None,
+            _ => Some(self.parse_expression(1)?),
+        };
+
+        if self.peek() != Some(&Token::Colon) 
+        {
+            self.expect(&Token::RightBracket)?;
+            return match lower 
+            {
+                Some(index) => Ok(Subscript::Index(index)),
+                None => Err(ParseError::InvalidExpression("Expected an index inside []".to_string(), self.previous_span())),
+            };
+        }
+
+        self.advance();
+        let upper = match self.peek() 
+        {
+            Some(Token::RightBracket) => None,
+            _ => Some(self.parse_expression(1)?),
+        };
+        self.expect(&Token::RightBracket)?;
+        Ok(Subscript::Slice { lower, upper })
+    }
+
+    // Called after the opening parenthesis has been consumed; reads `expr, ...)`.
+    fn parse_expression_list(&mut self) -> Result<Vec<Expression>, ParseError> 
+    {
+        let mut list = Vec::new();
+        loop 
+        {
+            list.push(self.parse_expression(1)?);
+            match self.advance() 
+            {
+                Some(Token::Comma) => continue,
+                Some(Token::RightParentheses) => break,
+                _ => return Err(self.expected_before(&["','", "')'"])),
+            }
+        }
+        Ok(list)
+    }
+}
+
+#[cfg(test)]
+mod tests 
+{
+    use super::*;
+    use crate::tokenizer::Tokenizer;
+    use crate::ast::IntegerWidth;
+    use pretty_assertions::assert_eq;
+
+    fn parse(input: &str) -> Expression 
+    {
+        let mut tokenizer = Tokenizer::new(input);
+        let mut tokens = Vec::new();
+        loop 
+        {
+            let token = tokenizer.next_token();
+            let done = token == Token::Eof;
+            tokens.push(token);
+            if done 
+            {
+                break;
+            }
+        }
+        PrattParser::new(&tokens).parse_expression(1).unwrap()
+    }
+
+    // Fully parenthesized rendering so the expected grouping is easy to read.
+    fn grouping(expr: &Expression) -> String 
+    {
+        match expr 
+        {
+            Expression::Identifier(name) => name.value.clone(),
+            Expression::Literal(Literal::Integer(n), _) => n.to_string(),
+            Expression::UnaryOperation { operator, operand, .. } => 
+            {
+                let op = match operator 
+                {
+                    UnaryOperator::Not => "NOT ",
+                    UnaryOperator::Negate => "-",
+                    UnaryOperator::Factorial => return format!("({} !)", grouping(operand)),
+                };
+                format!("({}{})", op, grouping(operand))
+            }
+            Expression::BinaryOperation { left_operand, operator, right_operand, .. } => 
+            {
+                let op = match operator 
+                {
+                    BinaryOperator::Equals => "=",
+                    BinaryOperator::NotEquals => "!=",
+                    BinaryOperator::LessThan => "<",
+                    BinaryOperator::GreaterThan => ">",
+                    BinaryOperator::And => "AND",
+                    BinaryOperator::Or => "OR",
+                    BinaryOperator::Add => "+",
+                    BinaryOperator::Subtract => "-",
+                    BinaryOperator::Multiply => "*",
+                    BinaryOperator::Exponent => "^",
+                    BinaryOperator::StringConcat => "||",
+                    BinaryOperator::BitwiseShiftLeft => "<<",
+                    other => panic!("operator {:?} not used in these tests", other),
+                };
+                format!("({} {} {})", grouping(left_operand), op, grouping(right_operand))
+            }
+            Expression::Grouped(inner, _) => grouping(inner),
+            Expression::InList { expr, list, negated, .. } => 
+            {
+                let items: Vec<String> = list.iter().map(grouping).collect();
+                let not = if *negated { "NOT " } else { "" };
+                format!("({} {}IN ({}))", grouping(expr), not, items.join(", "))
+            }
+            Expression::Like { expr, pattern, negated, .. } => 
+            {
+                let not = if *negated { "NOT " } else { "" };
+                format!("({} {}LIKE {})", grouping(expr), not, grouping(pattern))
+            }
+            Expression::Between { expr, low, high, negated, .. } => 
+            {
+                let not = if *negated { "NOT " } else { "" };
+                format!("({} {}BETWEEN {} AND {})", grouping(expr), not, grouping(low), grouping(high))
+            }
+            Expression::Is { expr, test, negated, .. } => 
+            {
+                let not = if *negated { "NOT " } else { "" };
+                let test = match test 
+                {
+                    IsTest::Null => "NULL",
+                    IsTest::True => "TRUE",
+                    IsTest::False => "FALSE",
+                    IsTest::Unknown => "UNKNOWN",
+                };
+                format!("({} IS {}{})", grouping(expr), not, test)
+            }
+            other => panic!("expression {:?} not used in these tests", other),
+        }
+    }
+
+    #[test]
+    fn precedence_matrix() 
+    {
+        let cases = [
+            ("NOT a = b OR c AND d", "((NOT (a = b)) OR (c AND d))"),
+            ("a OR b AND c", "(a OR (b AND c))"),
+            ("a AND b OR c", "((a AND b) OR c)"),
+            ("NOT a AND b", "((NOT a) AND b)"),
+            ("NOT a OR b", "((NOT a) OR b)"),
+            ("NOT NOT a", "(NOT (NOT a))"),
+            ("a = b AND c = d", "((a = b) AND (c = d))"),
+            ("a < b = c", "((a < b) = c)"),
+            ("a = b < c", "((a = b) < c)"),
+            ("a + b = c * d", "((a + b) = (c * d))"),
+            ("a + b * c", "(a + (b * c))"),
+            ("a - b - c", "((a - b) - c)"),
+            ("-a * b", "((-a) * b)"),
+            ("-1 - -2", "(-1 - -2)"),
+            ("NOT -a > b", "(NOT ((-a) > b))"),
+            ("(a OR b) AND c", "((a OR b) AND c)"),
+            ("a IS NULL AND b", "((a IS NULL) AND b)"),
+            ("NOT a IS NULL", "(NOT (a IS NULL))"),
+            ("a = b IS NOT NULL", "((a = b) IS NOT NULL)"),
+            ("(a OR b) IS TRUE", "((a OR b) IS TRUE)"),
+            ("a BETWEEN 1 AND 2 AND b", "((a BETWEEN 1 AND 2) AND b)"),
+            ("a NOT BETWEEN b + 1 AND c OR d", "((a NOT BETWEEN (b + 1) AND c) OR d)"),
+            ("a LIKE b OR c NOT LIKE d", "((a LIKE b) OR (c NOT LIKE d))"),
+            ("a LIKE b = c", "((a LIKE b) = c)"),
+            ("a IN (1, 2) AND NOT b IN (3)", "((a IN (1, 2)) AND (NOT (b IN (3))))"),
+            ("a NOT IN (1) OR b = c + 1", "((a NOT IN (1)) OR (b = (c + 1)))"),
+            ("a || b = c", "((a || b) = c)"),
+            ("a + b || c * d", "((a + b) || (c * d))"),
+            ("a || b LIKE c", "((a || b) LIKE c)"),
+            ("a || b || c", "((a || b) || c)"),
+            ("1 << 2 + 3", "(1 << (2 + 3))"),
+        ];
+        for (input, expected) in cases 
+        {
+            assert_eq!(grouping(&parse(input)), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn exponent_is_right_associative() 
+    {
+        assert_eq!(grouping(&parse("2 ^ 3 ^ 2")), "(2 ^ (3 ^ 2))");
+        assert_eq!(grouping(&parse("a ^ b ^ c ^ d")), "(a ^ (b ^ (c ^ d)))");
+    }
+
+    #[test]
+    fn exponent_binds_tighter_than_multiplication() 
+    {
+        assert_eq!(grouping(&parse("a * b ^ c")), "(a * (b ^ c))");
+        assert_eq!(grouping(&parse("a ^ b * c")), "((a ^ b) * c)");
+        assert_eq!(grouping(&parse("-a ^ b")), "((-a) ^ b)");
+    }
+
+    #[test]
+    fn left_associative_operators_group_left() 
+    {
+        assert_eq!(grouping(&parse("a - b + c")), "((a - b) + c)");
+        assert_eq!(grouping(&parse("a * b * c")), "((a * b) * c)");
+    }
+
+    #[test]
+    fn postfix_operators() 
+    {
+        assert_eq!(grouping(&parse("a + b IS NULL")), "((a + b) IS NULL)");
+        assert_eq!(grouping(&parse("a IS NOT NULL OR b")), "((a IS NOT NULL) OR b)");
+        assert_eq!(grouping(&parse("2 * 3 !")), "(2 * (3 !))");
+        assert_eq!(grouping(&parse("-3 !")), "(-3 !)");
+        assert_eq!(grouping(&parse("a ! = b")), "((a !) = b)");
+    }
+
+    #[test]
+    fn cast_binds_tightest() 
+    {
+        assert_eq!(
+            parse("-a::int"),
+            Expression::UnaryOperation 
+            {
+                operator: UnaryOperator::Negate,
+                operand: Box::new(Expression::Cast 
+                {
+                    expr: Box::new(Expression::Identifier(Ident::new("a"))),
+                    data_type: DataType::Integer { width: IntegerWidth::Regular, unsigned: false },
+                    span: NodeSpan::default(),
+                }),
+                span: NodeSpan::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn identifiers_keep_case_and_quotes() 
+    {
+        assert_eq!(parse("MyCol"), Expression::Identifier(Ident::new("MyCol")));
+        assert_eq!(parse("`My Col`"), Expression::Identifier(Ident::quoted("My Col", '`')));
+        assert_eq!(
+            parse("[Order Details].Qty"),
+            Expression::CompoundIdentifier(vec![Ident::quoted("Order Details", '['), Ident::new("Qty")], NodeSpan::default())
+        );
+        assert_eq!(Ident::new("MyCol").normalized(), "mycol");
+        assert_eq!(Ident::quoted("MyCol", '`').normalized(), "MyCol");
+    }
+
+    #[test]
+    fn numbers_too_large_for_a_float_are_kept_as_written() 
+    {
+        assert_eq!(parse("1e400"), Expression::Literal(Literal::Decimal("1e400".to_string()), NodeSpan::default()));
+        assert_eq!(parse("1e300"), Expression::Literal(Literal::Float(1e300), NodeSpan::default()));
+        assert_eq!(parse("1e400").to_string(), "1e400");
+    }
+
+    #[test]
+    fn mixfix_constructs() 
+    {
+        assert_eq!(grouping(&parse("a BETWEEN b AND c + 1 AND d")), "((a BETWEEN b AND (c + 1)) AND d)");
+        assert_eq!(
+            parse("CASE WHEN a THEN 1 ELSE 2 END + 3"),
+            Expression::BinaryOperation 
+            {
+                left_operand: Box::new(Expression::Case 
+                {
+                    operand: None,
+                    branches: vec![CaseWhen 
+                    {
+                        condition: Expression::Identifier(Ident::new("a")),
+                        result: Expression::Literal(Literal::Integer(1), NodeSpan::default()),
+                        span: NodeSpan::default(),
+                    }],
+                    else_result: Some(Box::new(Expression::Literal(Literal::Integer(2), NodeSpan::default()))),
+                    span: NodeSpan::default(),
+                }),
+                operator: BinaryOperator::Add,
+                right_operand: Box::new(Expression::Literal(Literal::Integer(3), NodeSpan::default())),
+                span: NodeSpan::default(),
+            }
+        );
+    }
+
+    fn string(value: &str) -> Expression 
+    {
+        Expression::Literal(Literal::String(value.to_string()), NodeSpan::default())
+    }
+
+    fn name(value: &str) -> Expression 
+    {
+        Expression::Identifier(Ident::new(value))
+    }
+
+    #[test]
+    fn position_separates_its_arguments_with_in() 
+    {
+        assert_eq!(
+            parse("POSITION('b' IN name)"),
+            Expression::Position { substring: Box::new(string("b")), string: Box::new(name("name")), span: NodeSpan::default() }
+        );
+    }
+
+    #[test]
+    fn trim_takes_a_side_and_characters() 
+    {
+        let trim = |input: &str| match parse(input) 
+        {
+            Expression::Trim { side, characters, expr, .. } => (side, characters.map(|c| c.to_string()), expr.to_string()),
+            other => panic!("not a TRIM: {:?}", other),
+        };
+        assert_eq!(trim("TRIM(s)"), (None, None, "s".to_string()));
+        assert_eq!(trim("TRIM(LEADING 'x' FROM s)"), (Some(TrimSide::Leading), Some("'x'".to_string()), "s".to_string()));
+        assert_eq!(trim("TRIM(BOTH FROM s)"), (Some(TrimSide::Both), None, "s".to_string()));
+        assert_eq!(trim("TRIM('x' FROM s)"), (None, Some("'x'".to_string()), "s".to_string()));
+    }
+
+    #[test]
+    fn interval_literals() 
+    {
+        let interval = |input: &str| match parse(input) 
+        {
+            Expression::Interval { value, unit, precision, last_unit, .. } => (value, unit, precision, last_unit),
+            other => panic!("not an INTERVAL: {:?}", other),
+        };
+        assert_eq!(interval("INTERVAL '1 day'"), ("1 day".to_string(), None, None, None));
+        assert_eq!(interval("INTERVAL '2' SECOND (3)"), ("2".to_string(), Some(IntervalUnit::Second), Some(3), None));
+        assert_eq!(interval("INTERVAL '1:30' HOUR TO MINUTE"), ("1:30".to_string(), Some(IntervalUnit::Hour), None, Some(IntervalUnit::Minute)));
+    }
+
+    #[test]
+    fn typed_string_literals() 
+    {
+        assert_eq!(
+            parse("DATE '2024-01-01'"),
+            Expression::TypedString { data_type: DataType::Date, value: "2024-01-01".to_string(), span: NodeSpan::default() }
+        );
+        assert!(matches!(
+            parse("TIMESTAMPTZ '2024-01-01 10:00'"),
+            Expression::TypedString { data_type: DataType::Timestamp { with_time_zone: true, .. }, .. }
+        ));
+        // Without a string after it, DATE is an ordinary name
+        assert_eq!(parse("date"), name("date"));
+    }
+
+    #[test]
+    fn array_literals_and_subscripts() 
+    {
+        let one = || Expression::Literal(Literal::Integer(1), NodeSpan::default());
+        let two = || Expression::Literal(Literal::Integer(2), NodeSpan::default());
+        assert_eq!(
+            parse("ARRAY[1, 2][1]"),
+            Expression::Subscript 
+            {
+                expr: Box::new(Expression::Array(vec![one(), two()], NodeSpan::default())),
+                subscript: Box::new(Subscript::Index(one())),
+                span: NodeSpan::default(),
+            }
+        );
+        let slice = |input: &str| match parse(input) 
+        {
+            Expression::Subscript { subscript, .. } => *subscript,
+            other => panic!("not a subscript: {:?}", other),
+        };
+        assert_eq!(slice("arr[1:2]"), Subscript::Slice { lower: Some(one()), upper: Some(two()) });
+        assert_eq!(slice("arr[:2]"), Subscript::Slice { lower: None, upper: Some(two()) });
+        assert_eq!(slice("arr[1:]"), Subscript::Slice { lower: Some(one()), upper: None });
+    }
+
+    #[test]
+    fn tuples_compare_and_fill_in_lists() 
+    {
+        let one = || Expression::Literal(Literal::Integer(1), NodeSpan::default());
+        let two = || Expression::Literal(Literal::Integer(2), NodeSpan::default());
+        let Expression::BinaryOperation { left_operand, right_operand, .. } = parse("(a, b) = (1, 2)") else { panic!() };
+        assert_eq!(*left_operand, Expression::Tuple(vec![name("a"), name("b")], NodeSpan::default()));
+        assert_eq!(*right_operand, Expression::Tuple(vec![one(), two()], NodeSpan::default()));
+        let Expression::InList { expr, list, .. } = parse("(a, b) IN ((1, 2), (2, 1))") else { panic!() };
+        assert!(matches!(*expr, Expression::Tuple(..)));
+        assert!(list.iter().all(|item| matches!(item, Expression::Tuple(items, _) if items.len() == 2)));
+        // One parenthesized expression is a grouping, not a tuple
+        assert!(matches!(parse("(a)"), Expression::Grouped(..)));
+    }
+
+    #[test]
+    fn collate_binds_tighter_than_any_operator() 
+    {
+        let Expression::BinaryOperation { right_operand, .. } = parse("a || b COLLATE `de_DE`") else { panic!() };
+        let Expression::Collate { expr, collation, .. } = *right_operand else { panic!() };
+        assert_eq!((*expr, collation), (name("b"), Ident::quoted("de_DE", '`')));
+        let Expression::Collate { collation, .. } = parse("b COLLATE \"de_DE\"") else { panic!() };
+        assert_eq!(collation, Ident::quoted("de_DE", '"'));
+        let Expression::Collate { collation, .. } = parse("b COLLATE 'de_DE'") else { panic!() };
+        assert_eq!(collation, Ident::quoted("de_DE", '\''));
+    }
+
+    #[test]
+    fn at_time_zone() 
+    {
+        let Expression::AtTimeZone { timestamp, time_zone, .. } = parse("ts AT TIME ZONE 'UTC'") else { panic!() };
+        assert_eq!((*timestamp, *time_zone), (name("ts"), string("UTC")));
+        let Expression::BinaryOperation { left_operand, .. } = parse("ts AT TIME ZONE 'UTC' = x") else { panic!() };
+        assert!(matches!(*left_operand, Expression::AtTimeZone { .. }));
+    }
+
+    #[test]
+    fn regex_match_spellings() 
+    {
+        let regex = |input: &str| match parse(input) 
+        {
+            Expression::RegexMatch { syntax, negated, case_insensitive, .. } => (syntax, negated, case_insensitive),
+            other => panic!("not a regex match: {:?}", other),
+        };
+        assert_eq!(regex("a NOT SIMILAR TO 'x%'"), (RegexSyntax::SimilarTo, true, false));
+        assert_eq!(regex("a ~ 'x'"), (RegexSyntax::Posix, false, false));
+        assert_eq!(regex("a !~* 'x'"), (RegexSyntax::Posix, true, true));
+        assert_eq!(regex("a REGEXP 'x'"), (RegexSyntax::Posix, false, false));
+        assert_eq!(regex("a NOT RLIKE 'x'"), (RegexSyntax::Posix, true, false));
+    }
+
+    #[test]
+    fn json_operators_chain_left_to_right() 
+    {
+        let Expression::BinaryOperation { left_operand, operator: BinaryOperator::Equals, .. } = parse("doc -> 'a' ->> 'b' = 'c'") else { panic!() };
+        let Expression::BinaryOperation { left_operand: inner, operator: BinaryOperator::JsonGetText, .. } = *left_operand else { panic!() };
+        assert!(matches!(*inner, Expression::BinaryOperation { operator: BinaryOperator::JsonGet, .. }));
+        assert!(matches!(parse("doc #>> '{a,b}'"), Expression::BinaryOperation { operator: BinaryOperator::JsonGetPathText, .. }));
+    }
+
+    #[test]
+    fn json_operators_bind_looser_than_arithmetic() 
+    {
+        let Expression::BinaryOperation { operator: BinaryOperator::JsonGetText, right_operand, .. } = parse("a->>'x' + 1") else { panic!() };
+        assert!(matches!(*right_operand, Expression::BinaryOperation { operator: BinaryOperator::Add, .. }));
+        let Expression::BinaryOperation { left_operand, operator: BinaryOperator::StringConcat, .. } = parse("doc -> 'a' || 'b'") else { panic!() };
+        assert!(matches!(*left_operand, Expression::BinaryOperation { operator: BinaryOperator::JsonGet, .. }));
+        assert!(matches!(parse("doc -> 'a' LIKE 'b%'"), Expression::Like { .. }));
+    }
+
+    #[test]
+    fn named_function_arguments() 
+    {
+        let Expression::Function { name, args, .. } = parse("make_interval(days => 1, 2)") else { panic!() };
+        assert_eq!(name, Ident::new("make_interval"));
+        assert_eq!(args.into_iter().map(|arg| arg.name).collect::<Vec<_>>(), [Some(Ident::new("days")), None]);
+    }
+
+    #[test]
+    fn aggregate_filters() 
+    {
+        let Expression::Function { args, filter, .. } = parse("count(*) FILTER (WHERE a > 1)") else { panic!() };
+        assert!(matches!(args[0].value, Expression::Wildcard(_)));
+        assert_eq!(filter.unwrap().to_string(), "a > 1");
+        let Expression::Function { filter, .. } = parse("count(a)") else { panic!() };
+        assert!(filter.is_none());
+    }
+
+    #[test]
+    fn ordered_aggregates() 
+    {
+        let Expression::Function { args, order_by, .. } = parse("string_agg(name, ',' ORDER BY name DESC, id)") else { panic!() };
+        assert_eq!(args.len(), 2);
+        assert_eq!(order_by.iter().map(|item| (item.expr.to_string(), item.asc)).collect::<Vec<_>>(), [
+            ("name".to_string(), Some(false)),
+            ("id".to_string(), None),
+        ]);
+    }
+
+    #[test]
+    fn escaped_strings_become_plain_string_literals() 
+    {
+        assert_eq!(parse(r"E'it\'s\n'"), Expression::Literal(Literal::String("it's\n".to_string()), NodeSpan::default()));
+        assert_eq!(parse("'it''s'"), string("it's"));
+    }
+
+    #[test]
+    fn literal_kinds() 
+    {
+        let literal = |input: &str| match parse(input) 
+        {
+            Expression::Literal(literal, _) => literal,
+            other => panic!("not a literal: {:?}", other),
+        };
+        assert_eq!(literal("N'text'"), Literal::NationalString("text".to_string()));
+        assert_eq!(literal("x'FF'"), Literal::HexString("FF".to_string()));
+        assert_eq!(literal("-42"), Literal::Integer(-42));
+        assert_eq!(literal(".5"), Literal::Float(0.5));
+        assert_eq!(literal("170141183460469231731687303715884105728"), Literal::Decimal("170141183460469231731687303715884105728".to_string()));
+    }
+
+    #[test]
+    fn a_minus_before_a_number_is_part_of_the_literal() 
+    {
+        let literal = |value| Expression::Literal(Literal::Integer(value), NodeSpan::default());
+        let negate = |operand| Expression::UnaryOperation { operator: UnaryOperator::Negate, operand: Box::new(operand), span: NodeSpan::default() };
+        // With or without a space, -1 is one literal, so it does not have the shape of -a
+        assert_eq!(parse("-1"), literal(-1));
+        assert_eq!(parse("- 1"), literal(-1));
+        assert_eq!(parse("-a"), negate(name("a")));
+        assert_eq!(parse("-(1)"), negate(Expression::Grouped(Box::new(literal(1)), NodeSpan::default())));
+        assert_eq!(parse("- -1"), negate(literal(-1)));
+        // The literal is one operand, so the minus is not subject to precedence: -2 ^ 2 is (-2) ^ 2
+        assert_eq!(grouping(&parse("-2 ^ 2")), "(-2 ^ 2)");
+        assert_eq!(grouping(&parse("-a ^ 2")), "((-a) ^ 2)");
+        // Between operands it is still a subtraction
+        assert!(matches!(parse("2 -1"), Expression::BinaryOperation { operator: BinaryOperator::Subtract, .. }));
+    }
+
+    #[test]
+    fn casts_share_the_column_type_grammar() 
+    {
+        let cast_type = |input: &str| match parse(input) 
+        {
+            Expression::Cast { data_type, .. } => data_type,
+            other => panic!("not a cast: {:?}", other),
+        };
+        assert_eq!(cast_type("CAST(a AS DECIMAL(10, 2))"), DataType::Decimal(Some((10, Some(2)))));
+        assert_eq!(cast_type("a::TIMESTAMP WITH TIME ZONE"), DataType::Timestamp { precision: None, with_time_zone: true });
+        assert_eq!(cast_type("a::INT[]"), DataType::Array(Box::new(DataType::Integer { width: IntegerWidth::Regular, unsigned: false }), 1));
     }
 }
@@ -0,0 +1,537 @@
+// The type grammar shared by `::` casts, CAST(... AS type) and CREATE TABLE column definitions.
+use super::PrattParser;
+use crate::tokenizer::{Token, Keyword};
+use crate::ast::{CharacterLength, DataType, Ident, IntegerWidth, StructField};
+use crate::ParseError;
+
+impl<'a> PrattParser<'a> 
+{
+    /// Parses a type name such as INT or VARCHAR(20), as used in casts and column definitions.
+    pub fn parse_data_type(&mut self) -> Result<DataType, ParseError> 
+    {
+        self.nested(|parser| parser.parse_nested_data_type())
+    }
+
+    fn parse_nested_data_type(&mut self) -> Result<DataType, ParseError> 
+    {
+        let data_type = self.parse_base_data_type()?;
+
+        // Postgres array suffixes: INT[], TEXT[][], INT[3] (the size is not enforced, so it is not kept)
+        let mut dimensions = 0;
+        while self.peek() == Some(&Token::LeftBracket) 
+        {
+            self.advance();
+            if let Some(Token::Number(_)) = self.peek() 
+            {
+                self.advance();
+            }
+            self.expect(&Token::RightBracket)?;
+            dimensions += 1;
+        }
+        if dimensions > 0 
+        {
+            return Ok(DataType::Array(Box::new(data_type), dimensions));
+        }
+        Ok(data_type)
+    }
+
+    fn parse_base_data_type(&mut self) -> Result<DataType, ParseError> 
+    {
+        match self.advance() 
+        {
+            Some(Token::Keyword(Keyword::Array)) => 
+            {
+                // ARRAY<element type>
+                self.expect(&Token::LessThan)?;
+                let element = self.parse_data_type()?;
+                if !self.parse_closing(&Token::GreaterThan) 
+                {
+                    self.note_expected("'>'");
+                    return Err(self.expected_error());
+                }
+                Ok(DataType::Array(Box::new(element), 1))
+            }
+            Some(Token::Keyword(Keyword::Int)) => Ok(self.parse_integer_type(IntegerWidth::Regular)),
+            Some(Token::Keyword(Keyword::Bool)) | Some(Token::Keyword(Keyword::Boolean)) => Ok(DataType::Boolean),
+            Some(Token::Keyword(Keyword::Varchar)) => self.parse_varchar_size(),
+            Some(Token::Identifier(name)) => 
+            {
+                // Most type names are not reserved words, so they arrive as identifiers.
+                let name = name.clone();
+                self.parse_named_data_type(&name)
+            }
+            Some(Token::QuotedIdentifier { value, quote }) => 
+            {
+                // A quoted name is never a built-in type
+                let name = Ident::quoted(value.clone(), *quote).with_span(self.previous_span());
+                Ok(DataType::Custom(name, self.parse_custom_type_arguments()?))
+            }
+            Some(Token::String(value)) => 
+            {
+                // Where double quotes delimit strings, "MyType" arrives as one; no type is a string
+                let name = Ident::quoted(value.clone(), '"').with_span(self.previous_span());
+                Ok(DataType::Custom(name, self.parse_custom_type_arguments()?))
+            }
+            _ => Err(self.expected_before(&["a data type"])),
+        }
+    }
+
+    // `name` is the type name as written, already consumed.
+    fn parse_named_data_type(&mut self, name: &str) -> Result<DataType, ParseError> 
+    {
+        let upper = name.to_uppercase();
+        match upper.as_str() 
+        {
+            "INTEGER" | "INT4" => Ok(self.parse_integer_type(IntegerWidth::Regular)),
+            "TINYINT" => Ok(self.parse_integer_type(IntegerWidth::Tiny)),
+            "SMALLINT" | "INT2" => Ok(self.parse_integer_type(IntegerWidth::Small)),
+            "MEDIUMINT" => Ok(self.parse_integer_type(IntegerWidth::Medium)),
+            "BIGINT" | "INT8" => Ok(self.parse_integer_type(IntegerWidth::Big)),
+            "SMALLSERIAL" | "SERIAL2" => Ok(DataType::Serial(IntegerWidth::Small)),
+            "SERIAL" | "SERIAL4" => Ok(DataType::Serial(IntegerWidth::Regular)),
+            "BIGSERIAL" | "SERIAL8" => Ok(DataType::Serial(IntegerWidth::Big)),
+            "TEXT" => Ok(DataType::Text),
+            "CHAR" | "CHARACTER" => 
+            {
+                if self.peek_words(0, &["VARYING"]) 
+                {
+                    // CHARACTER VARYING(n) is the standard spelling of VARCHAR(n)
+                    self.advance();
+                    self.parse_varchar_size()
+                } 
+                else 
+                {
+                    Ok(DataType::Char(self.parse_optional_type_argument()?))
+                }
+            }
+            "BINARY" => Ok(DataType::Binary(self.parse_optional_type_argument()?)),
+            "VARBINARY" => Ok(DataType::Varbinary(self.parse_optional_type_argument()?)),
+            "BLOB" => Ok(DataType::Blob),
+            "BYTEA" => Ok(DataType::Bytea),
+            "UUID" => Ok(DataType::Uuid),
+            "JSON" => Ok(DataType::Json),
+            "JSONB" => Ok(DataType::Jsonb),
+            "ENUM" => Ok(DataType::Enum(self.parse_enum_values()?)),
+            "STRUCT" => 
+            {
+                self.expect(&Token::LessThan)?;
+                Ok(DataType::Struct(self.parse_struct_fields(&Token::GreaterThan)?))
+            }
+            "ROW" => 
+            {
+                self.expect(&Token::LeftParentheses)?;
+                Ok(DataType::Row(self.parse_struct_fields(&Token::RightParentheses)?))
+            }
+            "DATE" => Ok(DataType::Date),
+            "TIME" | "TIMETZ" => 
+            {
+                let precision = self.parse_optional_type_argument()?;
+                let with_time_zone = self.parse_time_zone_suffix(upper == "TIMETZ");
+                Ok(DataType::Time { precision, with_time_zone })
+            }
+            "TIMESTAMP" | "TIMESTAMPTZ" => 
+            {
+                let precision = self.parse_optional_type_argument()?;
+                let with_time_zone = self.parse_time_zone_suffix(upper == "TIMESTAMPTZ");
+                Ok(DataType::Timestamp { precision, with_time_zone })
+            }
+            "FLOAT" => Ok(DataType::Float(self.parse_optional_type_argument()?)),
+            "REAL" => Ok(DataType::Real),
+            "DECIMAL" | "DEC" | "NUMERIC" => Ok(DataType::Decimal(self.parse_precision_and_scale()?)),
+            "DOUBLE" => 
+            {
+                if self.peek_words(0, &["PRECISION"]) 
+                {
+                    self.advance();
+                }
+                Ok(DataType::Double)
+            }
+            // User-defined and dialect-specific types are kept as written
+            _ => 
+            {
+                let name = Ident::new(name).with_span(self.previous_span());
+                Ok(DataType::Custom(name, self.parse_custom_type_arguments()?))
+            }
+        }
+    }
+
+    // Optional parenthesized arguments of a custom type, kept as their source text.
+    fn parse_custom_type_arguments(&mut self) -> Result<Vec<String>, ParseError> 
+    {
+        let mut args = Vec::new();
+        if self.peek() != Some(&Token::LeftParentheses) 
+        {
+            return Ok(args);
+        }
+        self.advance();
+        loop 
+        {
+            match self.advance() 
+            {
+                Some(Token::Number(n)) | Some(Token::Identifier(n)) => args.push(n.clone()),
+                Some(Token::String(s)) => args.push(format!("'{}'", s.replace('\'', "''"))),
+                _ => return Err(self.expected_before(&["a type argument"])),
+            }
+            match self.advance() 
+            {
+                Some(Token::Comma) => continue,
+                Some(Token::RightParentheses) => break,
+                _ => return Err(self.expected_before(&["','", "')'"])),
+            }
+        }
+        Ok(args)
+    }
+
+    // Comma-separated `[name] type` fields up to and including `close`.
+    fn parse_struct_fields(&mut self, close: &Token) -> Result<Vec<StructField>, ParseError> 
+    {
+        let mut fields = Vec::new();
+        loop 
+        {
+            // An identifier may be a field name or the start of an unnamed type, and some types take
+            // more than one word, as in STRUCT<DOUBLE PRECISION>. It is a name only when the tokens
+            // from it do not form a complete type on their own.
+            let start = self.position;
+            let mut name = None;
+            if let Some(Token::Identifier(word)) = self.peek() 
+            {
+                let checkpoint = self.checkpoint();
+                let unnamed = self.parse_data_type().is_ok() && self.at_field_end(close);
+                self.rollback(checkpoint);
+                if !unnamed 
+                {
+                    name = Some(Ident::new(word.clone()).with_span(self.current_span()));
+                    self.advance();
+                }
+            }
+            let data_type = self.parse_data_type()?;
+            fields.push(StructField { name, data_type, span: self.span_from(start) });
+
+            if self.parse_closing(close) 
+            {
+                return Ok(fields);
+            }
+            self.expect(&Token::Comma)?;
+        }
+    }
+
+    // Whether a struct field ends here, before a ',' or `close`.
+    fn at_field_end(&self, close: &Token) -> bool 
+    {
+        match self.peek() 
+        {
+            Some(Token::Comma) => true,
+            _ if *close == Token::GreaterThan => self.pending_greater_than || matches!(self.peek(), Some(Token::GreaterThan) | Some(Token::ShiftRight)),
+            next => next == Some(close),
+        }
+    }
+
+    // Consumes `close` if it comes next. A closing '>' may be half of a '>>' token, as in
+    // ARRAY<ARRAY<INT>>: the first half closes the inner type and the second is kept for the outer one.
+    fn parse_closing(&mut self, close: &Token) -> bool 
+    {
+        if *close == Token::GreaterThan 
+        {
+            if self.pending_greater_than 
+            {
+                self.pending_greater_than = false;
+                return true;
+            }
+            if self.peek() == Some(&Token::ShiftRight) 
+            {
+                self.advance();
+                self.pending_greater_than = true;
+                return true;
+            }
+        }
+        if self.peek() == Some(close) 
+        {
+            self.advance();
+            return true;
+        }
+        false
+    }
+
+    // The parenthesized list of string values after ENUM.
+    fn parse_enum_values(&mut self) -> Result<Vec<String>, ParseError> 
+    {
+        self.expect(&Token::LeftParentheses)?;
+        let mut values = Vec::new();
+        loop 
+        {
+            match self.advance() 
+            {
+                Some(Token::String(value)) => values.push(value.clone()),
+                _ => return Err(self.expected_before(&["a string"])),
+            }
+            match self.advance() 
+            {
+                Some(Token::Comma) => continue,
+                Some(Token::RightParentheses) => break,
+                _ => return Err(self.expected_before(&["','", "')'"])),
+            }
+        }
+        Ok(values)
+    }
+
+    // Called after an integer type name; picks up a trailing UNSIGNED.
+    fn parse_integer_type(&mut self, width: IntegerWidth) -> DataType 
+    {
+        let unsigned = self.peek_words(0, &["UNSIGNED"]);
+        if unsigned 
+        {
+            self.advance();
+        }
+        DataType::Integer { width, unsigned }
+    }
+
+    // The optional (n) or (MAX) after VARCHAR.
+    fn parse_varchar_size(&mut self) -> Result<DataType, ParseError> 
+    {
+        if self.peek() != Some(&Token::LeftParentheses) 
+        {
+            return Ok(DataType::Varchar(None));
+        }
+        self.advance();
+        let length = if self.peek_words(0, &["MAX"]) 
+        {
+            self.advance();
+            CharacterLength::Max
+        } 
+        else 
+        {
+            CharacterLength::Length(self.parse_type_number()?)
+        };
+        self.expect(&Token::RightParentheses)?;
+        Ok(DataType::Varchar(Some(length)))
+    }
+
+    // Optional WITH TIME ZONE / WITHOUT TIME ZONE after TIME or TIMESTAMP.
+    fn parse_time_zone_suffix(&mut self, default: bool) -> bool 
+    {
+        if self.peek_words(0, &["WITH", "TIME", "ZONE"]) 
+        {
+            self.position += 3;
+            true
+        } 
+        else if self.peek_words(0, &["WITHOUT", "TIME", "ZONE"]) 
+        {
+            self.position += 3;
+            false
+        } 
+        else 
+        {
+            default
+        }
+    }
+
+    // Optional (precision) or (precision, scale), as in DECIMAL(10, 2).
+    fn parse_precision_and_scale(&mut self) -> Result<Option<(u64, Option<u64>)>, ParseError> 
+    {
+        if self.peek() != Some(&Token::LeftParentheses) 
+        {
+            return Ok(None);
+        }
+        self.advance();
+        let precision = self.parse_type_number()?;
+        let scale = if self.peek() == Some(&Token::Comma) 
+        {
+            self.advance();
+            Some(self.parse_type_number()?)
+        } 
+        else 
+        {
+            None
+        };
+        self.expect(&Token::RightParentheses)?;
+        Ok(Some((precision, scale)))
+    }
+
+    fn parse_type_number(&mut self) -> Result<u64, ParseError> 
+    {
+        match self.advance() 
+        {
+            Some(Token::Number(n)) => n.parse::<u64>().map_err(|_| ParseError::InvalidExpression(format!("Invalid type argument: {}", n), self.previous_span())),
+            _ => Err(self.expected_before(&["a number"])),
+        }
+    }
+
+    // An optional parenthesized number after a type name, as in FLOAT(24).
+    fn parse_optional_type_argument(&mut self) -> Result<Option<u64>, ParseError> 
+    {
+        if self.peek() != Some(&Token::LeftParentheses) 
+        {
+            return Ok(None);
+        }
+        self.advance();
+        let value = self.parse_type_number()?;
+        self.expect(&Token::RightParentheses)?;
+        Ok(Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests 
+{
+    use super::*;
+    use crate::tokenizer::tokenize;
+    use pretty_assertions::assert_eq;
+
+    fn data_type(input: &str) -> DataType 
+    {
+        let tokens: Vec<Token> = tokenize(input).unwrap().into_iter().map(|t| t.token).collect();
+        let mut parser = PrattParser::new(&tokens);
+        let data_type = parser.parse_data_type().unwrap();
+        assert_eq!(parser.peek(), Some(&Token::Eof), "{} was not read to the end", input);
+        data_type
+    }
+
+    #[test]
+    fn floating_point_types() 
+    {
+        assert_eq!(data_type("FLOAT"), DataType::Float(None));
+        assert_eq!(data_type("FLOAT(53)"), DataType::Float(Some(53)));
+        assert_eq!(data_type("REAL"), DataType::Real);
+        assert_eq!(data_type("DOUBLE PRECISION"), DataType::Double);
+        assert_eq!(data_type("DOUBLE"), DataType::Double);
+    }
+
+    #[test]
+    fn decimal_precision_and_scale() 
+    {
+        assert_eq!(data_type("NUMERIC"), DataType::Decimal(None));
+        assert_eq!(data_type("DECIMAL(10)"), DataType::Decimal(Some((10, None))));
+        assert_eq!(data_type("DECIMAL(10, 2)"), DataType::Decimal(Some((10, Some(2)))));
+    }
+
+    #[test]
+    fn date_and_time_types() 
+    {
+        assert_eq!(data_type("DATE"), DataType::Date);
+        assert_eq!(data_type("TIME(3)"), DataType::Time { precision: Some(3), with_time_zone: false });
+        assert_eq!(data_type("TIMETZ"), DataType::Time { precision: None, with_time_zone: true });
+        assert_eq!(data_type("TIMESTAMP(6) WITH TIME ZONE"), DataType::Timestamp { precision: Some(6), with_time_zone: true });
+        assert_eq!(data_type("TIMESTAMP WITHOUT TIME ZONE"), DataType::Timestamp { precision: None, with_time_zone: false });
+    }
+
+    #[test]
+    fn text_and_char_types() 
+    {
+        assert_eq!(data_type("TEXT"), DataType::Text);
+        assert_eq!(data_type("CHAR(3)"), DataType::Char(Some(3)));
+        assert_eq!(data_type("CHAR"), DataType::Char(None));
+    }
+
+    #[test]
+    fn integer_widths() 
+    {
+        let integer = |width, unsigned| DataType::Integer { width, unsigned };
+        assert_eq!(data_type("TINYINT"), integer(IntegerWidth::Tiny, false));
+        assert_eq!(data_type("int4"), integer(IntegerWidth::Regular, false));
+        assert_eq!(data_type("BIGINT UNSIGNED"), integer(IntegerWidth::Big, true));
+    }
+
+    #[test]
+    fn binary_types() 
+    {
+        assert_eq!(data_type("VARBINARY(16)"), DataType::Varbinary(Some(16)));
+        assert_eq!(data_type("BLOB"), DataType::Blob);
+        assert_eq!(data_type("BYTEA"), DataType::Bytea);
+    }
+
+    #[test]
+    fn uuid_type() 
+    {
+        assert_eq!(data_type("UUID"), DataType::Uuid);
+        assert_eq!(data_type("uuid"), DataType::Uuid);
+    }
+
+    #[test]
+    fn json_types() 
+    {
+        assert_eq!(data_type("JSON"), DataType::Json);
+        assert_eq!(data_type("JSONB"), DataType::Jsonb);
+    }
+
+    #[test]
+    fn array_types() 
+    {
+        let int = || DataType::Integer { width: IntegerWidth::Regular, unsigned: false };
+        assert_eq!(data_type("INT[]"), DataType::Array(Box::new(int()), 1));
+        assert_eq!(data_type("TEXT[3][]"), DataType::Array(Box::new(DataType::Text), 2));
+        assert_eq!(data_type("ARRAY<INT>"), DataType::Array(Box::new(int()), 1));
+        // >> closes two nested types at once
+        assert_eq!(data_type("ARRAY<ARRAY<INT>>"), DataType::Array(Box::new(DataType::Array(Box::new(int()), 1)), 1));
+    }
+
+    #[test]
+    fn serial_types() 
+    {
+        assert_eq!(data_type("SERIAL"), DataType::Serial(IntegerWidth::Regular));
+        assert_eq!(data_type("BIGSERIAL"), DataType::Serial(IntegerWidth::Big));
+    }
+
+    #[test]
+    fn enum_types() 
+    {
+        assert_eq!(data_type("ENUM('a', 'b')"), DataType::Enum(vec!["a".to_string(), "b".to_string()]));
+    }
+
+    #[test]
+    fn unknown_names_are_custom_types() 
+    {
+        assert_eq!(data_type("geometry"), DataType::Custom(Ident::new("geometry"), Vec::new()));
+        assert_eq!(data_type("GEOGRAPHY(POINT, 4326)"), DataType::Custom(Ident::new("GEOGRAPHY"), vec!["POINT".to_string(), "4326".to_string()]));
+        assert_eq!(data_type("\"MyType\"(3)"), DataType::Custom(Ident::quoted("MyType", '"'), vec!["3".to_string()]));
+        assert_eq!(data_type("my_type('it''s')"), DataType::Custom(Ident::new("my_type"), vec!["'it''s'".to_string()]));
+    }
+
+    #[test]
+    fn varchar_lengths() 
+    {
+        assert_eq!(data_type("VARCHAR"), DataType::Varchar(None));
+        assert_eq!(data_type("VARCHAR(20)"), DataType::Varchar(Some(CharacterLength::Length(20))));
+        assert_eq!(data_type("varchar(max)"), DataType::Varchar(Some(CharacterLength::Max)));
+        assert_eq!(data_type("CHARACTER VARYING(5)"), DataType::Varchar(Some(CharacterLength::Length(5))));
+    }
+
+    fn field(name: Option<&str>, data_type: DataType) -> StructField 
+    {
+        StructField { name: name.map(Ident::new), data_type, span: Default::default() }
+    }
+
+    #[test]
+    fn struct_and_row_types() 
+    {
+        let int = || DataType::Integer { width: IntegerWidth::Regular, unsigned: false };
+        assert_eq!(
+            data_type("STRUCT<a INT, s STRUCT<b TEXT>, TEXT>"),
+            DataType::Struct(vec![
+                field(Some("a"), int()),
+                field(Some("s"), DataType::Struct(vec![field(Some("b"), DataType::Text)])),
+                field(None, DataType::Text),
+            ])
+        );
+        assert_eq!(data_type("ROW(a INT, b TEXT)"), DataType::Row(vec![field(Some("a"), int()), field(Some("b"), DataType::Text)]));
+        assert_eq!(
+            data_type("STRUCT<STRUCT<b TEXT>, ARRAY<INT>>"),
+            DataType::Struct(vec![
+                field(None, DataType::Struct(vec![field(Some("b"), DataType::Text)])),
+                field(None, DataType::Array(Box::new(int()), 1)),
+            ])
+        );
+        // Unnamed types of more than one word are not read as a name and a type
+        assert_eq!(data_type("STRUCT<DOUBLE PRECISION>"), DataType::Struct(vec![field(None, DataType::Double)]));
+        assert_eq!(
+            data_type("STRUCT<TIMESTAMP WITH TIME ZONE, d DOUBLE PRECISION>"),
+            DataType::Struct(vec![
+                field(None, DataType::Timestamp { precision: None, with_time_zone: true }),
+                field(Some("d"), DataType::Double),
+            ])
+        );
+        assert_eq!(data_type("ROW(double DOUBLE, ts TIMESTAMP)"), DataType::Row(vec![
+            field(Some("double"), DataType::Double),
+            field(Some("ts"), DataType::Timestamp { precision: None, with_time_zone: false }),
+        ]));
+    }
+}
@@ -0,0 +1,307 @@
+// Compact s-expression rendering of statements, one list per node with the operator first:
+// SELECT a FROM t WHERE a > 1 is (select (columns a) (from t) (where (> a 1))).
+use crate::ast::*;
+
+/// The statement as a single-line s-expression
+pub fn statement_to_sexpr(statement: &Statement) -> String {
+    match statement {
+        Statement::Select { columns, from, selection, order_by, .. } => {
+            let mut parts = vec!["select".to_string(), list("columns", columns.iter().map(expression_to_sexpr))];
+            parts.push(from_clause(from));
+            if let Some(selection) = selection {
+                parts.push(list("where", [expression_to_sexpr(selection)]));
+            }
+            if let Some(order_by) = order_by {
+                parts.push(list("order-by", order_by.iter().map(order_by_expr)));
+            }
+            group(parts)
+        }
+        Statement::CreateTable { table_name, columns, constraints, options, .. } => {
+            let mut parts = vec!["create-table".to_string(), ident(table_name)];
+            parts.push(list("columns", columns.iter().map(column_def)));
+            if !constraints.is_empty() {
+                parts.push(list("constraints", constraints.iter().map(table_constraint)));
+            }
+            if !options.is_empty() {
+                let options = options.iter().map(|option| group([string(&option.name), string(&option.value)]));
+                parts.push(list("options", options));
+            }
+            group(parts)
+        }
+        Statement::Insert { or, table_name, columns, values, .. } => {
+            let mut parts = vec!["insert".to_string(), ident(table_name)];
+            parts.extend(or.iter().map(|action| list("or", [action.to_string().to_lowercase()])));
+            parts.push(list("columns", columns.iter().map(ident)));
+            parts.push(list("values", values.iter().map(expression_to_sexpr)));
+            group(parts)
+        }
+    }
+}
+
+/// The expression as a single-line s-expression. Grouping parentheses are implied by the nesting.
+pub fn expression_to_sexpr(expr: &Expression) -> String {
+    match expr {
+        Expression::Identifier(_)
+        | Expression::CompoundIdentifier(_, _)
+        | Expression::Placeholder(..)
+        | Expression::Wildcard(_)
+        | Expression::Error
+        | Expression::QualifiedWildcard(..) => atom(&expr.to_string()),
+        Expression::Literal(Literal::String(value), _) => string(value),
+        Expression::Literal(literal, _) => atom(&literal.to_string().to_lowercase()),
+        Expression::UnaryOperation { operator, operand, .. } => {
+            let operator = operator.to_string().to_lowercase();
+            group([operator, expression_to_sexpr(operand)])
+        }
+        Expression::BinaryOperation { left_operand, operator, right_operand, .. } => group([
+            operator.to_string().to_lowercase(),
+            expression_to_sexpr(left_operand),
+            expression_to_sexpr(right_operand),
+        ]),
+        Expression::Grouped(inner, _) => expression_to_sexpr(inner),
+        Expression::Function { name, args, order_by, filter, .. } => {
+            let mut parts = vec!["call".to_string(), ident(name)];
+            parts.extend(args.iter().map(|arg| match &arg.name {
+                Some(name) => group(["=>".to_string(), ident(name), expression_to_sexpr(&arg.value)]),
+                None => expression_to_sexpr(&arg.value),
+            }));
+            if !order_by.is_empty() {
+                parts.push(list("order-by", order_by.iter().map(order_by_expr)));
+            }
+            if let Some(filter) = filter {
+                parts.push(list("filter", [expression_to_sexpr(filter)]));
+            }
+            group(parts)
+        }
+        Expression::Position { substring, string, .. } => {
+            group(["position".to_string(), expression_to_sexpr(substring), expression_to_sexpr(string)])
+        }
+        Expression::Trim { side, characters, expr, .. } => {
+            let mut parts = vec!["trim".to_string(), expression_to_sexpr(expr)];
+            if let Some(side) = side {
+                parts.push(list("side", [side.to_string().to_lowercase()]));
+            }
+            if let Some(characters) = characters {
+                parts.push(list("characters", [expression_to_sexpr(characters)]));
+            }
+            group(parts)
+        }
+        Expression::Interval { value, unit, precision, last_unit, .. } => {
+            let mut parts = vec!["interval".to_string(), string(value)];
+            parts.extend(unit.iter().map(|unit| unit.to_string().to_lowercase()));
+            parts.extend(precision.iter().map(|precision| list("precision", [precision.to_string()])));
+            parts.extend(last_unit.iter().map(|unit| list("to", [unit.to_string().to_lowercase()])));
+            group(parts)
+        }
+        Expression::TypedString { data_type, value, .. } => group(["typed".to_string(), atom(&data_type.to_string()), string(value)]),
+        Expression::Array(elements, _) => list("array", elements.iter().map(expression_to_sexpr)),
+        Expression::Subscript { expr, subscript, .. } => match &**subscript {
+            Subscript::Index(index) => group(["subscript".to_string(), expression_to_sexpr(expr), expression_to_sexpr(index)]),
+            Subscript::Slice { lower, upper } => group([
+                "slice".to_string(),
+                expression_to_sexpr(expr),
+                lower.as_ref().map_or("_".to_string(), expression_to_sexpr),
+                upper.as_ref().map_or("_".to_string(), expression_to_sexpr),
+            ]),
+        },
+        Expression::Tuple(elements, _) => list("tuple", elements.iter().map(expression_to_sexpr)),
+        Expression::InList { expr, list: items, negated, .. } => {
+            let operator = if *negated { "not-in" } else { "in" };
+            list(operator, std::iter::once(expression_to_sexpr(expr)).chain(items.iter().map(expression_to_sexpr)))
+        }
+        Expression::Collate { expr, collation, .. } => group(["collate".to_string(), expression_to_sexpr(expr), string(&collation.value)]),
+        Expression::AtTimeZone { timestamp, time_zone, .. } => {
+            group(["at-time-zone".to_string(), expression_to_sexpr(timestamp), expression_to_sexpr(time_zone)])
+        }
+        Expression::RegexMatch { expr, pattern, syntax, negated, case_insensitive, .. } => {
+            let operator = match (syntax, negated, case_insensitive) {
+                (RegexSyntax::SimilarTo, false, _) => "similar-to",
+                (RegexSyntax::SimilarTo, true, _) => "not-similar-to",
+                (RegexSyntax::Posix, false, false) => "~",
+                (RegexSyntax::Posix, false, true) => "~*",
+                (RegexSyntax::Posix, true, false) => "!~",
+                (RegexSyntax::Posix, true, true) => "!~*",
+            };
+            group([operator.to_string(), expression_to_sexpr(expr), expression_to_sexpr(pattern)])
+        }
+        Expression::Like { expr, pattern, negated, case_insensitive, .. } => {
+            let operator = match (negated, case_insensitive) {
+                (false, false) => "like",
+                (false, true) => "ilike",
+                (true, false) => "not-like",
+                (true, true) => "not-ilike",
+            };
+            group([operator.to_string(), expression_to_sexpr(expr), expression_to_sexpr(pattern)])
+        }
+        Expression::Between { expr, low, high, negated, .. } => {
+            let operator = if *negated { "not-between" } else { "between" };
+            group([operator.to_string(), expression_to_sexpr(expr), expression_to_sexpr(low), expression_to_sexpr(high)])
+        }
+        Expression::Is { expr, test, negated, .. } => {
+            let operator = if *negated { "is-not" } else { "is" };
+            group([operator.to_string(), expression_to_sexpr(expr), test.to_string().to_lowercase()])
+        }
+        Expression::Cast { expr, data_type, .. } => group(["cast".to_string(), expression_to_sexpr(expr), atom(&data_type.to_string())]),
+        Expression::Case { operand, branches, else_result, .. } => {
+            let mut parts = vec!["case".to_string()];
+            parts.extend(operand.iter().map(|operand| expression_to_sexpr(operand)));
+            for branch in branches {
+                parts.push(group(["when".to_string(), expression_to_sexpr(&branch.condition), expression_to_sexpr(&branch.result)]));
+            }
+            parts.extend(else_result.iter().map(|result| list("else", [expression_to_sexpr(result)])));
+            group(parts)
+        }
+    }
+}
+
+// (from t (join (as u x) (on ...))): each table is its name, or (as name alias) if it has one
+fn from_clause(from: &TableWithJoins) -> String {
+    list("from", std::iter::once(table_factor(&from.relation)).chain(from.joins.iter().map(join)))
+}
+
+fn table_factor(table: &TableFactor) -> String {
+    match &table.alias {
+        Some(alias) => group(["as".to_string(), ident(&table.name), ident(alias)]),
+        None => ident(&table.name),
+    }
+}
+
+fn join(join: &Join) -> String {
+    let head = match join.join_operator {
+        JoinOperator::Inner(_) => "join",
+        JoinOperator::LeftOuter(_) => "left-join",
+        JoinOperator::RightOuter(_) => "right-join",
+        JoinOperator::FullOuter(_) => "full-join",
+        JoinOperator::Cross => "cross-join",
+    };
+    let mut parts = vec![head.to_string(), table_factor(&join.relation)];
+    match join.join_operator.constraint() {
+        Some(JoinConstraint::On(condition)) => parts.push(list("on", [expression_to_sexpr(condition)])),
+        Some(JoinConstraint::Using(columns)) => parts.push(list("using", columns.iter().map(ident))),
+        None => {}
+    }
+    group(parts)
+}
+
+fn order_by_expr(item: &OrderByExpr) -> String {
+    match item.asc {
+        Some(true) => list("asc", [expression_to_sexpr(&item.expr)]),
+        Some(false) => list("desc", [expression_to_sexpr(&item.expr)]),
+        None => expression_to_sexpr(&item.expr),
+    }
+}
+
+fn column_def(column: &ColumnDef) -> String {
+    let mut parts = vec!["column".to_string(), ident(&column.name), atom(&column.data_type.to_string())];
+    parts.extend(column.options.iter().map(column_option));
+    group(parts)
+}
+
+fn column_option(option: &ColumnOption) -> String {
+    match option {
+        ColumnOption::NotNull => "not-null".to_string(),
+        ColumnOption::Null => "null".to_string(),
+        ColumnOption::PrimaryKey => "primary-key".to_string(),
+        ColumnOption::Unique => "unique".to_string(),
+        ColumnOption::Default(expr) => list("default", [expression_to_sexpr(expr)]),
+        ColumnOption::Check(expr) => list("check", [expression_to_sexpr(expr)]),
+        ColumnOption::References(reference) => references(reference),
+        ColumnOption::Generated { expr, storage } => {
+            let storage = storage.iter().map(|storage| match storage {
+                GeneratedStorage::Stored => "stored".to_string(),
+                GeneratedStorage::Virtual => "virtual".to_string(),
+            });
+            list("generated", std::iter::once(expression_to_sexpr(expr)).chain(storage))
+        }
+        ColumnOption::Identity { always, start, increment } => {
+            let mut parts = vec!["identity".to_string(), if *always { "always" } else { "by-default" }.to_string()];
+            parts.extend(start.iter().map(|start| list("start", [start.to_string()])));
+            parts.extend(increment.iter().map(|increment| list("increment", [increment.to_string()])));
+            group(parts)
+        }
+        ColumnOption::AutoIncrement => "auto-increment".to_string(),
+    }
+}
+
+fn table_constraint(constraint: &TableConstraint) -> String {
+    let (name, body) = match constraint {
+        TableConstraint::PrimaryKey { name, columns, .. } => (name, list("primary-key", columns.iter().map(ident))),
+        TableConstraint::Unique { name, columns, .. } => (name, list("unique", columns.iter().map(ident))),
+        TableConstraint::Check { name, expr, .. } => (name, list("check", [expression_to_sexpr(expr)])),
+        TableConstraint::ForeignKey { name, columns, references: target, .. } => {
+            (name, group(["foreign-key".to_string(), group(columns.iter().map(ident)), references(target)]))
+        }
+    };
+    match name {
+        Some(name) => group(["constraint".to_string(), ident(name), body]),
+        None => body,
+    }
+}
+
+fn references(reference: &ForeignKeyReference) -> String {
+    let mut parts = vec!["references".to_string(), ident(&reference.table)];
+    if !reference.columns.is_empty() {
+        parts.push(group(reference.columns.iter().map(ident)));
+    }
+    let action = |action: &ReferentialAction| action.to_string().to_lowercase().replace(' ', "-");
+    parts.extend(reference.on_delete.iter().map(|a| list("on-delete", [action(a)])));
+    parts.extend(reference.on_update.iter().map(|a| list("on-update", [action(a)])));
+    group(parts)
+}
+
+// (head items...)
+fn list(head: &str, items: impl IntoIterator<Item = String>) -> String {
+    group(std::iter::once(head.to_string()).chain(items))
+}
+
+fn group(parts: impl IntoIterator<Item = String>) -> String {
+    format!("({})", parts.into_iter().collect::<Vec<_>>().join(" "))
+}
+
+fn ident(name: &Ident) -> String {
+    atom(&name.to_string())
+}
+
+// Text that is not a single atom, such as `TIMESTAMP WITH TIME ZONE`, is written as a string
+fn atom(text: &str) -> String {
+    if text.is_empty() || text.chars().any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '"')) {
+        string(text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_sql;
+
+    fn sexpr(sql: &str) -> String {
+        statement_to_sexpr(&parse_sql(sql).unwrap())
+    }
+
+    #[test]
+    fn statements_render_as_nested_lists() {
+        assert_eq!(
+            sexpr("SELECT a, count(*) FROM t WHERE (a + 1) * 2 > 3 AND b NOT IN (1, 'x y') ORDER BY a DESC"),
+            r#"(select (columns a (call count *)) (from t) (where (and (> (* (+ a 1) 2) 3) (not-in b 1 "x y"))) (order-by (desc a)))"#
+        );
+        assert_eq!(
+            sexpr("SELECT u.name FROM users u LEFT JOIN orders AS o ON u.id = o.user_id CROSS JOIN p JOIN q USING (id)"),
+            "(select (columns u.name) (from (as users u) (left-join (as orders o) (on (= u.id o.user_id))) (cross-join p) (join q (using id))))"
+        );
+        assert_eq!(
+            sexpr("CREATE TABLE t (id INT PRIMARY KEY CHECK (id > 0), ts TIMESTAMP WITH TIME ZONE NOT NULL, FOREIGN KEY (id) REFERENCES u (id) ON DELETE SET NULL)"),
+            r#"(create-table t (columns (column id INT primary-key (check (> id 0))) (column ts "TIMESTAMP WITH TIME ZONE" not-null)) (constraints (foreign-key (id) (references u (id) (on-delete set-null)))))"#
+        );
+        assert_eq!(
+            sexpr("INSERT INTO t (a, b) VALUES (NULL, -x)"),
+            "(insert t (columns a b) (values null (- x)))"
+        );
+        assert_eq!(sexpr("INSERT OR FAIL INTO t (a) VALUES (1)"), "(insert t (or fail) (columns a) (values 1))");
+    }
+}
@@ -0,0 +1,181 @@
+// Parses a script one statement at a time as it is read, so dump files far larger than memory
+// can be processed. Only the statement being parsed is held, never the whole input.
+use std::io::BufRead;
+
+use crate::tokenizer::{Location, Span, StatementScanner};
+use crate::{read_tokens_at, Limits, ParseError, ParserOptions, SQLParser, Statement};
+
+/// Yields the statements of a script read from `reader` one at a time, each parsed only when it
+/// is asked for. Statements end where StatementScanner finds them: at semicolons outside strings,
+/// quoted names, comments and dollar quoted bodies. Empty ones are skipped. Spans count from the start of the whole input.
+///
+/// ```
+/// use joel_chirayath::{Statement, StatementIterator};
+///
+/// let script = "SELECT a FROM t;\nINSERT INTO t (a) VALUES (';');\nSELECT FROM";
+/// let results: Vec<_> = StatementIterator::new(script.as_bytes()).collect();
+/// assert!(matches!(results[1], Ok(Statement::Insert { .. })));
+/// assert_eq!(results[2].as_ref().unwrap_err().span().start.line, 3);
+/// ```
+pub struct StatementIterator<R> {
+    reader: R,
+    options: ParserOptions,
+    limits: Limits,
+    // Text read but not yet parsed, and where in the input it starts
+    buffer: String,
+    buffer_start: Location,
+    // Finds the semicolon that ends the statement at the start of `buffer`
+    scanner: StatementScanner,
+    done: bool,
+}
+
+impl<R: BufRead> StatementIterator<R> {
+    pub fn new(reader: R) -> Self {
+        StatementIterator {
+            reader,
+            options: ParserOptions::default(),
+            limits: Limits::default(),
+            buffer: String::new(),
+            buffer_start: Location::default(),
+            scanner: StatementScanner::new(),
+            done: false,
+        }
+    }
+
+    /// Parses with `options` instead of the defaults; statements end where the dialect's
+    /// quoted names allow
+    pub fn with_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        if let Some(dialect) = options.dialect {
+            self.scanner = std::mem::take(&mut self.scanner).with_dialect(dialect);
+        }
+        self
+    }
+
+    /// Applies `limits` to each statement; max_input_length caps the length of one statement
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Removes the first `length` bytes of the buffer, plus the semicolon after them if
+    /// `terminated`, and returns them with where they started
+    fn take_statement(&mut self, length: usize, terminated: bool) -> (String, Location) {
+        let start = self.buffer_start;
+        let consumed = length + usize::from(terminated);
+        let mut text: String = self.buffer.drain(..consumed).collect();
+        for ch in text.chars() {
+            self.buffer_start.offset += ch.len_utf8();
+            if ch == '\n' {
+                self.buffer_start.line += 1;
+                self.buffer_start.column = 1;
+            } else {
+                self.buffer_start.column += 1;
+            }
+        }
+        self.scanner.discard(consumed);
+        text.truncate(length);
+        (text, start)
+    }
+
+    /// Parses one statement's text; None if it holds only whitespace and comments
+    fn parse(&self, text: &str, start: Location) -> Option<Result<Statement, ParseError>> {
+        let (tokens, spans) = match read_tokens_at(text, start, &self.limits, self.options.dialect) {
+            Ok(read) => read,
+            Err(error) => return Some(Err(error)),
+        };
+        let mut parser = SQLParser::new(&tokens)
+            .with_spans(&spans)
+            .with_source_at(text, start.offset)
+            .with_options(self.options)
+            .with_max_depth(self.limits.max_depth.min(self.options.max_depth));
+        let (mut statements, mut errors) = parser.parse_statements();
+        if !errors.is_empty() {
+            return Some(Err(errors.remove(0)));
+        }
+        statements.pop().map(Ok)
+    }
+}
+
+impl<R: BufRead> Iterator for StatementIterator<R> {
+    type Item = Result<Statement, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(end) = self.scanner.find_terminator(&self.buffer) {
+                let (text, start) = self.take_statement(end, true);
+                match self.parse(&text, start) {
+                    Some(result) => return Some(result),
+                    None => continue,
+                }
+            }
+            if self.done {
+                return None;
+            }
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => {
+                    // The last statement needs no semicolon
+                    self.done = true;
+                    let (text, start) = self.take_statement(self.buffer.len(), false);
+                    return self.parse(&text, start);
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    self.done = true;
+                    let at = Span { start: self.buffer_start, end: self.buffer_start };
+                    return Some(Err(ParseError::General(format!("Could not read the input: {}", error), at)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_script, Dialect};
+    use std::io::BufReader;
+
+    #[test]
+    fn statements_match_parse_script() {
+        let script = "SELECT 'a;b' FROM t; -- c;\nINSERT INTO x (a) VALUES (E'\\';');;\n\
+                      /* one; /* two; */ */ SELECT `a;` FROM u ORDER BY a;\n  \n;SELECT Key FROM v";
+        // A one-byte buffer makes every read return a single line, however short
+        let streamed: Vec<Statement> = StatementIterator::new(BufReader::with_capacity(1, script.as_bytes()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(streamed, parse_script(script).unwrap());
+        assert_eq!(streamed.len(), 4);
+        assert_eq!(streamed[3].span().start, Location { line: 5, column: 2, offset: script.len() - 17 });
+        // Keywords used as names are spelled from each statement's own text
+        assert_eq!(streamed[3].to_string(), "SELECT `Key` FROM v");
+    }
+
+    #[test]
+    fn statements_end_where_the_dialect_allows() {
+        let script = "SELECT [a;b] FROM t;\nSELECT [c] FROM [u;v]";
+        let options = ParserOptions { dialect: Some(Dialect::MsSql), ..ParserOptions::default() };
+        let streamed: Vec<Statement> = StatementIterator::new(script.as_bytes()).with_options(options).collect::<Result<_, _>>().unwrap();
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(streamed[1].to_string(), "SELECT [c] FROM [u;v]");
+    }
+
+    #[test]
+    fn each_failing_statement_is_its_own_error() {
+        let script = "SELECT FROM t;\nSELECT a FROM t\n  WHERE ;\nSELECT a FROM $$;$$ x;\nSELECT b FROM u";
+        let results: Vec<_> = StatementIterator::new(script.as_bytes()).collect();
+        let found: Vec<Result<usize, (&str, usize)>> = results
+            .iter()
+            .map(|result| match result {
+                Ok(statement) => Ok(statement.span().start.line),
+                Err(error) => Err((error.code(), error.span().start.line)),
+            })
+            .collect();
+        assert_eq!(found, [Err(("E0002", 1)), Err(("E0002", 3)), Err(("E0007", 4)), Ok(5)]);
+
+        let limits = Limits { max_input_length: 20, ..Limits::default() };
+        let results: Vec<_> = StatementIterator::new("SELECT a FROM t;\nSELECT abcdefghijklmnop FROM t".as_bytes()).with_limits(limits).collect();
+        assert!(results[0].is_ok());
+        assert_eq!(results[1].as_ref().unwrap_err().code(), "E0008");
+    }
+}
@@ -1,3 +1,7 @@
+mod dialect;  // Per-dialect keywords, identifier quotes and operators
+
+pub use dialect::{Dialect, GenericDialect, TokenizerDialect};
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Keyword 
 {
@@ -24,8 +28,231 @@ pub enum Keyword
     Insert,
     Values,
     Boolean,
-    LeftParen,
-    RightParen
+    In,
+    Leading,
+    Trailing,
+    Both,
+    Interval,
+    Array,
+    Collate,
+    Asc,
+    Desc,
+    Is,
+    Like,
+    ILike,
+    Between,
+    Case,
+    When,
+    Then,
+    Else,
+    End,
+    As,
+    Unique,
+    Default,
+    References,
+    Foreign,
+    On,
+    Constraint,
+}
+
+// Every keyword with its spelling and whether it is reserved. Non-reserved keywords are
+// still lexed as keywords, but the parser accepts them as names wherever a name is expected,
+// so a column called `key` or `values` does not break parsing.
+const KEYWORDS: &[(&str, Keyword, bool)] = &[
+    ("SELECT", Keyword::Select, true),
+    ("FROM", Keyword::From, true),
+    ("WHERE", Keyword::Where, true),
+    ("CREATE", Keyword::Create, true),
+    ("TABLE", Keyword::Table, true),
+    ("ORDER", Keyword::Order, false),
+    ("BY", Keyword::By, true),
+    ("AND", Keyword::And, true),
+    ("OR", Keyword::Or, true),
+    ("NOT", Keyword::Not, true),
+    ("TRUE", Keyword::True, true),
+    ("FALSE", Keyword::False, true),
+    ("INT", Keyword::Int, false),
+    ("BOOL", Keyword::Bool, false),
+    ("BOOLEAN", Keyword::Boolean, false),
+    ("VARCHAR", Keyword::Varchar, false),
+    ("PRIMARY", Keyword::Primary, true),
+    ("KEY", Keyword::Key, false),
+    ("CHECK", Keyword::Check, true),
+    ("NULL", Keyword::Null, true),
+    ("INSERT", Keyword::Insert, false),
+    ("INTO", Keyword::Into, true),
+    ("VALUES", Keyword::Values, false),
+    ("IN", Keyword::In, true),
+    ("LEADING", Keyword::Leading, true),
+    ("TRAILING", Keyword::Trailing, true),
+    ("BOTH", Keyword::Both, true),
+    ("INTERVAL", Keyword::Interval, true),
+    ("ARRAY", Keyword::Array, true),
+    ("COLLATE", Keyword::Collate, true),
+    ("ASC", Keyword::Asc, true),
+    ("DESC", Keyword::Desc, true),
+    ("IS", Keyword::Is, true),
+    ("LIKE", Keyword::Like, true),
+    ("ILIKE", Keyword::ILike, true),
+    ("BETWEEN", Keyword::Between, true),
+    ("CASE", Keyword::Case, true),
+    ("WHEN", Keyword::When, true),
+    ("THEN", Keyword::Then, true),
+    ("ELSE", Keyword::Else, true),
+    ("END", Keyword::End, true),
+    ("AS", Keyword::As, true),
+    ("UNIQUE", Keyword::Unique, true),
+    ("DEFAULT", Keyword::Default, true),
+    ("REFERENCES", Keyword::References, true),
+    ("FOREIGN", Keyword::Foreign, true),
+    ("ON", Keyword::On, true),
+    ("CONSTRAINT", Keyword::Constraint, true),
+];
+
+/// The keyword among `candidates` that `word` is most likely a misspelling of, as in
+/// SELCT for SELECT or FORM for FROM. Allows one typo in short words and two in longer ones.
+pub fn suggest_keyword(word: &str, candidates: &[Keyword]) -> Option<Keyword> 
+{
+    let word = word.to_uppercase();
+    let allowed = if word.chars().count() <= 4 { 1 } else { 2 };
+    candidates.iter()
+        .map(|keyword| (edit_distance(&word, keyword.as_str()), keyword))
+        .filter(|(distance, _)| *distance > 0 && *distance <= allowed)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, keyword)| keyword.clone())
+}
+
+// Edits (insert, delete, substitute, or swap two neighbours) needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize 
+{
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // rows[i][j] is the distance between a[..i] and b[..j]
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() 
+    {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() 
+    {
+        for j in 1..=b.len() 
+        {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] 
+            {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+// Operators longer than one character, longest first so that '->>' wins over '->'.
+const OPERATORS: &[(&str, Token)] = &[
+    ("->>", Token::LongArrow),
+    ("#>>", Token::HashLongArrow),
+    ("!~*", Token::NotTildeStar),
+    ("->", Token::Arrow),
+    ("#>", Token::HashArrow),
+    ("::", Token::DoubleColon),
+    ("<=", Token::LessThanOrEqual),
+    (">=", Token::GreaterThanOrEqual),
+    ("<>", Token::NotEqual),
+    ("!=", Token::NotEqual),
+    ("||", Token::Concat),
+    ("<<", Token::ShiftLeft),
+    (">>", Token::ShiftRight),
+    ("=>", Token::FatArrow),
+    ("~*", Token::TildeStar),
+    ("!~", Token::NotTilde),
+];
+
+// How a token is named in error messages, e.g. `','`, `SELECT` or `identifier a`.
+impl std::fmt::Display for Token 
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+    {
+        let symbol = match self 
+        {
+            Token::Keyword(keyword) => return f.write_str(keyword.as_str()),
+            Token::Identifier(name) => return write!(f, "identifier {}", name),
+            Token::QuotedIdentifier { value, quote } => 
+            {
+                let close = if *quote == '[' { ']' } else { *quote };
+                return write!(f, "identifier {}{}{}", quote, value, close);
+            }
+            Token::String(value) | Token::NationalString(value) | Token::RawString(value) => return write!(f, "string '{}'", value),
+            Token::EscapedString { raw, .. } => return write!(f, "string E'{}'", raw),
+            Token::Number(digits) => return write!(f, "number {}", digits),
+            Token::HexString(digits) => return write!(f, "hex string {}", digits),
+            Token::BitString(bits) => return write!(f, "bit string b'{}'", bits),
+            Token::Placeholder(name) => return write!(f, "parameter {}", name),
+            Token::Invalid(c) => return write!(f, "'{}'", c),
+            Token::Whitespace(_) => return f.write_str("whitespace"),
+            Token::LineComment(_) | Token::BlockComment(_) => return f.write_str("a comment"),
+            Token::Eof => return f.write_str("end of input"),
+            Token::LeftParentheses => "(",
+            Token::RightParentheses => ")",
+            Token::LeftBracket => "[",
+            Token::RightBracket => "]",
+            Token::Colon => ":",
+            Token::Exclamation => "!",
+            Token::GreaterThan => ">",
+            Token::LessThan => "<",
+            Token::Equal => "=",
+            Token::Tilde => "~",
+            Token::Multiply => "*",
+            Token::Divide => "/",
+            Token::Caret => "^",
+            Token::Minus => "-",
+            Token::Plus => "+",
+            Token::Comma => ",",
+            Token::Period => ".",
+            Token::Semicolon => ";",
+            operator => OPERATORS.iter()
+                .find(|(_, candidate)| candidate == operator)
+                .map_or("?", |(spelling, _)| spelling),
+        };
+        write!(f, "'{}'", symbol)
+    }
+}
+
+// The keyword as it is spelled in SQL, e.g. FROM.
+impl std::fmt::Display for Keyword 
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+    {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The keyword spelled `word` (in any case) in the standard keyword table.
+pub fn lookup_keyword(word: &str) -> Option<Keyword> 
+{
+    KEYWORDS.iter()
+        .find(|(spelling, _, _)| spelling.eq_ignore_ascii_case(word))
+        .map(|(_, keyword, _)| keyword.clone())
+}
+
+impl Keyword 
+{
+    // The keyword as it is spelled in SQL, in upper case.
+    pub fn as_str(&self) -> &'static str 
+    {
+        KEYWORDS.iter()
+            .find(|(_, keyword, _)| keyword == self)
+            .map_or("", |(spelling, _, _)| spelling)
+    }
+
+    pub fn is_reserved(&self) -> bool 
+    {
+        KEYWORDS.iter()
+            .find(|(_, keyword, _)| keyword == self)
+            .is_none_or(|(_, _, reserved)| *reserved)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -33,34 +260,112 @@ pub enum Token
 {
     Keyword(Keyword),
     Identifier(String),
+    // `name` (MySQL) or [name] (SQL Server): always an identifier, even when it spells a keyword
+    QuotedIdentifier { value: String, quote: char },
     String(String),
-    Number(u64),
+    // Postgres E'...' string: `value` has backslash escapes decoded, `raw` is the text between the quotes as written
+    EscapedString { value: String, raw: String },
+    // Digits exactly as written; the parser decides how to represent the value
+    Number(String),
+    // 0xFF or x'FF': the hex digits as written
+    HexString(String),
+    // b'1010': the bits as written
+    BitString(String),
+    // N'text': a national character set string
+    NationalString(String),
+    // r'raw': backslashes and other characters are kept exactly as written
+    RawString(String),
     Invalid(char),
-    Boolean(bool),
     LeftParentheses,
-    LeftParen,
     RightParentheses,
-    RightParen,
+    LeftBracket,
+    RightBracket,
+    Colon,
+    DoubleColon,
+    Exclamation,
     GreaterThan,
     GreaterThanOrEqual,
     LessThan,
     LessThanOrEqual,
     Equal,
+    FatArrow,
+    // != or <>
     NotEqual,
+    // ||
+    Concat,
+    // << and >>; inside ARRAY<ARRAY<INT>> the type parser splits >> back into two >
+    ShiftLeft,
+    ShiftRight,
+    Tilde,
+    TildeStar,
+    NotTilde,
+    NotTildeStar,
+    Arrow,
+    LongArrow,
+    HashArrow,
+    HashLongArrow,
     Multiply,
     Divide,
+    Caret,
     Minus,
     Plus,
     Comma,
+    Period,
     Semicolon,
+    // Bind parameter as written: ?, $1, :name or @name
+    Placeholder(String),
+    // Trivia, only produced by Tokenizer::with_trivia: runs of whitespace and comments as written
+    Whitespace(String),
+    LineComment(String),
+    BlockComment(String),
     Eof,
-    Null
+}
+
+// A point in the input: 1-based line and column (in characters) and the byte offset.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location 
+{
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+// The start of the input.
+impl Default for Location 
+{
+    fn default() -> Self 
+    {
+        Location { line: 1, column: 1, offset: 0 }
+    }
+}
+
+// The source range a token covers; `end` is just past its last character.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span 
+{
+    pub start: Location,
+    pub end: Location,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken 
+{
+    pub token: Token,
+    pub span: Span,
 }
 
 pub struct Tokenizer 
 {
     input: Vec<char>,
     position: usize,
+    location: Location,
+    // The last non-trivia token; decides if '[' starts a subscript or a [name] and ':' a :name
+    previous: Option<Token>,
+    // Emit whitespace and comments as tokens instead of skipping them
+    keep_trivia: bool,
+    dialect: Box<dyn TokenizerDialect>,
 }
 
 impl Tokenizer 
@@ -71,6 +376,77 @@ impl Tokenizer
         {
             input: input.chars().collect(),
             position: 0,
+            location: Location::default(),
+            previous: None,
+            keep_trivia: false,
+            dialect: Box::new(GenericDialect),
+        }
+    }
+
+    // Lexes with `dialect`'s keywords, identifier quotes and operators instead of the generic ones.
+    pub fn with_dialect(mut self, dialect: impl TokenizerDialect + 'static) -> Self 
+    {
+        self.dialect = Box::new(dialect);
+        self
+    }
+
+    // Numbers lines, columns and offsets from `start` instead of from the beginning, for input
+    // that was cut out of a larger text.
+    pub fn starting_at(mut self, start: Location) -> Self 
+    {
+        self.location = start;
+        self
+    }
+
+    // Makes the tokenizer return whitespace and comments as Whitespace, LineComment and
+    // BlockComment tokens, so the exact source can be rebuilt from the token stream.
+    pub fn with_trivia(mut self) -> Self 
+    {
+        self.keep_trivia = true;
+        self
+    }
+
+    // The input between two character positions, as written.
+    fn text(&self, start: usize, end: usize) -> String 
+    {
+        let end = end.min(self.input.len());
+        self.input[start.min(end)..end].iter().collect()
+    }
+
+    // Reads one run of whitespace or one comment. None if the input does not start with trivia.
+    fn read_trivia(&mut self) -> Option<Token> 
+    {
+        let start = self.position;
+        match (self.peek()?, self.peek_next()) 
+        {
+            (ch, _) if ch.is_whitespace() => 
+            {
+                while self.peek().is_some_and(char::is_whitespace) 
+                {
+                    self.advance();
+                }
+                Some(Token::Whitespace(self.text(start, self.position)))
+            }
+            ('-', Some('-')) => 
+            {
+                while self.peek().is_some_and(|ch| ch != '\n') 
+                {
+                    self.advance();
+                }
+                Some(Token::LineComment(self.text(start, self.position)))
+            }
+            ('/', Some('*')) => 
+            {
+                if self.skip_block_comment() 
+                {
+                    Some(Token::BlockComment(self.text(start, self.position)))
+                } 
+                else 
+                {
+                    Some(Token::Invalid('/'))
+                }
+            }
+            _ => None,
         }
     }
 
@@ -83,21 +459,101 @@ impl Tokenizer
     {
         let ch = self.peek();
         self.position += 1;
+        if let Some(ch) = ch 
+        {
+            self.location.offset += ch.len_utf8();
+            if ch == '\n' 
+            {
+                self.location.line += 1;
+                self.location.column = 1;
+            } 
+            else 
+            {
+                self.location.column += 1;
+            }
+        }
         ch
     }
 
-    fn skip_whitespace(&mut self) 
+    fn peek_next(&self) -> Option<char> 
+    {
+        self.input.get(self.position + 1).copied()
+    }
+
+    // Skips whitespace, `--` comments (to the end of the line) and /* */ comments.
+    // Returns false if a block comment is never closed.
+    fn skip_whitespace(&mut self) -> bool 
     {
         while let Some(ch) = self.peek() 
         {
             if ch.is_whitespace() 
             {
                 self.advance();
-            } else 
+            } 
+            else if ch == '-' && self.peek_next() == Some('-') 
+            {
+                while let Some(ch) = self.advance() 
+                {
+                    if ch == '\n' 
+                    {
+                        break;
+                    }
+                }
+            } 
+            else if ch == '/' && self.peek_next() == Some('*') 
+            {
+                if !self.skip_block_comment() 
+                {
+                    return false;
+                }
+            } 
+            else 
             {
                 break;
             }
         }
+        true
+    }
+
+    // Block comments nest, so /* a /* b */ c */ is a single comment.
+    fn skip_block_comment(&mut self) -> bool 
+    {
+        let mut depth = 0;
+        while let Some(ch) = self.advance() 
+        {
+            if ch == '/' && self.peek() == Some('*') 
+            {
+                self.advance();
+                depth += 1;
+            } 
+            else if ch == '*' && self.peek() == Some('/') 
+            {
+                self.advance();
+                depth -= 1;
+                if depth == 0 
+                {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    // Letters of any script and '_' may start an unquoted identifier.
+    fn is_identifier_start(ch: char) -> bool 
+    {
+        ch.is_alphabetic() || ch == '_'
+    }
+
+    // After the first character: digits, '$' and combining marks (as in a decomposed é)
+    // and the zero-width joiners used by some scripts.
+    fn is_identifier_part(ch: char) -> bool 
+    {
+        Self::is_identifier_start(ch)
+            || ch.is_numeric()
+            || ch == '$'
+            || matches!(ch, '\u{0300}'..='\u{036F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}')
+            || matches!(ch, '\u{200C}' | '\u{200D}')
     }
 
     fn read_identifier(&mut self) -> String 
@@ -105,7 +561,7 @@ impl Tokenizer
         let mut result = String::new();
         while let Some(ch) = self.peek() 
         {
-            if ch.is_alphanumeric() || ch == '_' 
+            if Self::is_identifier_part(ch) 
             {
                 result.push(ch);
                 self.advance();
@@ -118,134 +574,1091 @@ impl Tokenizer
         result
     }
 
-    fn lookup_keyword(word: &str) -> Option<Keyword> 
-    {
-        match word.to_uppercase().as_str() 
-        {
-            "SELECT" => Some(Keyword::Select),
-            "FROM" => Some(Keyword::From),
-            "WHERE" => Some(Keyword::Where),
-            "CREATE" => Some(Keyword::Create),
-            "TABLE" => Some(Keyword::Table),
-            "ORDER" => Some(Keyword::Order),
-            "BY" => Some(Keyword::By),
-            "AND" => Some(Keyword::And),
-            "OR" => Some(Keyword::Or),
-            "NOT" => Some(Keyword::Not),
-            "TRUE" => Some(Keyword::True),
-            "FALSE" => Some(Keyword::False),
-            "INT" => Some(Keyword::Int),
-            "BOOL" => Some(Keyword::Bool),
-            "VARCHAR" => Some(Keyword::Varchar),
-            "PRIMARY" => Some(Keyword::Primary),
-            "KEY" => Some(Keyword::Key),
-            "CHECK" => Some(Keyword::Check),
-            "NULL" => Some(Keyword::Null),
-            _ => None,
+    // Reads the body of an E'...' string after the opening quote.
+    fn read_escaped_string(&mut self) -> Token 
+    {
+        let mut value = String::new();
+        let mut raw = String::new();
+        while let Some(ch) = self.advance() 
+        {
+            match ch 
+            {
+                '\'' if self.peek() == Some('\'') => 
+                {
+                    self.advance();
+                    raw.push_str("''");
+                    value.push('\'');
+                }
+                '\'' => return Token::EscapedString { value, raw },
+                '\\' => 
+                {
+                    raw.push('\\');
+                    match self.read_escape_sequence(&mut raw) 
+                    {
+                        Some(decoded) => value.push(decoded),
+                        None => return Token::Invalid('\\'),
+                    }
+                }
+                _ => 
+                {
+                    raw.push(ch);
+                    value.push(ch);
+                }
+            }
+        }
+        Token::Invalid('\'')
+    }
+
+    // Decodes the escape sequence following a backslash: \n \t \r \b \f, octal \ooo,
+    // hex \xhh, unicode \uXXXX and \UXXXXXXXX; any other character stands for itself.
+    fn read_escape_sequence(&mut self, raw: &mut String) -> Option<char> 
+    {
+        let ch = self.advance()?;
+        raw.push(ch);
+        let (radix, max_digits) = match ch 
+        {
+            'n' => return Some('\n'),
+            't' => return Some('\t'),
+            'r' => return Some('\r'),
+            'b' => return Some('\u{8}'),
+            'f' => return Some('\u{c}'),
+            'x' => (16, 2),
+            'u' => (16, 4),
+            'U' => (16, 8),
+            '0'..='7' => (8, 3),
+            other => return Some(other),
+        };
+
+        // Octal escapes start with the digit just read; the others need at least one more digit.
+        let mut digits = if radix == 8 { ch.to_string() } else { String::new() };
+        while digits.len() < max_digits 
+        {
+            match self.peek() 
+            {
+                Some(next) if next.is_digit(radix) => 
+                {
+                    self.advance();
+                    raw.push(next);
+                    digits.push(next);
+                }
+                _ => break,
+            }
+        }
+        if digits.is_empty() 
+        {
+            // \x without hex digits is just an x
+            return Some(ch);
+        }
+        u32::from_str_radix(&digits, radix).ok().and_then(char::from_u32)
+    }
+
+    fn read_digits(&mut self, text: &mut String) 
+    {
+        while let Some(next) = self.peek() 
+        {
+            if next.is_ascii_digit() 
+            {
+                text.push(next);
+                self.advance();
+            } 
+            else 
+            {
+                break;
+            }
+        }
+    }
+
+    // Reads a numeric literal such as 42, 3.14, .5, 42., 6.02E23 or 0xFF whose first character is `first`.
+    fn read_number(&mut self, first: char) -> Token 
+    {
+        if first == '0' && matches!(self.peek(), Some('x') | Some('X')) && self.peek_next().is_some_and(|c| c.is_ascii_hexdigit()) 
+        {
+            // MySQL 0xFF
+            self.advance();
+            let mut digits = String::new();
+            while let Some(next) = self.peek().filter(|c| c.is_ascii_hexdigit()) 
+            {
+                digits.push(next);
+                self.advance();
+            }
+            return Token::HexString(digits);
+        }
+
+        let mut num_str = first.to_string();
+        self.read_digits(&mut num_str);
+        if first != '.' && self.peek() == Some('.') 
+        {
+            num_str.push('.');
+            self.advance();
+            self.read_digits(&mut num_str);
+        }
+
+        // An exponent needs digits after the e (and optional sign); otherwise the e starts an identifier.
+        if matches!(self.peek(), Some('e') | Some('E')) 
+        {
+            let sign_len = if matches!(self.peek_next(), Some('+') | Some('-')) { 1 } else { 0 };
+            if self.input.get(self.position + 1 + sign_len).is_some_and(|c| c.is_ascii_digit()) 
+            {
+                for _ in 0..=sign_len 
+                {
+                    num_str.push(self.input[self.position]);
+                    self.advance();
+                }
+                self.read_digits(&mut num_str);
+            }
+        }
+        Token::Number(num_str)
+    }
+
+    // Reads a quoted string after its opening `quote`; a doubled quote stands for one quote,
+    // as in 'O''Brien'. None if the string is never closed.
+    fn read_string_body(&mut self, quote: char) -> Option<String> 
+    {
+        let mut result = String::new();
+        while let Some(ch) = self.advance() 
+        {
+            if ch == quote && self.peek() == Some(quote) 
+            {
+                self.advance();
+                result.push(quote);
+            } 
+            else if ch == quote 
+            {
+                return Some(result);
+            } 
+            else 
+            {
+                result.push(ch);
+            }
         }
+        None
+    }
+
+    // Reads up to the closing `close`; a doubled closing character stands for itself.
+    fn read_quoted_identifier(&mut self, close: char) -> Token 
+    {
+        let mut name = String::new();
+        while let Some(ch) = self.advance() 
+        {
+            if ch == close 
+            {
+                if self.peek() == Some(close) 
+                {
+                    self.advance();
+                    name.push(close);
+                } 
+                else 
+                {
+                    let quote = if close == ']' { '[' } else { close };
+                    return Token::QuotedIdentifier { value: name, quote };
+                }
+            } 
+            else 
+            {
+                name.push(ch);
+            }
+        }
+        Token::Invalid(close)
+    }
+
+    // Reads the longest operator in OPERATORS that starts here and that the dialect supports.
+    fn read_operator(&mut self) -> Option<Token> 
+    {
+        let (text, token) = OPERATORS.iter().find(|(text, _)| 
+        {
+            text.chars().enumerate().all(|(i, c)| self.input.get(self.position + i) == Some(&c))
+                && self.dialect.supports_operator(text)
+        })?;
+        for _ in text.chars() 
+        {
+            self.advance();
+        }
+        Some(token.clone())
     }
 
     pub fn next_token(&mut self) -> Token 
     {
-        self.skip_whitespace();
+        self.next_spanned_token().token
+    }
+
+    // Like next_token, but also reports where the token is in the input.
+    pub fn next_spanned_token(&mut self) -> SpannedToken 
+    {
+        if self.keep_trivia 
+        {
+            let start = self.location;
+            if let Some(trivia) = self.read_trivia() 
+            {
+                // Trivia does not change whether '[' follows an operand
+                return SpannedToken { token: trivia, span: Span { start, end: self.location } };
+            }
+        }
+        let skipped = self.skip_whitespace();
+        let start = self.location;
+        let token = if skipped 
+        {
+            self.read_token()
+        } 
+        else 
+        {
+            // Unterminated block comment: it swallows the rest of the input
+            Token::Invalid('/')
+        };
+        self.previous = Some(token.clone());
+        SpannedToken { token, span: Span { start, end: self.location } }
+    }
+
+    // Whether the last token could end an operand, so a following '[' is a subscript.
+    fn after_operand(&self) -> bool 
+    {
+        ends_operand(self.previous.as_ref())
+    }
+
+    // Reads the name after ':' or '@' in a :name / @name placeholder.
+    fn read_named_placeholder(&mut self, sigil: char) -> Token 
+    {
+        let mut name = sigil.to_string();
+        name.push_str(&self.read_identifier());
+        Token::Placeholder(name)
+    }
+
+    // Reads one token starting at the current (non-whitespace) position.
+    fn read_token(&mut self) -> Token 
+    {
+        if let Some(operator) = self.read_operator() 
+        {
+            return operator;
+        }
         match self.advance() 
         {
             Some(',') => Token::Comma,
+            Some('.') if self.peek().is_some_and(|next| next.is_ascii_digit()) => self.read_number('.'),
+            Some('.') => Token::Period,
             Some(';') => Token::Semicolon,
             Some('(') => Token::LeftParentheses,
             Some(')') => Token::RightParentheses,
+            // SQL Server [Order Details] wherever a subscript could not start
+            Some('[') => match self.dialect.identifier_quote('[') 
+            {
+                Some(close) if !self.after_operand() => self.read_quoted_identifier(close),
+                _ => Token::LeftBracket,
+            },
+            Some(']') => Token::RightBracket,
+            // :name, except where ':' separates the bounds of a slice such as arr[lo:hi] or arr[:hi]
+            Some(':') if self.peek().is_some_and(Self::is_identifier_start)
+                && !self.after_operand()
+                && self.previous != Some(Token::LeftBracket) => self.read_named_placeholder(':'),
+            Some('@') if self.peek().is_some_and(Self::is_identifier_start) => self.read_named_placeholder('@'),
+            Some('?') => Token::Placeholder("?".to_string()),
+            Some('$') if self.peek().is_some_and(|c| c.is_ascii_digit()) => 
+            {
+                let mut text = "$".to_string();
+                self.read_digits(&mut text);
+                Token::Placeholder(text)
+            }
+            Some(':') => Token::Colon,
             Some('+') => Token::Plus,
             Some('-') => Token::Minus,
             Some('*') => Token::Multiply,
             Some('/') => Token::Divide,
+            Some('^') => Token::Caret,
             Some('=') => Token::Equal,
-            Some('>') => 
+            Some('>') => Token::GreaterThan,
+            Some('<') => Token::LessThan,
+            Some('!') => Token::Exclamation,
+            Some('~') => Token::Tilde,
+            Some(ch) if (ch == 'e' || ch == 'E') && self.peek() == Some('\'') => 
             {
-                if self.peek() == Some('=') 
-                {
-                    self.advance();
-                    Token::GreaterThanOrEqual
-                } 
-                else 
+                self.advance();
+                self.read_escaped_string()
+            }
+            Some(ch) if (ch == 'n' || ch == 'N') && self.peek() == Some('\'') => 
+            {
+                self.advance();
+                match self.read_string_body('\'') 
                 {
-                    Token::GreaterThan
+                    Some(value) => Token::NationalString(value),
+                    None => Token::Invalid('\''),
                 }
             }
-            Some('<') => 
+            Some(ch) if (ch == 'r' || ch == 'R') && matches!(self.peek(), Some('\'') | Some('"')) => 
             {
-                if self.peek() == Some('=') 
+                // Raw strings end at the first closing quote; nothing inside is an escape
+                let quote = self.advance().unwrap_or('\'');
+                let mut value = String::new();
+                while let Some(ch) = self.advance() 
                 {
-                    self.advance();
-                    Token::LessThanOrEqual
-                } 
-                else 
-                {
-                    Token::LessThan
+                    if ch == quote 
+                    {
+                        return Token::RawString(value);
+                    }
+                    value.push(ch);
                 }
+                Token::Invalid(quote)
             }
-            Some('!') => 
+            Some(ch) if (ch == 'x' || ch == 'X') && self.peek() == Some('\'') => 
             {
-                if self.peek() == Some('=') 
+                // x'DEADBEEF'
+                self.advance();
+                match self.read_string_body('\'') 
                 {
-                    self.advance();
-                    Token::NotEqual
-                } 
-                else 
+                    Some(digits) if digits.chars().all(|c| c.is_ascii_hexdigit()) => Token::HexString(digits),
+                    _ => Token::Invalid('\''),
+                }
+            }
+            Some(ch) if (ch == 'b' || ch == 'B') && self.peek() == Some('\'') => 
+            {
+                // b'1010'
+                self.advance();
+                match self.read_string_body('\'') 
                 {
-                    Token::Invalid('!')
+                    Some(bits) if bits.chars().all(|c| c == '0' || c == '1') => Token::BitString(bits),
+                    _ => Token::Invalid('\''),
                 }
             }
-            Some(ch) if ch.is_alphabetic() => 
+            Some(ch) if Self::is_identifier_start(ch) => 
             {
                 let mut ident = String::new();
                 ident.push(ch);
                 ident.push_str(&self.read_identifier());
-                if let Some(keyword) = Self::lookup_keyword(&ident) 
+                if let Some(keyword) = self.dialect.keyword(&ident) 
                 {
                     Token::Keyword(keyword)
-                } else 
+                } 
+                else 
                 {
                     Token::Identifier(ident)
                 }
             }
-            Some(ch) if ch.is_digit(10) => 
+            Some(ch) if ch.is_ascii_digit() => self.read_number(ch),
+            Some(open) if self.dialect.identifier_quote(open).is_some() => 
+            {
+                let close = self.dialect.identifier_quote(open).unwrap_or(open);
+                self.read_quoted_identifier(close)
+            }
+            // A collation is a name, so COLLATE "de_DE" keeps its double quotes even where they delimit strings
+            Some('"') if self.previous == Some(Token::Keyword(Keyword::Collate)) => self.read_quoted_identifier('"'),
+            Some(quote) if quote == '"' || quote == '\'' => match self.read_string_body(quote) 
+            {
+                Some(value) => Token::String(value),
+                None => Token::Invalid(quote),
+            },
+            None => Token::Eof,
+            Some(ch) => Token::Invalid(ch),
+        }
+    }
+}
+
+/// A token the tokenizer could not read: a stray character, or a string, quoted name or
+/// comment that is never closed. `character` is where the bad token starts.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexError 
+{
+    pub character: char,
+    pub span: Span,
+}
+
+impl std::fmt::Display for LexError 
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+    {
+        write!(f, "Invalid or unterminated token starting with '{}' at line {}, column {}",
+            self.character, self.span.start.line, self.span.start.column)
+    }
+}
+
+impl std::error::Error for LexError {}
+
+/// Tokenizes all of `input` without parsing it, for tools that only need the lexer
+/// (highlighting, statement splitting, token metrics). Whitespace and comments are skipped,
+/// and the last token is always `Token::Eof`. Fails on the first token that cannot be read.
+///
+/// ```
+/// use joel_chirayath::tokenizer::{tokenize, Token};
+///
+/// let tokens = tokenize("SELECT a\nFROM t").unwrap();
+/// assert_eq!(tokens[1].token, Token::Identifier("a".to_string()));
+/// assert_eq!((tokens[2].span.start.line, tokens[2].span.start.column), (2, 1));
+/// assert!(tokenize("SELECT 'open").is_err());
+/// ```
+pub fn tokenize(input: &str) -> Result<Vec<SpannedToken>, LexError> 
+{
+    let mut tokenizer = Tokenizer::new(input);
+    let mut tokens = Vec::new();
+    loop 
+    {
+        let spanned = tokenizer.next_spanned_token();
+        match spanned.token 
+        {
+            Token::Invalid(character) => return Err(LexError { character, span: spanned.span }),
+            Token::Eof => 
             {
-                let mut num_str = String::new();
-                num_str.push(ch);
-                while let Some(next) = self.peek() 
+                tokens.push(spanned);
+                return Ok(tokens);
+            }
+            _ => tokens.push(spanned),
+        }
+    }
+}
+
+// Whether `token` could end an operand, so a '[' after it is a subscript rather than a [name].
+fn ends_operand(token: Option<&Token>) -> bool 
+{
+    matches!(
+        token,
+        Some(Token::Identifier(_))
+            | Some(Token::QuotedIdentifier { .. })
+            | Some(Token::Placeholder(_))
+            | Some(Token::RightParentheses)
+            | Some(Token::RightBracket)
+            | Some(Token::Keyword(Keyword::Array | Keyword::Int | Keyword::Varchar | Keyword::Bool | Keyword::Boolean))
+    )
+}
+
+/// Splits a script into statements on semicolons, ignoring semicolons inside strings,
+/// quoted identifiers, comments and Postgres dollar-quoted bodies ($$ ... $$, $tag$ ... $tag$).
+/// Returned statements are trimmed, exclude the semicolon, and empty ones are dropped.
+/// Names are quoted as in GenericDialect; use StatementScanner::split for another dialect.
+pub fn split_statements(input: &str) -> Vec<&str> 
+{
+    StatementScanner::new().split(input)
+}
+
+/// Finds the semicolons that end statements in a script, skipping those inside strings, the
+/// dialect's quoted identifiers, comments and dollar-quoted bodies. The script may arrive a
+/// line at a time: each call carries on from where the last one stopped, even inside a string
+/// or comment.
+///
+/// ```
+/// use joel_chirayath::tokenizer::{Dialect, StatementScanner};
+///
+/// let script = "SELECT [a;b] FROM t; SELECT 1";
+/// assert_eq!(StatementScanner::new().with_dialect(Dialect::MsSql).split(script), vec!["SELECT [a;b] FROM t", "SELECT 1"]);
+/// ```
+pub struct StatementScanner 
+{
+    dialect: Box<dyn TokenizerDialect>,
+    // How much of the text has been scanned, and what that text ended inside
+    scanned: usize,
+    state: ScanState,
+}
+
+// What kind of text the next character of a script is part of
+enum ScanState 
+{
+    Code,
+    // A string or quoted name, up to its closing character
+    Quoted { close: char, backslash_escapes: bool },
+    LineComment,
+    // Block comments nest, so this is how many are open
+    BlockComment(usize),
+    // A $tag$ ... $tag$ body, holding its delimiter
+    DollarQuoted(String),
+}
+
+impl Default for StatementScanner 
+{
+    fn default() -> Self 
+    {
+        Self::new()
+    }
+}
+
+impl StatementScanner 
+{
+    pub fn new() -> Self 
+    {
+        StatementScanner { dialect: Box::new(GenericDialect), scanned: 0, state: ScanState::Code }
+    }
+
+    pub fn with_dialect(mut self, dialect: impl TokenizerDialect + 'static) -> Self 
+    {
+        self.dialect = Box::new(dialect);
+        self
+    }
+
+    /// Splits `input` into trimmed statements, as split_statements does
+    pub fn split(mut self, input: &str) -> Vec<&str> 
+    {
+        let mut statements = Vec::new();
+        let mut start = 0;
+        loop 
+        {
+            let end = self.find_terminator(input);
+            let statement = input[start..end.unwrap_or(input.len())].trim();
+            if !statement.is_empty() 
+            {
+                statements.push(statement);
+            }
+            match end 
+            {
+                Some(end) => start = end + 1,
+                None => return statements,
+            }
+        }
+    }
+
+    /// Scans `text` on from where the last call stopped and returns the byte offset of the
+    /// next semicolon that ends a statement, if there is one yet. Each call must be given the
+    /// text of the last one with any more appended, and text is best appended a whole line at
+    /// a time, so that looking a few characters ahead never runs past what has not arrived.
+    pub fn find_terminator(&mut self, text: &str) -> Option<usize> 
+    {
+        let mut i = self.scanned;
+        let next = |i: usize| text[i..].chars().next();
+        while let Some(ch) = next(i) 
+        {
+            let after = i + ch.len_utf8();
+            match &mut self.state 
+            {
+                ScanState::Code => match ch 
                 {
-                    if next.is_digit(10) 
+                    ';' => 
                     {
-                        num_str.push(next);
-                        self.advance();
-                    } 
-                    else 
+                        self.scanned = after;
+                        return Some(i);
+                    }
+                    '\'' | '"' | '`' | '[' => 
                     {
-                        break;
+                        let close = match ch 
+                        {
+                            '\'' => Some(ch),
+                            // A double quote that does not quote names delimits a string
+                            '"' => Some(self.dialect.identifier_quote(ch).unwrap_or(ch)),
+                            '[' if self.after_operand(&text[..i]) => None,
+                            _ => self.dialect.identifier_quote(ch),
+                        };
+                        if let Some(close) = close 
+                        {
+                            // E'...' strings use backslash escapes; everywhere else a doubled quote is the escape
+                            let backslash_escapes = ch == '\'' && text[..i].ends_with(['e', 'E']);
+                            self.state = ScanState::Quoted { close, backslash_escapes };
+                        }
+                    }
+                    '-' if next(after) == Some('-') => self.state = ScanState::LineComment,
+                    '/' if next(after) == Some('*') => 
+                    {
+                        self.state = ScanState::BlockComment(1);
+                        i = after + 1;
+                        continue;
+                    }
+                    '$' => 
+                    {
+                        let tag_length = text[after..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len() - after);
+                        let tag_is_valid = !next(after).is_some_and(|c| c.is_ascii_digit());
+                        if tag_is_valid && next(after + tag_length) == Some('$') 
+                        {
+                            let delimiter = text[i..after + tag_length + 1].to_string();
+                            i += delimiter.len();
+                            self.state = ScanState::DollarQuoted(delimiter);
+                            continue;
+                        }
+                    }
+                    _ => {}
+                },
+                ScanState::Quoted { close, backslash_escapes } => 
+                {
+                    if *backslash_escapes && ch == '\\' 
+                    {
+                        i = after + next(after).map_or(0, char::len_utf8);
+                        continue;
+                    }
+                    if ch == *close 
+                    {
+                        if next(after) == Some(ch) 
+                        {
+                            i = after + ch.len_utf8();
+                            continue;
+                        }
+                        self.state = ScanState::Code;
                     }
                 }
-                Token::Number(num_str.parse::<u64>().unwrap())
-            }
-            Some('"') | Some('\'') => 
-            {
-                let quote = self.input[self.position - 1];
-                let mut result = String::new();
-                while let Some(ch) = self.peek() 
+                ScanState::LineComment => 
                 {
-                    self.advance();
-                    if ch == quote 
+                    if ch == '\n' 
                     {
-                        return Token::String(result);
-                    } 
-                    else 
+                        self.state = ScanState::Code;
+                    }
+                }
+                ScanState::BlockComment(depth) => 
+                {
+                    if ch == '/' && next(after) == Some('*') 
+                    {
+                        *depth += 1;
+                        i = after + 1;
+                        continue;
+                    }
+                    if ch == '*' && next(after) == Some('/') 
                     {
-                        result.push(ch);
+                        *depth -= 1;
+                        if *depth == 0 
+                        {
+                            self.state = ScanState::Code;
+                        }
+                        i = after + 1;
+                        continue;
+                    }
+                }
+                ScanState::DollarQuoted(delimiter) => 
+                {
+                    if text[i..].starts_with(delimiter.as_str()) 
+                    {
+                        i += delimiter.len();
+                        self.state = ScanState::Code;
+                        continue;
                     }
                 }
-                Token::Invalid(quote)
             }
-            None => Token::Eof,
-            Some(ch) => Token::Invalid(ch),
+            i = after;
         }
+        self.scanned = i;
+        None
+    }
+
+    /// Forgets the first `length` bytes of the text, for a caller that removes each statement
+    /// from its buffer once it is found. The next call is given the text that remains.
+    pub fn discard(&mut self, length: usize) 
+    {
+        self.scanned -= length;
+    }
+
+    // Whether `before` ends with an operand, so a '[' after it is a subscript. Only the last
+    // word or bracket is looked at, as the tokenizer looks only at the previous token.
+    fn after_operand(&self, before: &str) -> bool 
+    {
+        let before = before.trim_end();
+        let word_start = before.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_').len();
+        let word = &before[word_start..];
+        let token = match before[..word_start].chars().last() 
+        {
+            Some('$' | ':' | '@') if !word.is_empty() => Token::Placeholder(word.to_string()),
+            _ if word.starts_with(|c: char| c.is_ascii_digit()) => Token::Number(word.to_string()),
+            _ if !word.is_empty() => match self.dialect.keyword(word) 
+            {
+                Some(keyword) => Token::Keyword(keyword),
+                None => Token::Identifier(word.to_string()),
+            },
+            Some('?') => Token::Placeholder("?".to_string()),
+            Some(')') => Token::RightParentheses,
+            Some(']') => Token::RightBracket,
+            Some(quote) if self.dialect.identifier_quote(quote) == Some(quote) => Token::QuotedIdentifier { value: String::new(), quote },
+            _ => return false,
+        };
+        ends_operand(Some(&token))
+    }
+}
+
+#[cfg(test)]
+mod tests 
+{
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tokens(input: &str) -> Vec<Token> 
+    {
+        collect(Tokenizer::new(input))
+    }
+
+    fn collect(mut tokenizer: Tokenizer) -> Vec<Token> 
+    {
+        let mut tokens = Vec::new();
+        loop 
+        {
+            let token = tokenizer.next_token();
+            if token == Token::Eof 
+            {
+                return tokens;
+            }
+            tokens.push(token);
+        }
+    }
+
+    fn number(text: &str) -> Token 
+    {
+        Token::Number(text.to_string())
+    }
+
+    fn ident(name: &str) -> Token 
+    {
+        Token::Identifier(name.to_string())
+    }
+
+    #[test]
+    fn spans_track_lines_and_columns() 
+    {
+        let mut tokenizer = Tokenizer::new("SELECT a,\n  'é' -- note\nFROM");
+        let mut spans = Vec::new();
+        loop 
+        {
+            let spanned = tokenizer.next_spanned_token();
+            let span = spanned.span;
+            spans.push((spanned.token, (span.start.line, span.start.column, span.start.offset), (span.end.line, span.end.column, span.end.offset)));
+            if spans.last().map(|s| &s.0) == Some(&Token::Eof) 
+            {
+                break;
+            }
+        }
+        assert_eq!(spans, vec![
+            (Token::Keyword(Keyword::Select), (1, 1, 0), (1, 7, 6)),
+            (ident("a"), (1, 8, 7), (1, 9, 8)),
+            (Token::Comma, (1, 9, 8), (1, 10, 9)),
+            (Token::String("é".to_string()), (2, 3, 12), (2, 6, 16)),
+            (Token::Keyword(Keyword::From), (3, 1, 25), (3, 5, 29)),
+            (Token::Eof, (3, 5, 29), (3, 5, 29)),
+        ]);
+    }
+
+    #[test]
+    fn hex_and_bit_literals() 
+    {
+        assert_eq!(tokens("0xFF"), vec![Token::HexString("FF".to_string())]);
+        assert_eq!(tokens("x'DEADBEEF' X''"), vec![Token::HexString("DEADBEEF".to_string()), Token::HexString(String::new())]);
+        assert_eq!(tokens("b'1010'"), vec![Token::BitString("1010".to_string())]);
+        assert_eq!(tokens("0x"), vec![number("0"), ident("x")]);
+        assert_eq!(tokens("x'GG'"), vec![Token::Invalid('\'')]);
+        assert_eq!(tokens("x + b"), vec![ident("x"), Token::Plus, ident("b")]);
+    }
+
+    // Postgres-style lexing: double quotes for names, no backticks or brackets, no '<<'
+    struct TestDialect;
+
+    impl TokenizerDialect for TestDialect 
+    {
+        fn keyword(&self, word: &str) -> Option<Keyword> 
+        {
+            if word.eq_ignore_ascii_case("key") { None } else { lookup_keyword(word) }
+        }
+
+        fn identifier_quote(&self, open: char) -> Option<char> 
+        {
+            if open == '"' { Some('"') } else { None }
+        }
+
+        fn supports_operator(&self, operator: &str) -> bool 
+        {
+            operator != "<<"
+        }
+    }
+
+    #[test]
+    fn dialect_decides_keywords_quotes_and_operators() 
+    {
+        let lex = |input: &str| collect(Tokenizer::new(input).with_dialect(TestDialect));
+        assert_eq!(lex("\"Name\" key"), vec![Token::QuotedIdentifier { value: "Name".to_string(), quote: '"' }, ident("key")]);
+        assert_eq!(lex("a << b >> c"), vec![ident("a"), Token::LessThan, Token::LessThan, ident("b"), Token::ShiftRight, ident("c")]);
+        assert_eq!(lex("[x] `y`"), vec![Token::LeftBracket, ident("x"), Token::RightBracket, Token::Invalid('`'), ident("y"), Token::Invalid('`')]);
+        // The generic dialect is unchanged
+        assert_eq!(tokens("\"Name\" key"), vec![Token::String("Name".to_string()), Token::Keyword(Keyword::Key)]);
+    }
+
+    #[test]
+    fn built_in_dialects() 
+    {
+        let lex = |input: &str, dialect: Dialect| collect(Tokenizer::new(input).with_dialect(dialect));
+        let quoted = |value: &str, quote: char| Token::QuotedIdentifier { value: value.to_string(), quote };
+        assert_eq!(lex("\"a\" `b`", Dialect::MySql), vec![Token::String("a".to_string()), quoted("b", '`')]);
+        assert_eq!(lex("[b] \"a\"", Dialect::MsSql), vec![quoted("b", '['), quoted("a", '"')]);
+        assert_eq!(lex("x::INT", Dialect::Postgres), vec![ident("x"), Token::DoubleColon, Token::Keyword(Keyword::Int)]);
+        assert_eq!(lex("x->>'k'", Dialect::Ansi), vec![ident("x"), Token::Minus, Token::GreaterThan, Token::GreaterThan, Token::String("k".to_string())]);
+        assert_eq!(lex("a ILIKE b", Dialect::Sqlite), vec![ident("a"), ident("ILIKE"), ident("b")]);
+        assert_eq!(Dialect::from_name("PostgreSQL"), Some(Dialect::Postgres));
+    }
+
+    #[test]
+    fn prefixed_strings() 
+    {
+        assert_eq!(tokens("N'text' n'it''s'"), vec![Token::NationalString("text".to_string()), Token::NationalString("it's".to_string())]);
+        assert_eq!(tokens("B'0101'"), vec![Token::BitString("0101".to_string())]);
+        assert_eq!(tokens("r'a\\d+' R\"x\""), vec![Token::RawString("a\\d+".to_string()), Token::RawString("x".to_string())]);
+        assert_eq!(tokens("n r"), vec![ident("n"), ident("r")]);
+    }
+
+    #[test]
+    fn split_statements_ignores_quoted_semicolons() 
+    {
+        let script = "SELECT 'a;b' FROM t; -- c;\nINSERT INTO \"x;y\" (a) VALUES (E'\\';');;\n\
+                      /* one; /* two; */ */ CREATE FUNCTION f() AS $body$ a; b $body$;\
+                      SELECT $$;$$, $1 FROM t";
+        assert_eq!(split_statements(script), vec![
+            "SELECT 'a;b' FROM t",
+            "-- c;\nINSERT INTO \"x;y\" (a) VALUES (E'\\';')",
+            "/* one; /* two; */ */ CREATE FUNCTION f() AS $body$ a; b $body$",
+            "SELECT $$;$$, $1 FROM t",
+        ]);
+        assert_eq!(split_statements("  ;; "), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn statements_split_on_the_dialects_quotes() 
+    {
+        let script = "SELECT [a;b], x[1] FROM [t;] WHERE \"c;\" = `d;`; SELECT 1";
+        let split = |dialect: Dialect| StatementScanner::new().with_dialect(dialect).split(script);
+        assert_eq!(split_statements(script), vec!["SELECT [a;b], x[1] FROM [t;] WHERE \"c;\" = `d;`", "SELECT 1"]);
+        // SQL Server has no `names` and MySQL no [names]: the semicolons inside them end statements
+        assert_eq!(split(Dialect::MsSql), vec!["SELECT [a;b], x[1] FROM [t;] WHERE \"c;\" = `d", "`", "SELECT 1"]);
+        assert_eq!(split(Dialect::MySql), vec!["SELECT [a", "b], x[1] FROM [t", "] WHERE \"c;\" = `d;`", "SELECT 1"]);
+
+        // Fed a line at a time, the scanner carries a quote over to the next line
+        let mut scanner = StatementScanner::new().with_dialect(Dialect::MsSql);
+        let mut text = "SELECT [a;\n".to_string();
+        assert_eq!(scanner.find_terminator(&text), None);
+        text.push_str("b] FROM t;\n");
+        assert_eq!(scanner.find_terminator(&text), Some(text.len() - 2));
+    }
+
+    #[test]
+    fn tokenize_reports_the_first_bad_token() 
+    {
+        let tokens = tokenize("a = 'x'").unwrap();
+        let kinds: Vec<Token> = tokens.into_iter().map(|t| t.token).collect();
+        assert_eq!(kinds, vec![ident("a"), Token::Equal, Token::String("x".to_string()), Token::Eof]);
+
+        let error = tokenize("SELECT a,\n  'oops FROM t").unwrap_err();
+        assert_eq!(error.character, '\'');
+        assert_eq!((error.span.start.line, error.span.start.column), (2, 3));
+    }
+
+    #[test]
+    fn trivia_mode_keeps_whitespace_and_comments() 
+    {
+        let input = "SELECT a -- first\n  /* note */ FROM t";
+        let mut tokenizer = Tokenizer::new(input).with_trivia();
+        let mut all = Vec::new();
+        loop 
+        {
+            let token = tokenizer.next_token();
+            if token == Token::Eof 
+            {
+                break;
+            }
+            all.push(token);
+        }
+        let space = |text: &str| Token::Whitespace(text.to_string());
+        assert_eq!(all, vec![
+            Token::Keyword(Keyword::Select),
+            space(" "),
+            ident("a"),
+            space(" "),
+            Token::LineComment("-- first".to_string()),
+            space("\n  "),
+            Token::BlockComment("/* note */".to_string()),
+            space(" "),
+            Token::Keyword(Keyword::From),
+            space(" "),
+            ident("t"),
+        ]);
+        assert_eq!(tokens(input), vec![Token::Keyword(Keyword::Select), ident("a"), Token::Keyword(Keyword::From), ident("t")]);
+    }
+
+    #[test]
+    fn unicode_identifiers() 
+    {
+        assert_eq!(tokens("straße 东京 _tmp"), vec![ident("straße"), ident("东京"), ident("_tmp")]);
+        assert_eq!(tokens("cafe\u{301}.nai\u{308}ve"), vec![ident("cafe\u{301}"), Token::Period, ident("nai\u{308}ve")]);
+        assert_eq!(tokens("东京+1,straße"), vec![ident("东京"), Token::Plus, number("1"), Token::Comma, ident("straße")]);
+        assert_eq!(tokens("a$1 x٣"), vec![ident("a$1"), ident("x٣")]);
+        assert_eq!(tokens("1东京"), vec![number("1"), ident("东京")]);
+        // Combining marks and full-width punctuation cannot start a token
+        assert_eq!(tokens("\u{301}"), vec![Token::Invalid('\u{301}')]);
+        assert_eq!(tokens("东京，"), vec![ident("东京"), Token::Invalid('，')]);
+    }
+
+    #[test]
+    fn placeholders() 
+    {
+        let placeholder = |text: &str| Token::Placeholder(text.to_string());
+        assert_eq!(
+            tokens("a = ? AND b = $12 OR c = :name OR d = @var"),
+            vec![
+                ident("a"), Token::Equal, placeholder("?"), Token::Keyword(Keyword::And),
+                ident("b"), Token::Equal, placeholder("$12"), Token::Keyword(Keyword::Or),
+                ident("c"), Token::Equal, placeholder(":name"), Token::Keyword(Keyword::Or),
+                ident("d"), Token::Equal, placeholder("@var"),
+            ]
+        );
+        // Slices and casts keep their colons
+        assert_eq!(tokens("x[lo:hi]"), vec![ident("x"), Token::LeftBracket, ident("lo"), Token::Colon, ident("hi"), Token::RightBracket]);
+        assert_eq!(tokens("x[:hi]"), vec![ident("x"), Token::LeftBracket, Token::Colon, ident("hi"), Token::RightBracket]);
+        assert_eq!(tokens("x::int"), vec![ident("x"), Token::DoubleColon, Token::Keyword(Keyword::Int)]);
+    }
+
+    #[test]
+    fn misspelled_keywords_get_suggestions() 
+    {
+        let statements = [Keyword::Select, Keyword::Create, Keyword::Insert];
+        assert_eq!(suggest_keyword("SELCT", &statements), Some(Keyword::Select));
+        assert_eq!(suggest_keyword("selet", &statements), Some(Keyword::Select));
+        assert_eq!(suggest_keyword("INSRET", &statements), Some(Keyword::Insert));
+        assert_eq!(suggest_keyword("FORM", &[Keyword::From]), Some(Keyword::From));
+        assert_eq!(suggest_keyword("FRM", &[Keyword::From]), Some(Keyword::From));
+        // Too far from anything, or already correct
+        assert_eq!(suggest_keyword("UPDATE", &statements), None);
+        assert_eq!(suggest_keyword("FM", &[Keyword::From]), None);
+        assert_eq!(suggest_keyword("from", &[Keyword::From]), None);
+    }
+
+    #[test]
+    fn keywords_are_words() 
+    {
+        for (spelling, keyword, _) in KEYWORDS 
+        {
+            assert!(spelling.chars().all(|c| c.is_ascii_alphabetic()), "{}", spelling);
+            assert_eq!(tokens(spelling), vec![Token::Keyword(keyword.clone())]);
+            assert_eq!(keyword.as_str(), *spelling);
+        }
+        // Punctuation is never a keyword
+        assert_eq!(tokens("()"), vec![Token::LeftParentheses, Token::RightParentheses]);
+    }
+
+    #[test]
+    fn tokens_display_as_written_in_sql() 
+    {
+        let shown: Vec<String> = tokens("SELECT a, `b c` FROM t WHERE x <> 'y' || 1.5 ->> $1")
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(shown, [
+            "SELECT", "identifier a", "','", "identifier `b c`", "FROM", "identifier t", "WHERE",
+            "identifier x", "'<>'", "string 'y'", "'||'", "number 1.5", "'->>'", "parameter $1",
+        ]);
+        assert_eq!(Token::Eof.to_string(), "end of input");
+        assert_eq!(Keyword::From.to_string(), "FROM");
+    }
+
+    #[test]
+    fn multi_character_operators_are_single_tokens() 
+    {
+        let cases = [
+            ("<=", Token::LessThanOrEqual),
+            (">=", Token::GreaterThanOrEqual),
+            ("<>", Token::NotEqual),
+            ("!=", Token::NotEqual),
+            ("||", Token::Concat),
+            ("::", Token::DoubleColon),
+            ("->", Token::Arrow),
+            ("->>", Token::LongArrow),
+            ("#>", Token::HashArrow),
+            ("#>>", Token::HashLongArrow),
+            ("<<", Token::ShiftLeft),
+            (">>", Token::ShiftRight),
+            ("=>", Token::FatArrow),
+            ("~*", Token::TildeStar),
+            ("!~", Token::NotTilde),
+            ("!~*", Token::NotTildeStar),
+        ];
+        for (input, expected) in cases 
+        {
+            assert_eq!(tokens(input), vec![expected.clone()], "input: {}", input);
+            // The same operator between operands, with no spaces
+            assert_eq!(tokens(&format!("a{}b", input)), vec![ident("a"), expected, ident("b")], "input: a{}b", input);
+        }
+        // Longest match first, then whatever is left
+        assert_eq!(tokens("<<="), vec![Token::ShiftLeft, Token::Equal]);
+        assert_eq!(tokens(">>="), vec![Token::ShiftRight, Token::Equal]);
+        assert_eq!(tokens("|||"), vec![Token::Concat, Token::Invalid('|')]);
+        assert_eq!(tokens(":::"), vec![Token::DoubleColon, Token::Colon]);
+    }
+
+    #[test]
+    fn scientific_notation() 
+    {
+        assert_eq!(tokens("1e10"), vec![number("1e10")]);
+        assert_eq!(tokens("6.02E23"), vec![number("6.02E23")]);
+        assert_eq!(tokens("1.5e-3"), vec![number("1.5e-3")]);
+        assert_eq!(tokens("2E+5"), vec![number("2E+5")]);
+        assert_eq!(tokens(".5e2"), vec![number(".5e2")]);
+        assert_eq!(tokens("1e10 + 2"), vec![number("1e10"), Token::Plus, number("2")]);
+    }
+
+    #[test]
+    fn exponent_boundary_with_identifiers() 
+    {
+        assert_eq!(tokens("e10"), vec![ident("e10")]);
+        assert_eq!(tokens("1 e10"), vec![number("1"), ident("e10")]);
+        assert_eq!(tokens("1e"), vec![number("1"), ident("e")]);
+        assert_eq!(tokens("1ex"), vec![number("1"), ident("ex")]);
+        assert_eq!(tokens("1e-x"), vec![number("1"), ident("e"), Token::Minus, ident("x")]);
+        assert_eq!(tokens("1e+"), vec![number("1"), ident("e"), Token::Plus]);
+        assert_eq!(
+            tokens("SELECT e FROM t"),
+            vec![Token::Keyword(Keyword::Select), ident("e"), Token::Keyword(Keyword::From), ident("t")]
+        );
+    }
+
+    #[test]
+    fn regex_operators() 
+    {
+        assert_eq!(tokens("~ ~* !~ !~*"), vec![Token::Tilde, Token::TildeStar, Token::NotTilde, Token::NotTildeStar]);
+        assert_eq!(tokens("a!~b"), vec![ident("a"), Token::NotTilde, ident("b")]);
+    }
+
+    #[test]
+    fn json_operators() 
+    {
+        assert_eq!(tokens("-> ->> #> #>>"), vec![Token::Arrow, Token::LongArrow, Token::HashArrow, Token::HashLongArrow]);
+        assert_eq!(tokens("a->>'k'"), vec![ident("a"), Token::LongArrow, Token::String("k".to_string())]);
+        assert_eq!(tokens("a - > b"), vec![ident("a"), Token::Minus, Token::GreaterThan, ident("b")]);
+    }
+
+    #[test]
+    fn fat_arrow_names_an_argument() 
+    {
+        assert_eq!(tokens("a => 1"), vec![ident("a"), Token::FatArrow, number("1")]);
+        assert_eq!(tokens("a>=1"), vec![ident("a"), Token::GreaterThanOrEqual, number("1")]);
+    }
+
+    #[test]
+    fn escaped_strings_keep_their_raw_text() 
+    {
+        assert_eq!(tokens(r"E'it\'s\n' e'\\'"), vec![
+            Token::EscapedString { value: "it's\n".to_string(), raw: r"it\'s\n".to_string() },
+            Token::EscapedString { value: r"\".to_string(), raw: r"\\".to_string() },
+        ]);
+        assert_eq!(tokens("E 'x'"), vec![ident("E"), Token::String("x".to_string())]);
+    }
+
+    #[test]
+    fn line_comments_run_to_the_end_of_the_line() 
+    {
+        assert_eq!(tokens("a -- b; c\nd --"), vec![ident("a"), ident("d")]);
+        assert_eq!(tokens("a - -b"), vec![ident("a"), Token::Minus, Token::Minus, ident("b")]);
+    }
+
+    #[test]
+    fn block_comments_nest() 
+    {
+        assert_eq!(tokens("a /* b /* c */ d */ e"), vec![ident("a"), ident("e")]);
+        assert_eq!(tokens("a/**/b"), vec![ident("a"), ident("b")]);
+        // Only the nested comment is closed, so the outer one swallows the rest
+        assert_eq!(tokens("a /* b /* c */ d"), vec![ident("a"), Token::Invalid('/')]);
+    }
+
+    #[test]
+    fn backticks_quote_identifiers() 
+    {
+        assert_eq!(tokens("`a``b`"), vec![Token::QuotedIdentifier { value: "a`b".to_string(), quote: '`' }]);
+        assert_eq!(tokens("`open"), vec![Token::Invalid('`')]);
+    }
+
+    #[test]
+    fn brackets_quote_identifiers() 
+    {
+        assert_eq!(tokens("[a]]b]"), vec![Token::QuotedIdentifier { value: "a]b".to_string(), quote: '[' }]);
+        assert_eq!(tokens("[order]"), vec![Token::QuotedIdentifier { value: "order".to_string(), quote: '[' }]);
+    }
+
+    #[test]
+    fn quotes_are_escaped_by_doubling() 
+    {
+        assert_eq!(tokens("'it''s' ''''"), vec![Token::String("it's".to_string()), Token::String("'".to_string())]);
+        assert_eq!(tokens("'open''"), vec![Token::Invalid('\'')]);
+    }
+
+    #[test]
+    fn numbers_with_fractions_and_exponents() 
+    {
+        assert_eq!(tokens("3.14 .5 42. 1e3 1.5E-2"), vec![number("3.14"), number(".5"), number("42."), number("1e3"), number("1.5E-2")]);
+        assert_eq!(tokens("t.a"), vec![ident("t"), Token::Period, ident("a")]);
+        assert_eq!(tokens("1e"), vec![number("1"), ident("e")]);
     }
 }
@@ -0,0 +1,123 @@
+// What differs between SQL dialects at the lexical level: which words are keywords, which
+// characters quote identifiers, and which multi-character operators exist.
+use super::{lookup_keyword, Keyword};
+
+/// Lexical rules for one SQL dialect. Every method has a default that matches GenericDialect,
+/// so a dialect only overrides what it does differently.
+pub trait TokenizerDialect 
+{
+    /// The keyword `word` stands for; None makes it an identifier.
+    fn keyword(&self, word: &str) -> Option<Keyword> 
+    {
+        lookup_keyword(word)
+    }
+
+    /// The closing character of an identifier quoted with `open`, or None if `open` does not
+    /// quote identifiers. A '[' only opens a name where a subscript could not start.
+    fn identifier_quote(&self, open: char) -> Option<char> 
+    {
+        match open 
+        {
+            '`' => Some('`'),
+            '[' => Some(']'),
+            _ => None,
+        }
+    }
+
+    /// Whether a multi-character operator such as "::" or "->>" exists. When it does not,
+    /// its characters are lexed as shorter operators.
+    fn supports_operator(&self, _operator: &str) -> bool 
+    {
+        true
+    }
+}
+
+/// Accepts the union of what the parser understands: MySQL backticks, SQL Server brackets
+/// and every operator. Double quotes delimit strings.
+pub struct GenericDialect;
+
+impl TokenizerDialect for GenericDialect {}
+
+/// The SQL dialects the parser knows. Each one lexes only its own identifier quotes and
+/// operators, and the parser accepts only its own extensions (see ParserOptions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect 
+{
+    Ansi,
+    Postgres,
+    MySql,
+    Sqlite,
+    MsSql,
+}
+
+impl Dialect 
+{
+    /// The dialect called `name`, as given on the command line: ansi, postgres, mysql, sqlite or mssql.
+    pub fn from_name(name: &str) -> Option<Dialect> 
+    {
+        match name.to_lowercase().as_str() 
+        {
+            "ansi" => Some(Dialect::Ansi),
+            "postgres" | "postgresql" => Some(Dialect::Postgres),
+            "mysql" => Some(Dialect::MySql),
+            "sqlite" => Some(Dialect::Sqlite),
+            "mssql" | "sqlserver" => Some(Dialect::MsSql),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Dialect 
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result 
+    {
+        f.write_str(match self 
+        {
+            Dialect::Ansi => "ANSI SQL",
+            Dialect::Postgres => "PostgreSQL",
+            Dialect::MySql => "MySQL",
+            Dialect::Sqlite => "SQLite",
+            Dialect::MsSql => "SQL Server",
+        })
+    }
+}
+
+impl TokenizerDialect for Dialect 
+{
+    // ILIKE is a Postgres operator; elsewhere it is an ordinary name.
+    fn keyword(&self, word: &str) -> Option<Keyword> 
+    {
+        match lookup_keyword(word) 
+        {
+            Some(Keyword::ILike) if *self != Dialect::Postgres => None,
+            keyword => keyword,
+        }
+    }
+
+    // Double quotes delimit names everywhere but MySQL, where they delimit strings.
+    fn identifier_quote(&self, open: char) -> Option<char> 
+    {
+        match (self, open) 
+        {
+            (Dialect::MySql, '"') => None,
+            (_, '"') => Some('"'),
+            (Dialect::MySql | Dialect::Sqlite, '`') => Some('`'),
+            (Dialect::MsSql | Dialect::Sqlite, '[') => Some(']'),
+            _ => None,
+        }
+    }
+
+    fn supports_operator(&self, operator: &str) -> bool 
+    {
+        let extra: &[&str] = match self 
+        {
+            Dialect::Ansi => &["||", "=>"],
+            Dialect::Postgres => return true,
+            Dialect::MySql => &["->", "->>", "<<", ">>"],
+            Dialect::Sqlite => &["||", "->", "->>", "<<", ">>"],
+            Dialect::MsSql => &[],
+        };
+        // Comparisons are common to all of them
+        matches!(operator, "<=" | ">=" | "<>" | "!=") || extra.contains(&operator)
+    }
+}